@@ -3,13 +3,13 @@ use serde::{Serialize, Deserialize};
 use serde_json::{Map as JSMap, Value as JSValue, Number as JSNumber};
 use std::collections::HashMap;
 use std::fs::OpenOptions;
-use std::io::{Seek, SeekFrom, Write, BufWriter};
+use std::io::{Cursor, Seek, SeekFrom, Read, Write, BufWriter};
 use std::path::PathBuf;
 use crate::traits::ReadFrom;
 use super::indexer::Indexer;
 use super::indexer::header::InputType;
 use super::indexer::value::{Value as IndexValue, MatchFlag};
-use super::table::record::Record;
+use super::table::record::{Record, Value};
 use super::source::Source;
 
 /// MatchFlag masked value.
@@ -137,19 +137,77 @@ impl<T: ExporterWriter> ExporterWriter for &'_ mut T {
     }
 }
 
+/// Filtered CSV field value.
+struct CSVField {
+    /// Field value.
+    value: String,
+
+    /// Whether the field must be quoted even when empty.
+    quoted: bool
+}
+
+impl From<String> for CSVField {
+    fn from(value: String) -> Self {
+        Self{value, quoted: false}
+    }
+}
+
 struct ExporterCSVWriter<W: Write> {
-    pub writer: csv::Writer<W>
+    pub writer: W,
+    pub float_precision: Option<usize>,
+    pub null_token: Option<String>
 }
 
 impl<W: Write> ExporterCSVWriter<W> {
-    /// Filter a single field into a String vector.
+    /// Converts a record value into a CSV field. When a null token is
+    /// configured, default and null values are rendered as the null
+    /// token and empty strings are quoted so they can be told apart.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Record value to convert.
+    fn record_field(&self, value: &Value) -> CSVField {
+        match (&self.null_token, value) {
+            (Some(token), Value::Default | Value::Null) => token.to_string().into(),
+            (Some(_), Value::Str(s)) if s.is_empty() => CSVField{
+                value: String::new(),
+                quoted: true
+            },
+            _ => value.to_display_string(self.float_precision).into()
+        }
+    }
+
+    /// Write a CSV line quoting the fields that need it, empty fields are
+    /// only quoted when flagged to or when they are the only line field.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `fields` - Fields to write.
+    fn write_line(&mut self, fields: &[CSVField]) -> Result<()> {
+        let mut line = Vec::with_capacity(fields.len());
+        for field in fields {
+            let must_quote = field.quoted
+                || (fields.len() == 1 && field.value.is_empty())
+                || field.value.contains([',', '"', '\r', '\n']);
+            if must_quote {
+                line.push(format!("\"{}\"", field.value.replace('"', "\"\"")));
+            } else {
+                line.push(field.value.to_string());
+            }
+        }
+        self.writer.write_all(line.join(",").as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Filter a single field into a CSV field vector.
     /// 
     /// # Arguments
     /// 
     /// * `field` - Export field.
-    /// * `data` - String vector to store values into.
+    /// * `data` - CSV field vector to store values into.
     /// * `source` - Source data to filter.
-    fn filter_single(field: &ExportField, data: &mut Vec<String>, source: &ExportData) {
+    fn filter_single(&self, field: &ExportField, data: &mut Vec<CSVField>, source: &ExportData) {
         let value =  match field {
             ExportField::SpentTime{label: _, decimal} => Self::calc_spent_time(*decimal, source).to_string(),
             ExportField::MatchFlag{label: _, mask} => Self::calc_match_flag(mask, source),
@@ -161,7 +219,10 @@ impl<W: Write> ExporterCSVWriter<W> {
                 None => "".to_string()
             },
             ExportField::Record{label: _, name} => match source.record.get(name) {
-                Some(v) => v.to_string(),
+                Some(v) => {
+                    data.push(self.record_field(v));
+                    return
+                },
                 None => "".to_string()
             },
             ExportField::AllInput{overrides} => {
@@ -169,7 +230,7 @@ impl<W: Write> ExporterCSVWriter<W> {
                     // apply field override
                     if let Some(map) = overrides {
                         if let Some(new_field) = map.get(s) {
-                            Self::filter_single(new_field, data, source);
+                            self.filter_single(new_field, data, source);
                             continue
                         }
                     }
@@ -182,7 +243,7 @@ impl<W: Write> ExporterCSVWriter<W> {
                         },
                         None => "".to_string()
                     };
-                    data.push(val);
+                    data.push(val.into());
                 }
                 return
             },
@@ -191,31 +252,31 @@ impl<W: Write> ExporterCSVWriter<W> {
                     // apply field override
                     if let Some(map) = overrides {
                         if let Some(new_field) = map.get(s) {
-                            Self::filter_single(new_field, data, source);
+                            self.filter_single(new_field, data, source);
                             continue
                         }
                     }
 
                     // add field value
-                    data.push(v.to_string());
+                    data.push(self.record_field(v));
                 }
                 return
             }
             ExportField::None{label: _} => "".to_string(),
         };
-        data.push(value);
+        data.push(value.into());
     }
 
-    /// Filter all fields value into a String vector.
+    /// Filter all fields value into a CSV field vector.
     /// 
     /// # Arguments
     /// 
     /// * `fields` - Export fields.
     /// * `source` - Source data to filter.
-    fn filter_data(fields: &[ExportField], source: ExportData) -> Vec<String> {
+    fn filter_data(&self, fields: &[ExportField], source: ExportData) -> Vec<CSVField> {
         let mut data = Vec::new();
         for field in fields {
-            Self::filter_single(field, &mut data, &source)
+            self.filter_single(field, &mut data, &source)
         }
         data
     }
@@ -227,26 +288,46 @@ impl<W: Write> ExporterWriter for ExporterCSVWriter<W> {
     }
 
     fn write_headers(&mut self, headers: &[String]) -> Result<()> {
-        self.writer.write_record(headers)?;
-        Ok(())
+        let fields: Vec<CSVField> = headers.iter().map(|h| h.to_string().into()).collect();
+        self.write_line(&fields)
     }
 
     fn write_data(&mut self, fields: &[ExportField], source: ExportData, _: bool) -> Result<()> {
-        let data = Self::filter_data(fields, source);
-        self.writer.write_record(&data)?;
-        Ok(())
+        let data = self.filter_data(fields, source);
+        self.write_line(&data)
     }
 
     fn write_end(&mut self) -> Result<()> {
+        self.writer.flush()?;
         Ok(())
     }
 }
 
 struct ExporterJSONWriter<W: Write> {
-    pub writer: W
+    pub writer: W,
+    pub float_precision: Option<usize>
 }
 
 impl<W: Write> ExporterJSONWriter<W> {
+    /// Converts a record value into a Json value rounding floats when
+    /// a float precision is set.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Record value.
+    fn record_value(&self, value: &Value) -> JSValue {
+        match value {
+            Value::F32(_) | Value::F64(_) if self.float_precision.is_some() => {
+                let rounded = value.to_display_string(self.float_precision);
+                match rounded.parse::<f64>().ok().and_then(JSNumber::from_f64) {
+                    Some(v) => JSValue::Number(v),
+                    None => JSValue::Null
+                }
+            },
+            v => v.into()
+        }
+    }
+
     /// Filter a single field into a Json map.
     /// 
     /// # Arguments
@@ -254,7 +335,7 @@ impl<W: Write> ExporterJSONWriter<W> {
     /// * `field` - Export field.
     /// * `data` - String vector to store values into.
     /// * `source` - Source data to filter.
-    fn filter_single(field: &ExportField, data: &mut JSMap<String, JSValue>, source: &ExportData) {
+    fn filter_single(&self, field: &ExportField, data: &mut JSMap<String, JSValue>, source: &ExportData) {
         match field {
             ExportField::SpentTime{label, decimal} => {
                 let value = JSValue::Number(JSNumber::from_f64(
@@ -287,7 +368,7 @@ impl<W: Write> ExporterJSONWriter<W> {
             },
             ExportField::Record{label, name} => {
                 let value = match source.record.get(name) {
-                    Some(v) => v.into(),
+                    Some(v) => self.record_value(v),
                     None => JSValue::Null
                 };
                 let key = match label {
@@ -301,7 +382,7 @@ impl<W: Write> ExporterJSONWriter<W> {
                     // apply field override
                     if let Some(map) = overrides {
                         if let Some(new_field) = map.get(s) {
-                            Self::filter_single(new_field, data, source);
+                            self.filter_single(new_field, data, source);
                             continue
                         }
                     }
@@ -315,13 +396,13 @@ impl<W: Write> ExporterJSONWriter<W> {
                     // apply field override
                     if let Some(map) = overrides {
                         if let Some(new_field) = map.get(s) {
-                            Self::filter_single(new_field, data, source);
+                            self.filter_single(new_field, data, source);
                             continue
                         }
                     }
 
                     // add field value
-                    data[s] = v.into();
+                    data[s] = self.record_value(v);
                 }
             },
             ExportField::None{label} => data[label] = "".into(),
@@ -334,10 +415,10 @@ impl<W: Write> ExporterJSONWriter<W> {
     /// 
     /// * `fields` - Export fields.
     /// * `source` - Source data to filter.
-    fn filter_data(fields: &[ExportField], source: ExportData) -> JSMap<String, JSValue> {
+    fn filter_data(&self, fields: &[ExportField], source: ExportData) -> JSMap<String, JSValue> {
         let mut data = JSMap::new();
         for field in fields {
-            Self::filter_single(field, &mut data, &source)
+            self.filter_single(field, &mut data, &source)
         }
         data
     }
@@ -355,7 +436,7 @@ impl<W: Write> ExporterWriter for ExporterJSONWriter<W> {
     }
 
     fn write_data(&mut self, fields: &[ExportField], source: ExportData, is_first: bool) -> Result<()> {
-        let data = Self::filter_data(fields, source);
+        let data = self.filter_data(fields, source);
         if !is_first {
             self.writer.write_all(&[b','])?;
         }
@@ -375,7 +456,16 @@ pub struct Exporter<'s> {
     pub source: &'s Source,
 
     /// Output file type
-    pub file_type: ExportFileType
+    pub file_type: ExportFileType,
+
+    /// Decimal count used to export record float values, all
+    /// decimals are exported when `None`.
+    pub float_precision: Option<usize>,
+
+    /// Token used to export default and null record values on CSV
+    /// files. Empty record strings are exported as a quoted empty field
+    /// (`""`) when set so they can be told apart from default values.
+    pub null_token: Option<String>
 }
 
 impl<'s> Exporter<'s> {
@@ -388,7 +478,9 @@ impl<'s> Exporter<'s> {
     pub fn new(source: &'s Source, file_type: ExportFileType) -> Self {
         Self{
             source,
-            file_type
+            file_type,
+            float_precision: None,
+            null_token: None
         }
     }
 
@@ -464,8 +556,14 @@ impl<'s> Exporter<'s> {
         let pos = self.source.table.calc_record_pos(0);
         table_rdr.seek(SeekFrom::Start(pos))?;
 
-        // create input CSV reader
-        let input_rdr = self.source.index.new_input_reader()?;
+        // create input CSV reader, headerless inputs are prefixed with
+        // the input fields as headers so records are mapped by position
+        let input_rdr: Box<dyn Read> = if self.source.index.has_header {
+            Box::new(self.source.index.new_input_reader()?)
+        } else {
+            let header_line = self.source.index.csv_header_line()?;
+            Box::new(Cursor::new(header_line).chain(self.source.index.new_input_reader()?))
+        };
         let mut csv_reader = csv::ReaderBuilder::new()
             .has_headers(true)
             .flexible(true)
@@ -531,7 +629,9 @@ impl<'s> Exporter<'s> {
         match self.file_type {
             ExportFileType::CSV => {
                 let mut exporter_writer = ExporterCSVWriter{
-                    writer: csv::Writer::from_writer(writer)
+                    writer,
+                    float_precision: self.float_precision,
+                    null_token: self.null_token.clone()
                 };
                 match self.source.index.header.input_type {
                     InputType::CSV => self.export_from_csv(
@@ -545,7 +645,8 @@ impl<'s> Exporter<'s> {
             },
             ExportFileType::JSON => {
                 let mut exporter_writer = ExporterJSONWriter{
-                    writer
+                    writer,
+                    float_precision: self.float_precision
                 };
                 match self.source.index.header.input_type {
                     InputType::CSV => self.export_from_csv(
@@ -576,9 +677,65 @@ impl<'s> Exporter<'s> {
     }
 }
 
-#[cfg(tests)]
+#[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Export a single record using the CSV writer and returns the output.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `record` - Record to export.
+    /// * `null_token` - CSV writer null token.
+    fn export_csv_record(record: Record, null_token: Option<&str>) -> Result<String> {
+        let mut buf = Vec::new();
+        {
+            let mut writer = ExporterCSVWriter{
+                writer: &mut buf,
+                float_precision: None,
+                null_token: null_token.map(|v| v.to_string())
+            };
+            let headers: Vec<String> = record.iter().map(|(k, _)| k.to_string()).collect();
+            let source = ExportData{
+                input_headers: Vec::new(),
+                input: JSMap::new(),
+                index: IndexValue::new(),
+                record
+            };
+            writer.write_headers(&headers)?;
+            writer.write_data(&[ExportField::AllRecord{overrides: None}], source, true)?;
+            writer.write_end()?;
+        }
+        Ok(String::from_utf8(buf)?)
+    }
+
+    #[test]
+    fn csv_null_token() -> Result<()> {
+        let mut record = Record::new();
+        record.add("foo", Value::Default)?;
+        record.add("bar", Value::Str("".to_string()))?;
+        record.add("baz", Value::Str("a,\"b\"".to_string()))?;
+        let output = export_csv_record(record, Some("NULL"))?;
+        assert_eq!("foo,bar,baz\nNULL,\"\",\"a,\"\"b\"\"\"\n", output);
+
+        // test round-trip
+        let mut reader = csv::Reader::from_reader(output.as_bytes());
+        let rows = reader.records().collect::<std::result::Result<Vec<_>, _>>()?;
+        assert_eq!(1, rows.len());
+        assert_eq!(vec!["NULL", "", "a,\"b\""], rows[0].iter().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn csv_without_null_token() -> Result<()> {
+        let mut record = Record::new();
+        record.add("foo", Value::Default)?;
+        record.add("bar", Value::Str("".to_string()))?;
+        let output = export_csv_record(record, None)?;
+        assert_eq!("foo,bar\n,\n", output);
+        Ok(())
+    }
+
 //     /// Return the fake output content as bytes.
 //     pub fn fake_output_bytes() -> Vec<u8> {
 //         let buf = build_empty_extra_fields().to_vec();
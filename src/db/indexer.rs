@@ -3,13 +3,16 @@ pub mod value;
 
 use anyhow::{bail, Result};
 use regex::Regex;
+use serde::Serialize;
 use serde_json::{Map as JSMap, Value as JSValue};
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::fs::{File, OpenOptions};
 use std::io::{Seek, SeekFrom, Read, Write, BufReader, BufWriter};
 use std::path::PathBuf;
 use crate::error::ParseError;
-use crate::{file_size, generate_hash};
+use crate::{file_size, generate_hash_with, generate_legacy_hash, HASH_SIZE};
 use crate::error::IndexError;
 use crate::traits::{ByteSized, LoadFrom, ReadFrom, WriteTo};
 use header::{Header, InputType};
@@ -21,9 +24,18 @@ pub const VERSION: u32 = 2;
 /// Index file extension.
 pub const FILE_EXTENSION: &str = "fmindex";
 
+/// Index header journal file suffix appended to the index file path.
+pub const HEADER_JOURNAL_SUFFIX: &str = ".journal";
+
 /// Default indexing batch size before updating headers.
 const DEFAULT_BATCH_SIZE: u64 = 100;
 
+/// Default indexed value count between header checkpoints.
+const DEFAULT_CHECKPOINT_SIZE: u64 = 100_000;
+
+/// Input read buffer size.
+const BUF_SIZE: usize = 4096;
+
 /// index healthcheck status.
 #[derive(Debug, PartialEq)]
 pub enum Status {
@@ -48,6 +60,58 @@ impl Display for Status{
     }
 }
 
+/// Input file line ending counts.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct LineEndingReport {
+    /// `\n` line ending count.
+    pub lf: u64,
+
+    /// `\r\n` line ending count.
+    pub crlf: u64,
+
+    /// Lone `\r` line ending count.
+    pub cr: u64
+}
+
+impl LineEndingReport {
+    /// Returns `true` when the input uses more than one line ending style.
+    pub fn is_mixed(&self) -> bool {
+        [self.lf, self.crlf, self.cr].iter().filter(|v| **v > 0).count() > 1
+    }
+
+    /// Returns a warning message when the line endings are mixed.
+    pub fn warning(&self) -> Option<String> {
+        if !self.is_mixed() {
+            return None;
+        }
+        Some(format!(
+            "mixed line endings found: {} \\n, {} \\r\\n and {} \\r",
+            self.lf,
+            self.crlf,
+            self.cr
+        ))
+    }
+}
+
+/// Index entry with its position and decision data.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct IndexEntry {
+    /// Record index.
+    pub index: u64,
+
+    /// Input record start byte position.
+    pub input_start_pos: u64,
+
+    /// Input record end byte position.
+    pub input_end_pos: u64,
+
+    /// Match flag.
+    pub match_flag: MatchFlag,
+
+    /// Spent time to resolve.
+    pub spent_time: u64
+}
+
 /// Indexer engine.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Indexer {
@@ -60,11 +124,43 @@ pub struct Indexer {
     /// Index header data.
     pub header: Header,
 
-    /// Indexing batch size before updating the index header.
+    /// Indexing batch size before updating the index header, `0` to only
+    /// update it at checkpoints. Batch updates aren't synced to disk nor
+    /// journaled, see [Self::checkpoint_size].
     pub batch_size: u64,
 
+    /// Indexed value count between index header checkpoints, where the
+    /// header is committed by [Self::commit_header_into]. The header is
+    /// also committed at the end of the indexing run, `0` to commit it
+    /// only at the end.
+    pub checkpoint_size: u64,
+
     /// Input field name list.
     pub input_fields: Vec<String>,
+
+    /// `true` when the CSV input first row contains the field names. When
+    /// `false` the input fields must be provided and are mapped by position.
+    pub has_header: bool,
+
+    /// Skips rows identical to the input fields while indexing, useful on
+    /// concatenated CSV files that repeat the header row. The skipped row
+    /// count is reported on `skipped_header_rows`.
+    pub skip_duplicate_headers: bool,
+
+    /// Duplicated header rows skipped by the last indexing run, callers
+    /// should check it after indexing to report the skipped rows.
+    pub skipped_header_rows: u64,
+
+    /// Repairs an interrupted header commit and drops the uncommitted
+    /// values when opening the index, otherwise the index is reported as
+    /// corrupted.
+    pub recover_on_open: bool,
+
+    /// Indexes only the records appended to an already indexed input file
+    /// when the previously indexed content didn't change, otherwise the
+    /// input is indexed from scratch. When `false` a changed input file is
+    /// reported as the wrong input file.
+    pub resume_on_append: bool
 }
 
 impl Indexer {
@@ -97,10 +193,23 @@ impl Indexer {
             index_path,
             header,
             batch_size: DEFAULT_BATCH_SIZE,
-            input_fields: Vec::new()
+            checkpoint_size: DEFAULT_CHECKPOINT_SIZE,
+            input_fields: Vec::new(),
+            has_header: true,
+            skip_duplicate_headers: false,
+            skipped_header_rows: 0,
+            recover_on_open: true,
+            resume_on_append: false
         }
     }
 
+    /// Returns the index header journal file path.
+    pub fn header_journal_path(&self) -> PathBuf {
+        let mut path = self.index_path.clone().into_os_string();
+        path.push(HEADER_JOURNAL_SUFFIX);
+        path.into()
+    }
+
     /// Returns an input file buffered reader.
     pub fn new_input_reader(&self) -> Result<BufReader<File>> {
         let file = File::open(&self.input_path)?;
@@ -183,6 +292,24 @@ impl Indexer {
         self.seek_value_from(&mut reader, index, false)
     }
 
+    /// Returns an iterator over every indexed value as an index entry.
+    pub fn index_entries(&self) -> Result<impl Iterator<Item = Result<IndexEntry>>> {
+        // the range is empty when there are no values to seek
+        let mut reader = self.new_index_reader()?;
+        self.seek_value_pos_from(&mut reader, 0, false)?;
+        let iter = (0..self.header.indexed_count).map(move |index| {
+            let value = Value::read_from(&mut reader)?;
+            Ok(IndexEntry{
+                index,
+                input_start_pos: value.input_start_pos,
+                input_end_pos: value.input_end_pos,
+                match_flag: value.data.match_flag,
+                spent_time: value.data.spent_time
+            })
+        });
+        Ok(iter)
+    }
+
     /// Reads a batch of index values from a reader at it's current position
     /// and return a the value list whenever a read value is returned.
     /// 
@@ -228,6 +355,21 @@ impl Indexer {
         Ok(list)
     }
 
+    /// Limits a batch size to the indexed values left from an index, so
+    /// the checksum trailer is never read as a value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    /// * `size` - Batch size, 0 for every value left.
+    fn limit_batch_size(&self, index: u64, size: u64) -> u64 {
+        let left = self.header.indexed_count.saturating_sub(index);
+        if size < 1 || size > left {
+            return left;
+        }
+        size
+    }
+
     /// Reads a batch of index values from the index file.
     /// and return a the value list whenever a read value is returned.
     /// 
@@ -247,7 +389,7 @@ impl Indexer {
         if !self.seek_value_pos_from(&mut reader, index, false)? {
             return Ok(Vec::new())
         }
-        Ok(self.scan_from(&mut reader, size, f)?)
+        Ok(self.scan_from(&mut reader, self.limit_batch_size(index, size), f)?)
     }
 
     /// Process a batch of index values from a reader at it's current position.
@@ -327,18 +469,13 @@ impl Indexer {
         if self.seek_value_pos_from(&mut reader, index, false)? {
             let pos = reader.stream_position()?;
             writer.seek(SeekFrom::Start(pos))?;
-            self.process_from(&mut reader, &mut writer, size, f)?;
+            self.process_from(&mut reader, &mut writer, self.limit_batch_size(index, size), f)?;
         }
         Ok(())
     }
 
-    /// Parse the input record from an index value as CSV.
-    /// 
-    /// # Arguments
-    /// 
-    /// * `value` - Index value
-    fn parse_csv_input(&self, value: &Value) -> Result<JSMap<String, JSValue>> {
-        // create CSV headers
+    /// Builds a CSV header line from the input fields.
+    pub fn csv_header_line(&self) -> Result<Vec<u8>> {
         let mut buf = Vec::new();
         let limit = self.input_fields.len();
         if limit < 1 {
@@ -352,6 +489,17 @@ impl Indexer {
             }
         }
         buf.push(b'\n');
+        Ok(buf)
+    }
+
+    /// Parse the input record from an index value as CSV.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Index value
+    fn parse_csv_input(&self, value: &Value) -> Result<JSMap<String, JSValue>> {
+        // create CSV headers
+        let mut buf = self.csv_header_line()?;
 
         // read input record
         let mut reader = self.new_input_reader()?;
@@ -406,6 +554,21 @@ impl Indexer {
         }
     }
 
+    /// Reads and parses an input record by its index. Every index value
+    /// stores the record byte offsets within the input file, so the input
+    /// is read directly at the record position instead of being scanned.
+    /// Returns `None` when the index is out of bounds.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    pub fn input(&self, index: u64) -> Result<Option<JSMap<String, JSValue>>> {
+        match self.value(index)? {
+            Some(value) => Ok(Some(self.parse_input(&value)?)),
+            None => Ok(None)
+        }
+    }
+
     /// Updates or append an index value into the index file.
     /// 
     /// # Arguments
@@ -423,6 +586,13 @@ impl Indexer {
 
     /// Updates or append an index value data into the index file.
     /// 
+    /// The index file is laid out as `<header><value 0>..<value N>` where
+    /// every value has a fixed size of `Value::BYTES` bytes as
+    /// `<input_start_pos:8><input_end_pos:8><spent_time:8><match_flag:1>`,
+    /// so saving a decision is an in-place write of the value data region
+    /// at `calc_value_pos(index) + Value::DATA_OFFSET` and never rewrites
+    /// the rest of the file.
+    /// 
     /// # Arguments
     /// 
     /// * `index` - Value index.
@@ -436,20 +606,101 @@ impl Indexer {
         Ok(())
     }
 
+    /// Resets a value decision back to unprocessed, clearing its match flag
+    /// and spent time in place so [Self::find_pending] picks it up again.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Value index.
+    pub fn clear_data(&self, index: u64) -> Result<()> {
+        if index >= self.header.indexed_count {
+            bail!("index {} is out of range, expected less than {}", index, self.header.indexed_count);
+        }
+        self.save_data(index, &Data::new())
+    }
+
+    /// Moves the index values after the input records were reordered or
+    /// compacted, every value at an old index is moved into its new index
+    /// and values missing from the mapping are dropped. The new indexes
+    /// must cover `0..remap.len()` exactly once.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `remap` - List of `(old_index, new_index)` produced by the rewrite.
+    pub fn reindex_after_remap(&mut self, remap: &[(u64, u64)]) -> Result<()> {
+        if !self.header.indexed {
+            bail!(IndexError::Unavailable(Status::Incomplete));
+        }
+
+        // read the current values
+        let mut reader = self.new_index_reader()?;
+        reader.seek(SeekFrom::Start(Self::calc_value_pos(0)))?;
+        let mut old_values = Vec::with_capacity(self.header.indexed_count as usize);
+        for _ in 0..self.header.indexed_count {
+            old_values.push(Some(Value::read_from(&mut reader)?));
+        }
+
+        // move the values into their new index
+        let count = remap.len() as u64;
+        let mut values = vec![None; remap.len()];
+        for (old, new) in remap.iter() {
+            if *new >= count {
+                bail!("new index {} is out of range, expected less than {}", new, count);
+            }
+            let value = match old_values.get_mut(*old as usize) {
+                Some(v) => match v.take() {
+                    Some(v) => v,
+                    None => bail!("old index {} is mapped more than once", old)
+                },
+                None => bail!("old index {} is out of range, expected less than {}", old, self.header.indexed_count)
+            };
+            if values[*new as usize].is_some() {
+                bail!("new index {} is mapped more than once", new);
+            }
+            values[*new as usize] = Some(value);
+        }
+
+        // write the values and drop the leftovers
+        let mut writer = self.new_index_writer(false)?;
+        writer.seek(SeekFrom::Start(Self::calc_value_pos(0)))?;
+        for value in values.iter().flatten() {
+            value.write_to(&mut writer)?;
+        }
+        writer.flush()?;
+        writer.get_ref().set_len(Self::calc_value_pos(count))?;
+        self.header.indexed_count = count;
+        self.write_checksum_into(&mut writer)?;
+        self.commit_header_into(&mut writer)?;
+        Ok(())
+    }
+
     /// Return the index of the closest non-processed value.
     /// 
     /// # Arguments
     /// 
     /// * `from_index` - Index offset as search starting point.
     pub fn find_pending(&self, from_index: u64) -> Result<Option<u64>> {
+        Ok(self.list_pending(from_index, 1)?.pop())
+    }
+
+    /// Returns up to `limit` indexes of non-processed values starting at
+    /// `from_index`, useful to page through the pending values as a work
+    /// queue instead of searching them one at a time.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `from_index` - Index offset as search starting point.
+    /// * `limit` - Max amount of indexes to return.
+    pub fn list_pending(&self, from_index: u64, limit: usize) -> Result<Vec<u64>> {
         // validate indexed
         if !self.header.indexed {
             bail!(IndexError::Unavailable(Status::Incomplete));
         }
 
-        // validate index size
-        if self.header.indexed_count < 1 {
-            return Ok(None);
+        // validate index size and limit
+        let mut indexes = Vec::new();
+        if self.header.indexed_count < 1 || limit < 1 {
+            return Ok(indexes);
         }
 
         // seek start point by using the provided offset
@@ -458,27 +709,182 @@ impl Indexer {
         let mut pos = Self::calc_value_pos(index);
         reader.seek(SeekFrom::Start(pos))?;
 
-        // search next unmatched record
+        // collect unmatched records until the limit is reached
         let mut buf = [0u8; Value::BYTES];
-        let limit = Self::calc_value_pos(self.header.indexed_count);
-        while pos < limit {
+        let end = Self::calc_value_pos(self.header.indexed_count);
+        while pos < end {
             reader.read_exact(&mut buf)?;
             if buf[Value::MATCH_FLAG_BYTE_INDEX] < 1u8 {
-                return Ok(Some(index));
+                indexes.push(index);
+                if indexes.len() >= limit {
+                    break;
+                }
             }
             index += 1;
             pos += Value::BYTES as u64;
         }
 
-        Ok(None)
+        Ok(indexes)
+    }
+
+    /// Returns the decision progress as `(decided_count, total_count)`,
+    /// where a value is decided when its match flag isn't `MatchFlag::None`.
+    pub fn progress(&self) -> Result<(u64, u64)> {
+        let mut decided = 0;
+        for entry in self.index_entries()? {
+            if entry?.match_flag != MatchFlag::None {
+                decided += 1;
+            }
+        }
+        Ok((decided, self.header.indexed_count))
+    }
+
+    /// Counts the indexed values by match flag, every flag is included even
+    /// when no value has it. Values not yet decided are counted under
+    /// `MatchFlag::None`.
+    pub fn flag_counts(&self) -> Result<HashMap<MatchFlag, u64>> {
+        let mut counts: HashMap<MatchFlag, u64> = MatchFlag::as_array().into_iter()
+            .map(|flag| (flag, 0))
+            .collect();
+        for entry in self.index_entries()? {
+            *counts.entry(entry?.match_flag).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Generates the input file hash by using the header hash algorithm.
+    pub fn input_hash(&self) -> Result<[u8; HASH_SIZE]> {
+        let mut reader = self.new_input_reader()?;
+        self.hash_input_from(&mut reader)
+    }
+
+    /// Generates the hash of the first `size` input file bytes by using the
+    /// header hash algorithm. Fails on index files without hash algorithm,
+    /// since the legacy hash only depends on the input size.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `size` - Input prefix size in bytes.
+    pub fn input_prefix_hash(&self, size: u64) -> Result<[u8; HASH_SIZE]> {
+        if self.header.hash_algorithm.is_none() {
+            bail!("can't hash the input prefix without a hash algorithm, the legacy hash only depends on the input size");
+        }
+        let mut reader = self.new_input_reader()?.take(size);
+        self.hash_input_from(&mut reader)
+    }
+
+    /// Generates the hash of a reader contents by using the header hash
+    /// algorithm.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    fn hash_input_from(&self, reader: &mut impl Read) -> Result<[u8; HASH_SIZE]> {
+        let hash = match self.header.hash_algorithm {
+            Some(algorithm) => Header::clone_hash(&generate_hash_with(reader, algorithm)?)?,
+            None => generate_legacy_hash(reader)?
+        };
+        Ok(hash)
+    }
+
+    /// Returns the input position right after the last indexed record line
+    /// ending when the input file still starts with the indexed content, so
+    /// the records appended after it can be indexed from there. Returns
+    /// `None` when the indexed content changed, the last indexed record
+    /// had no line ending or the index file has no hash algorithm, since
+    /// the legacy hash can't tell whether the indexed content changed.
+    pub fn appended_input_pos(&self) -> Result<Option<u64>> {
+        if !self.header.indexed || self.header.indexed_count < 1 || self.header.hash_algorithm.is_none() {
+            return Ok(None);
+        }
+        let saved_hash = match self.header.hash {
+            Some(v) => v,
+            None => return Ok(None)
+        };
+        let mut reader = self.new_index_reader()?;
+        let value = match self.seek_value_from(&mut reader, self.header.indexed_count - 1, true)? {
+            Some(v) => v,
+            None => return Ok(None)
+        };
+
+        // the indexed content size includes the last record line ending
+        let mut input = self.new_input_reader()?;
+        input.seek(SeekFrom::Start(value.input_end_pos + 1))?;
+        let mut buf = Vec::with_capacity(2);
+        input.take(2).read_to_end(&mut buf)?;
+        let size = match buf[..] {
+            [b'\r', b'\n'] => value.input_end_pos + 3,
+            [b'\n', ..] | [b'\r', ..] => value.input_end_pos + 2,
+            _ => return Ok(None)
+        };
+
+        // validate the indexed content didn't change
+        if self.input_prefix_hash(size)? != saved_hash {
+            return Ok(None);
+        }
+        Ok(Some(size))
+    }
+
+    /// Calculates the index body checksum as the SHA3-256 hash of every
+    /// indexed value input positions. Decision data (spent time and match
+    /// flag) is updated in place after indexing so it isn't covered.
+    pub fn body_checksum(&self) -> Result<[u8; HASH_SIZE]> {
+        let mut reader = self.new_index_reader()?;
+        reader.seek(SeekFrom::Start(Self::calc_value_pos(0)))?;
+        let mut hasher = Sha3_256::new();
+        let mut buf = [0u8; Value::BYTES];
+        for _ in 0..self.header.indexed_count {
+            reader.read_exact(&mut buf)?;
+            hasher.update(&buf[..Value::DATA_OFFSET]);
+        }
+        Ok(hasher.finalize().into())
+    }
+
+    /// Writes the index body checksum trailer right after the last indexed
+    /// value, see [Self::body_checksum].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Index byte writer.
+    pub fn write_checksum_into(&self, writer: &mut (impl Write + Seek)) -> Result<()> {
+        writer.flush()?;
+        let checksum = self.body_checksum()?;
+        writer.seek(SeekFrom::Start(Self::calc_value_pos(self.header.indexed_count)))?;
+        writer.write_all(&checksum)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Checks the index body against the checksum trailer written at the
+    /// end of the indexing. Returns `false` when the trailer is missing or
+    /// doesn't match, which means the index file was truncated or corrupted
+    /// and should be rebuilt.
+    pub fn verify_integrity(&self) -> Result<bool> {
+        if !self.header.indexed {
+            bail!(IndexError::Unavailable(Status::Incomplete));
+        }
+        let checksum_pos = Self::calc_value_pos(self.header.indexed_count);
+        if file_size(&self.index_path)? != checksum_pos + HASH_SIZE as u64 {
+            return Ok(false);
+        }
+        let mut reader = self.new_index_reader()?;
+        reader.seek(SeekFrom::Start(checksum_pos))?;
+        let mut expected = [0u8; HASH_SIZE];
+        reader.read_exact(&mut expected)?;
+        Ok(expected == self.body_checksum()?)
     }
 
     /// Perform a healthckeck over the index file by reading
     /// the headers and checking the file size.
     pub fn healthcheck(&mut self) -> Result<Status> {
         // calculate the input hash
-        let mut reader = self.new_input_reader()?;
-        let hash = generate_hash(&mut reader)?;
+        let hash_algorithm = self.header.hash_algorithm;
+        let mut hash = self.input_hash()?;
+
+        // a pending header journal means the last header commit was interrupted
+        if self.header_journal_path().exists() {
+            return Ok(Status::Corrupted);
+        }
 
         // check whenever index file exists
         match self.new_index_reader() {
@@ -523,6 +929,11 @@ impl Indexer {
         // validate input hash match
         match self.header.hash {
             Some(saved_hash) => {
+                // rehash when the index file uses a different hash algorithm
+                if self.header.hash_algorithm != hash_algorithm {
+                    hash = self.input_hash()?;
+                }
+
                 // validate input file hash
                 if saved_hash != hash {
                     return Ok(Status::WrongInputFile);
@@ -539,7 +950,8 @@ impl Indexer {
         let real_size = file_size(&self.index_path)?;
         let expected_size = Self::calc_value_pos(self.header.indexed_count);
         if self.header.indexed {
-            if real_size != expected_size {
+            // index files written before the checksum trailer don't have it
+            if real_size != expected_size && real_size != expected_size + HASH_SIZE as u64 {
                 // sizes don't match, the file is corrupted
                 return Ok(Status::Corrupted);
             }
@@ -548,6 +960,10 @@ impl Indexer {
                 // sizes is smaller, the file is corrupted
                 return Ok(Status::Corrupted);
             }
+            if real_size > expected_size && !self.recover_on_open {
+                // values written after the last header commit
+                return Ok(Status::Corrupted);
+            }
             // index is incomplete
             return Ok(Status::Incomplete);
         }
@@ -556,6 +972,30 @@ impl Indexer {
         Ok(Status::Indexed)
     }
 
+    /// Checks that the input file still contains every indexed value by
+    /// comparing the last indexed value end position with the input file
+    /// size, errors with [ParseError::InputLengthMismatch] when the input
+    /// file is shorter than the index expects.
+    pub fn validate_input_length(&self) -> Result<()> {
+        if self.header.indexed_count < 1 {
+            return Ok(());
+        }
+        let mut reader = self.new_index_reader()?;
+        let value = match self.seek_value_from(&mut reader, self.header.indexed_count - 1, true)? {
+            Some(v) => v,
+            None => return Ok(())
+        };
+        let input_size = file_size(&self.input_path)?;
+        if value.input_end_pos >= input_size {
+            bail!(ParseError::InputLengthMismatch{
+                indexed_count: self.header.indexed_count,
+                indexed_size: value.input_end_pos + 1,
+                input_size
+            });
+        }
+        Ok(())
+    }
+
     /// Saves the index header and then jump back to the last writer stream position.
     /// 
     /// # Arguments
@@ -577,8 +1017,97 @@ impl Indexer {
         self.save_header_into(&mut writer)
     }
 
+    /// Commits the index header as the last step of a write. The values are
+    /// synced to disk first, then the header is written and synced into
+    /// the header journal and only then into the index file, so an
+    /// interrupted commit can be recovered by [Self::recover_header]. The
+    /// journal is removed once the index header is synced to disk.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Index file writer.
+    pub fn commit_header_into(&self, writer: &mut BufWriter<File>) -> Result<()> {
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        let journal_path = self.header_journal_path();
+        let mut journal = File::create(&journal_path)?;
+        self.header.write_to(&mut journal)?;
+        journal.sync_all()?;
+        self.save_header_into(writer)?;
+        writer.get_ref().sync_all()?;
+        std::fs::remove_file(&journal_path)?;
+        Ok(())
+    }
+
+    /// Restores the index header from the header journal left by an
+    /// interrupted commit and removes the journal. A partially written
+    /// journal is discarded since the index header wasn't touched yet.
+    /// Returns `true` when the index header was restored.
+    pub fn recover_header(&self) -> Result<bool> {
+        let journal_path = self.header_journal_path();
+        if !journal_path.exists() {
+            return Ok(false);
+        }
+        let mut journal = BufReader::new(File::open(&journal_path)?);
+        let restored = match Header::read_from(&mut journal) {
+            Ok(header) => {
+                let mut writer = self.new_index_writer(false)?;
+                header.write_to(&mut writer)?;
+                writer.flush()?;
+                writer.get_ref().sync_all()?;
+                true
+            },
+            Err(_) => false
+        };
+        std::fs::remove_file(&journal_path)?;
+        Ok(restored)
+    }
+
+    /// Counts the line endings by style on the input file, useful to
+    /// detect inputs mixing `\n` and `\r\n` line endings.
+    pub fn line_ending_report(&self) -> Result<LineEndingReport> {
+        let mut reader = self.new_input_reader()?;
+        let mut report = LineEndingReport::default();
+        let mut buf = [0u8; BUF_SIZE];
+        let mut after_cr = false;
+        loop {
+            let count = reader.read(&mut buf)?;
+            if count < 1 {
+                break;
+            }
+            for b in &buf[..count] {
+                match b {
+                    b'\n' => if after_cr {
+                        report.crlf += 1;
+                    } else {
+                        report.lf += 1;
+                    },
+                    b'\r' => if after_cr {
+                        report.cr += 1;
+                    },
+                    _ => if after_cr {
+                        report.cr += 1;
+                    }
+                }
+                after_cr = *b == b'\r';
+            }
+        }
+        if after_cr {
+            report.cr += 1;
+        }
+        Ok(report)
+    }
+
     /// Loads fields names from a CSV input file.
     fn load_input_csv_fields(&mut self) -> Result<()> {
+        // headerless inputs use the provided input fields
+        if !self.has_header {
+            if self.input_fields.is_empty() {
+                bail!(IndexError::NoInputFields)
+            }
+            return Ok(());
+        }
+
         let reader = self.new_input_reader()?;
         let mut csv_reader = csv::ReaderBuilder::new()
             .has_headers(true)
@@ -661,15 +1190,21 @@ impl Indexer {
     /// 
     /// * `input_rdr` - Input byte reader.
     /// * `index_wrt` - Index byte writer.
-    /// * `is_first` - `true` when the input reader is set at position 0.
-    fn index_csv(&mut self, input_rdr: impl Read, index_wrt: &mut (impl Seek + Write), is_first: bool) -> Result<()> {
-        // index records
-        let mut is_first = is_first;
+    /// * `start_pos` - Input byte position to start indexing from, `0` to index from the beginning.
+    fn index_csv(&mut self, input_rdr: impl Read + Seek, index_wrt: &mut BufWriter<File>, start_pos: u64) -> Result<()> {
+        // index records, the first row is only skipped on inputs with headers
+        let mut is_first = start_pos < 1 && self.has_header;
+        self.skipped_header_rows = 0;
         let mut input_rdr_nav = self.new_input_reader()?;
         let mut input_csv = csv::ReaderBuilder::new()
             .has_headers(false)
             .flexible(true)
             .from_reader(input_rdr);
+
+        // keep the CSV positions relative to the input start
+        let mut pos = csv::Position::new();
+        pos.set_byte(start_pos);
+        input_csv.seek_raw(SeekFrom::Start(start_pos), pos)?;
         let mut iter = input_csv.records();
         'records: loop {
             match iter.next() {
@@ -682,26 +1217,35 @@ impl Indexer {
                     }
 
                     // create index value
-                    let value = match item {
-                        Ok(v) => self.index_csv_record(&iter, v, &mut input_rdr_nav)?,
+                    let item = match item {
+                        Ok(v) => v,
                         Err(e) => bail!(e)
                     };
+                    if self.skip_duplicate_headers && item.iter().eq(self.input_fields.iter()) {
+                        self.skipped_header_rows += 1;
+                        continue 'records;
+                    }
+                    let value = self.index_csv_record(&iter, item, &mut input_rdr_nav)?;
 
                     // write index value for this record
                     value.write_to(index_wrt)?;
                     self.header.indexed_count += 1;
 
-                    // save headers every batch
-                    if self.header.indexed_count % self.batch_size < 1 {
+                    // commit headers on every checkpoint, save them every batch
+                    let count = self.header.indexed_count;
+                    if self.checkpoint_size > 0 && count % self.checkpoint_size < 1 {
+                        self.commit_header_into(index_wrt)?;
+                    } else if self.batch_size > 0 && count % self.batch_size < 1 {
                         self.save_header_into(index_wrt)?;
                     }
                 }
             }
         }
 
-        // write headers
+        // write the checksum trailer and commit headers
+        self.write_checksum_into(index_wrt)?;
         self.header.indexed = true;
-        self.save_header_into(index_wrt)?;
+        self.commit_header_into(index_wrt)?;
 
         Ok(())
     }
@@ -712,7 +1256,12 @@ impl Indexer {
         // create reader and writer buffers
         let mut input_rdr = self.new_input_reader()?;
         let mut index_wrt = self.new_index_writer(true)?;
-        let mut is_first = true;
+        let mut start_pos = 0;
+
+        // recover an interrupted header commit
+        if self.recover_on_open {
+            self.recover_header()?;
+        }
 
         // perform index healthcheck
         match self.healthcheck() {
@@ -722,24 +1271,55 @@ impl Indexer {
                     return Ok(())
                 },
                 Status::Incomplete => {
-                    // read last indexed record or create the index file
-                    let mut reader = self.new_index_reader()?;
-                    match self.seek_value_from(&mut reader, self.header.indexed_count, true)? {
-                        Some(value) => {
-                            // load last known indexed value position
-                            is_first = false;
-                            input_rdr.seek(SeekFrom::Start(value.input_end_pos + 1))?;
-                            let next_pos = Self::calc_value_pos(self.header.indexed_count);
-                            index_wrt.seek(SeekFrom::Start(next_pos))?;
-                        },
-                        None => {}
+                    // drop the values written after the last header commit
+                    let next_pos = Self::calc_value_pos(self.header.indexed_count);
+                    if file_size(&self.index_path)? > next_pos {
+                        index_wrt.get_ref().set_len(next_pos)?;
+                    }
+
+                    // resume right after the last committed value
+                    if self.header.indexed_count > 0 {
+                        let mut reader = self.new_index_reader()?;
+                        let last_index = self.header.indexed_count - 1;
+                        if let Some(value) = self.seek_value_from(&mut reader, last_index, true)? {
+                            start_pos = value.input_end_pos + 1;
+                        }
                     }
+                    index_wrt.seek(SeekFrom::Start(next_pos))?;
                 },
                 Status::New => {
                     // create index headers
                     self.header.write_to(&mut index_wrt)?;
                     index_wrt.flush()?;
-                }
+                },
+                Status::WrongInputFile => {
+                    if !self.resume_on_append {
+                        // report a truncated input with a clearer error
+                        self.validate_input_length()?;
+                        bail!(IndexError::Unavailable(Status::WrongInputFile))
+                    }
+
+                    match self.appended_input_pos()? {
+                        Some(pos) => {
+                            // drop the checksum trailer and index the appended records
+                            let next_pos = Self::calc_value_pos(self.header.indexed_count);
+                            index_wrt.get_ref().set_len(next_pos)?;
+                            index_wrt.seek(SeekFrom::Start(next_pos))?;
+                            start_pos = pos;
+                        },
+                        None => {
+                            // the indexed content changed, index it from scratch
+                            // along the default hash algorithm
+                            index_wrt.get_ref().set_len(0)?;
+                            index_wrt.seek(SeekFrom::Start(Self::calc_value_pos(0)))?;
+                            self.header.indexed_count = 0;
+                            self.header.hash_algorithm = Header::new().hash_algorithm;
+                        }
+                    }
+                    self.header.indexed = false;
+                    self.header.hash = Some(self.input_hash()?);
+                    self.commit_header_into(&mut index_wrt)?;
+                },
                 vu => bail!(IndexError::Unavailable(vu))
             },
             Err(e) => return Err(e)
@@ -748,7 +1328,7 @@ impl Indexer {
         // index input file
         self.load_input_fields()?;
         match self.header.input_type {
-            InputType::CSV => self.index_csv(&mut input_rdr, &mut index_wrt, is_first),
+            InputType::CSV => self.index_csv(&mut input_rdr, &mut index_wrt, start_pos),
             InputType::JSON => unimplemented!(),
             InputType::Unknown => bail!("not supported input file type")
         }
@@ -867,9 +1447,9 @@ pub mod test_helper {
 
     /// Returns the fake input hash value.
     pub fn fake_input_hash() -> [u8; HASH_SIZE] {
-        [ 47, 130, 231, 73, 14, 84, 144, 114, 198, 155, 94, 35, 15,
-          101, 71, 156, 48, 113, 13, 217, 129, 108, 130, 240, 24, 19,
-          159, 141, 205, 59, 71, 227]
+        [152, 68, 137, 13, 41, 202, 171, 174, 165, 71, 78, 203, 177,
+          91, 187, 124, 125, 95, 35, 253, 162, 218, 1, 66, 196, 54,
+          131, 112, 105, 95, 64, 40]
     }
 
     /// Create a fake input file.
@@ -973,6 +1553,22 @@ pub mod test_helper {
         Ok(values)
     }
 
+    /// Appends the body checksum trailer to an index file bytes, see
+    /// [Indexer::body_checksum].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `buf` - Index file bytes.
+    pub fn with_checksum_trailer(mut buf: Vec<u8>) -> Vec<u8> {
+        let mut hasher = Sha3_256::new();
+        for value in buf[Header::BYTES..].chunks(Value::BYTES) {
+            hasher.update(&value[..Value::DATA_OFFSET]);
+        }
+        let checksum: [u8; HASH_SIZE] = hasher.finalize().into();
+        buf.extend_from_slice(&checksum);
+        buf
+    }
+
     /// Execute a function with both a temp directory and a new Indexer.
     /// 
     /// # Arguments
@@ -1029,7 +1625,13 @@ mod tests {
             index_path: "my_index.fmidx".into(),
             header,
             batch_size: DEFAULT_BATCH_SIZE,
-            input_fields: Vec::new()
+            checkpoint_size: DEFAULT_CHECKPOINT_SIZE,
+            input_fields: Vec::new(),
+            has_header: true,
+            skip_duplicate_headers: false,
+            skipped_header_rows: 0,
+            recover_on_open: true,
+            resume_on_append: false
         };
         let indexer = Indexer::new("my_input.csv".into(), "my_index.fmidx".into(), InputType::JSON);
         assert_eq!(expected, indexer);
@@ -1963,40 +2565,131 @@ mod tests {
     }
 
     #[test]
-    fn save_data() {
-        with_tmpdir_and_indexer(&|_, indexer| {
-            // create index and check original value
-            let mut values = create_fake_index(&indexer.index_path, true)?;
-            let pos = Indexer::calc_value_pos(2);
-            let mut buf = [0u8; Value::BYTES];
-            let file = File::open(&indexer.index_path)?;
-            let mut reader = BufReader::new(file);
-            let mut old_bytes_before = vec!(0u8; pos as usize);
-            let mut old_bytes_after = [0u8; Value::BYTES];
-            reader.read_exact(&mut old_bytes_before)?;
-            reader.read_exact(&mut buf)?;
-            reader.read_exact(&mut old_bytes_after)?;
-            let expected = [
-                // start_pos
-                0, 0, 0, 0, 0, 0, 0, 82u8,
-                // end_pos
-                0, 0, 0, 0, 0, 0, 0, 106u8,
-                // spent_time
-                0, 0, 0, 0, 0, 0, 0, 0,
-                // match flag
-                0
-            ];
-            assert_eq!(expected, buf);
+    fn reindex_after_remap() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            let values = create_fake_index(&indexer.index_path, false)?;
+            indexer.load_header_from(&mut indexer.new_index_reader()?)?;
 
-            // save value and check value
-            let expected = [
-                // start_pos
-                0, 0, 0, 0, 0, 0, 0, 82u8,
-                // end_pos
-                0, 0, 0, 0, 0, 0, 0, 106u8,
-                // spent_time
-                0, 0, 0, 0, 0, 0, 0, 93u8,
-                // match flag
+            // compact the second value and swap the last two
+            indexer.reindex_after_remap(&[(0, 0), (2, 2), (3, 1)])?;
+
+            // test
+            assert_eq!(3, indexer.header.indexed_count);
+            assert_eq!(Indexer::calc_value_pos(3) + HASH_SIZE as u64, file_size(&indexer.index_path)?);
+            assert!(indexer.verify_integrity()?);
+            assert_eq!(Some(&values[0]), indexer.value(0)?.as_ref());
+            assert_eq!(Some(&values[3]), indexer.value(1)?.as_ref());
+            assert_eq!(Some(&values[2]), indexer.value(2)?.as_ref());
+            assert_eq!(None, indexer.value(3)?);
+            assert_eq!(MatchFlag::Skip, indexer.value(1)?.unwrap().data.match_flag);
+            assert_eq!(Some(2), indexer.find_pending(0)?);
+
+            // test the header was saved
+            let mut other = indexer.clone();
+            other.load_header_from(&mut other.new_index_reader()?)?;
+            assert_eq!(3, other.header.indexed_count);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn verify_integrity() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.index()?;
+            assert!(indexer.verify_integrity()?);
+
+            // the trailer isn't read as a value
+            assert_eq!(4, indexer.scan(0, 0, |v| Ok((Some(v), false)))?.len());
+
+            // decisions don't invalidate the checksum
+            let mut value = indexer.value(1)?.unwrap();
+            value.data.match_flag = MatchFlag::Yes;
+            indexer.save_data(1, &value.data)?;
+            assert!(indexer.verify_integrity()?);
+
+            // flip a value input position byte
+            let mut buf = std::fs::read(&indexer.index_path)?;
+            let pos = Indexer::calc_value_pos(2) as usize + 7;
+            buf[pos] ^= 0xff;
+            create_file_with_bytes(&indexer.index_path, &buf)?;
+            assert!(!indexer.verify_integrity()?);
+
+            // truncated checksum trailer
+            buf[pos] ^= 0xff;
+            create_file_with_bytes(&indexer.index_path, &buf[..buf.len() - 1])?;
+            assert!(!indexer.verify_integrity()?);
+
+            // index files without checksum trailer
+            create_file_with_bytes(&indexer.index_path, &buf[..buf.len() - HASH_SIZE])?;
+            assert!(!indexer.verify_integrity()?);
+            assert_eq!(Status::Indexed, indexer.healthcheck()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn reindex_after_remap_with_invalid_mapping() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_index(&indexer.index_path, false)?;
+            indexer.load_header_from(&mut indexer.new_index_reader()?)?;
+            let expected = std::fs::read(&indexer.index_path)?;
+            let cases: [(&[(u64, u64)], &str); 4] = [
+                (&[(0, 0), (1, 2)], "new index 2 is out of range, expected less than 2"),
+                (&[(0, 0), (4, 1)], "old index 4 is out of range, expected less than 4"),
+                (&[(1, 0), (1, 1)], "old index 1 is mapped more than once"),
+                (&[(0, 1), (1, 1)], "new index 1 is mapped more than once")
+            ];
+            for (remap, message) in cases {
+                match indexer.reindex_after_remap(remap) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(message, e.to_string())
+                }
+            }
+
+            // index file is untouched on error
+            assert_eq!(4, indexer.header.indexed_count);
+            assert_eq!(expected, std::fs::read(&indexer.index_path)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn save_data() {
+        with_tmpdir_and_indexer(&|_, indexer| {
+            // create index and check original value
+            let mut values = create_fake_index(&indexer.index_path, true)?;
+            let pos = Indexer::calc_value_pos(2);
+            let mut buf = [0u8; Value::BYTES];
+            let file = File::open(&indexer.index_path)?;
+            let mut reader = BufReader::new(file);
+            let mut old_bytes_before = vec!(0u8; pos as usize);
+            let mut old_bytes_after = [0u8; Value::BYTES];
+            reader.read_exact(&mut old_bytes_before)?;
+            reader.read_exact(&mut buf)?;
+            reader.read_exact(&mut old_bytes_after)?;
+            let expected = [
+                // start_pos
+                0, 0, 0, 0, 0, 0, 0, 82u8,
+                // end_pos
+                0, 0, 0, 0, 0, 0, 0, 106u8,
+                // spent_time
+                0, 0, 0, 0, 0, 0, 0, 0,
+                // match flag
+                0
+            ];
+            assert_eq!(expected, buf);
+
+            // save value and check value
+            let expected = [
+                // start_pos
+                0, 0, 0, 0, 0, 0, 0, 82u8,
+                // end_pos
+                0, 0, 0, 0, 0, 0, 0, 106u8,
+                // spent_time
+                0, 0, 0, 0, 0, 0, 0, 93u8,
+                // match flag
                 b'Y'
             ];
             values[2].data.match_flag = MatchFlag::Yes;
@@ -2018,6 +2711,60 @@ mod tests {
         });
     }
 
+    #[test]
+    fn flag_counts() {
+        with_tmpdir_and_indexer(&|_, indexer| {
+            let mut values = create_fake_index(&indexer.index_path, false)?;
+            indexer.header.indexed = true;
+            indexer.header.indexed_count = 4;
+            let mut expected = HashMap::new();
+            expected.insert(MatchFlag::Yes, 1);
+            expected.insert(MatchFlag::No, 1);
+            expected.insert(MatchFlag::Skip, 1);
+            expected.insert(MatchFlag::None, 1);
+            assert_eq!(expected, indexer.flag_counts()?);
+
+            // decide the pending value
+            values[2].data.match_flag = MatchFlag::Yes;
+            indexer.save_value(2, &values[2])?;
+            expected.insert(MatchFlag::Yes, 2);
+            expected.insert(MatchFlag::None, 0);
+            assert_eq!(expected, indexer.flag_counts()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn clear_data() {
+        with_tmpdir_and_indexer(&|_, indexer| {
+            let mut values = create_fake_index(&indexer.index_path, false)?;
+            indexer.header.indexed = true;
+            indexer.header.indexed_count = 4;
+
+            // decide the pending value and then undo it
+            values[2].data = Data{
+                match_flag: MatchFlag::Yes,
+                spent_time: 25
+            };
+            indexer.save_data(2, &values[2].data)?;
+            assert_eq!(None, indexer.find_pending(0)?);
+            indexer.clear_data(2)?;
+            assert_eq!(Some(2), indexer.find_pending(0)?);
+            values[2].data = Data::new();
+            assert_eq!(Some(values[2].clone()), indexer.value(2)?);
+
+            // other values are kept as is
+            assert_eq!(Some(values[1].clone()), indexer.value(1)?);
+            assert_eq!(Some(values[3].clone()), indexer.value(3)?);
+
+            match indexer.clear_data(4) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("index 4 is out of range, expected less than 4", e.to_string())
+            }
+            Ok(())
+        });
+    }
+
     #[test]
     fn find_pending() {
         with_tmpdir_and_indexer(&|_, indexer| {
@@ -2127,6 +2874,45 @@ mod tests {
         });
     }
 
+    #[test]
+    fn list_pending() {
+        with_tmpdir_and_indexer(&|_, indexer| {
+            // create index and flag some values
+            let mut values = create_fake_index(&indexer.index_path, true)?;
+            indexer.header.indexed = true;
+            indexer.header.indexed_count = 4;
+            values[1].data.match_flag = MatchFlag::Yes;
+            indexer.save_value(1, &values[1])?;
+            values[2].data.match_flag = MatchFlag::Skip;
+            indexer.save_value(2, &values[2])?;
+
+            // test pages
+            assert_eq!(vec![0u64, 3], indexer.list_pending(0, 10)?);
+            assert_eq!(vec![0u64], indexer.list_pending(0, 1)?);
+            assert_eq!(vec![3u64], indexer.list_pending(1, 10)?);
+            assert!(indexer.list_pending(0, 0)?.is_empty());
+            assert!(indexer.list_pending(5, 10)?.is_empty());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn list_pending_with_non_indexed() {
+        with_tmpdir_and_indexer(&|_, indexer| {
+            create_fake_index(&indexer.index_path, true)?;
+            indexer.header.indexed_count = 4;
+            match indexer.list_pending(0, 10) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => match e.downcast::<IndexError>() {
+                    Ok(IndexError::Unavailable(Status::Incomplete)) => {},
+                    Ok(ex) => panic!("expected IndexError::Unavailable(Incomplete) but got: {:?}", ex),
+                    Err(ex) => panic!("{:?}", ex)
+                }
+            }
+            Ok(())
+        });
+    }
+
     #[test]
     fn healthcheck_new_index() {
         with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
@@ -2267,6 +3053,35 @@ mod tests {
         });
     }
 
+    #[test]
+    fn healthcheck_legacy_hash() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            let mut reader = indexer.new_input_reader()?;
+            let legacy_hash = crate::generate_legacy_hash(&mut reader)?;
+
+            // index files without hash algorithm use the legacy hash
+            let mut buf = [0u8; Header::BYTES+Header::BYTES+FAKE_VALUES_BYTES];
+            let mut writer = &mut buf as &mut [u8];
+            let mut header = Header::new();
+            header.indexed_count = 3;
+            header.hash = Some(legacy_hash);
+            header.hash_algorithm = None;
+            header.write_to(&mut writer)?;
+            create_file_with_bytes(&indexer.index_path, &buf)?;
+            assert_eq!(Status::Incomplete, indexer.healthcheck()?);
+            assert_eq!(None, indexer.header.hash_algorithm);
+
+            // legacy hash doesn't match the new default hash algorithm
+            header.hash_algorithm = Some(crate::HashAlgorithm::Sha3_256);
+            let mut writer = &mut buf as &mut [u8];
+            header.write_to(&mut writer)?;
+            create_file_with_bytes(&indexer.index_path, &buf)?;
+            assert_eq!(Status::WrongInputFile, indexer.healthcheck()?);
+            Ok(())
+        });
+    }
+
     #[test]
     fn save_header_into() {
         with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
@@ -2382,6 +3197,7 @@ mod tests {
             let mut reader = BufReader::new(file);
             let mut expected = Vec::new();
             reader.read_to_end(&mut expected)?;
+            let expected = with_checksum_trailer(expected);
             
             // validate index bytes
             let file = File::open(&indexer.index_path)?;
@@ -2403,6 +3219,105 @@ mod tests {
         });
     }
 
+    #[test]
+    fn index_resume_on_append() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_file_with_bytes(&indexer.input_path, b"name,size\nfork,1\nknife,2\n")?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.index()?;
+            let mut value = indexer.value(0)?.unwrap();
+            value.data.match_flag = MatchFlag::Yes;
+            indexer.save_data(0, &value.data)?;
+
+            // a changed input is rejected unless resume on append is enabled
+            create_file_with_bytes(&indexer.input_path, b"name,size\nfork,1\nknife,2\nspoon,3\r\nplate,4")?;
+            let mut indexer = Indexer::new(indexer.input_path.clone(), indexer.index_path.clone(), InputType::CSV);
+            assert!(indexer.index().is_err());
+            indexer.resume_on_append = true;
+            assert_eq!(Some(25), indexer.appended_input_pos()?);
+            indexer.index()?;
+
+            // the indexed values are kept, so their decisions are too
+            assert_eq!(4, indexer.header.indexed_count);
+            assert_eq!(Some(value), indexer.value(0)?);
+            let positions: Vec<(u64, u64)> = indexer.index_entries()?
+                .map(|v| v.map(|v| (v.input_start_pos, v.input_end_pos)))
+                .collect::<Result<_>>()?;
+            assert_eq!(vec![(10, 15), (17, 23), (25, 31), (34, 40)], positions);
+            assert_eq!(Some(indexer.input_hash()?), indexer.header.hash);
+            assert!(indexer.verify_integrity()?);
+            assert_eq!(Status::Indexed, indexer.healthcheck()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_resume_on_append_with_changed_input() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_file_with_bytes(&indexer.input_path, b"name,size\nfork,1\nknife,2\n")?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.index()?;
+            let mut value = indexer.value(0)?.unwrap();
+            value.data.match_flag = MatchFlag::Yes;
+            indexer.save_data(0, &value.data)?;
+
+            // the indexed content changed so the input is indexed from scratch
+            create_file_with_bytes(&indexer.input_path, b"name,size\nspoon,1\nknife,2\nplate,4\n")?;
+            let mut indexer = Indexer::new(indexer.input_path.clone(), indexer.index_path.clone(), InputType::CSV);
+            indexer.resume_on_append = true;
+            assert_eq!(None, indexer.appended_input_pos()?);
+            indexer.index()?;
+            assert_eq!(3, indexer.header.indexed_count);
+            assert_eq!(MatchFlag::None, indexer.value(0)?.unwrap().data.match_flag);
+            assert!(indexer.verify_integrity()?);
+            assert_eq!(Status::Indexed, indexer.healthcheck()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_resume_on_append_with_legacy_hash() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_file_with_bytes(&indexer.input_path, b"name,size\nfork,1\nknife,2\n")?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.index()?;
+            let mut value = indexer.value(0)?.unwrap();
+            value.data.match_flag = MatchFlag::Yes;
+            indexer.save_data(0, &value.data)?;
+
+            // turn it into an index file without hash algorithm
+            let mut reader = indexer.new_input_reader()?;
+            indexer.header.hash = Some(crate::generate_legacy_hash(&mut reader)?);
+            indexer.header.hash_algorithm = None;
+            indexer.save_header()?;
+
+            // rewrite the indexed content keeping its size and append a record
+            create_file_with_bytes(&indexer.input_path, b"name,size\nspon,1\nknife,2\nplate,4\n")?;
+            let mut indexer = Indexer::new(indexer.input_path.clone(), indexer.index_path.clone(), InputType::CSV);
+            indexer.resume_on_append = true;
+            assert_eq!(Status::WrongInputFile, indexer.healthcheck()?);
+            assert_eq!(None, indexer.header.hash_algorithm);
+            assert_eq!(None, indexer.appended_input_pos()?);
+            match indexer.input_prefix_hash(25) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "can't hash the input prefix without a hash algorithm, the legacy hash only depends on the input size",
+                    e.to_string()
+                )
+            }
+
+            // test the input is indexed from scratch
+            indexer.index()?;
+            assert_eq!(3, indexer.header.indexed_count);
+            assert_eq!(MatchFlag::None, indexer.value(0)?.unwrap().data.match_flag);
+            assert_eq!(Some(crate::HashAlgorithm::Sha3_256), indexer.header.hash_algorithm);
+            assert_eq!(Some(indexer.input_hash()?), indexer.header.hash);
+            assert!(indexer.verify_integrity()?);
+            assert_eq!(Status::Indexed, indexer.healthcheck()?);
+            Ok(())
+        });
+    }
+
     #[test]
     fn index_existing() {
         with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
@@ -2432,4 +3347,461 @@ mod tests {
             Ok(())
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn index_without_header() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            let buf = "\
+                fork,\"1 inch\",12.34,red\n\
+                keyboard,medium,23.45,\"black\nwhite\"\n\
+                mouse,\"12 cm\",98.76,white\
+            ".as_bytes();
+            create_file_with_bytes(&indexer.input_path, buf)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.has_header = false;
+            indexer.input_fields = vec![
+                "name".to_string(),
+                "size".to_string(),
+                "price".to_string(),
+                "color".to_string()
+            ];
+            indexer.index()?;
+            assert_eq!(3, indexer.header.indexed_count);
+
+            // the first row must be the first record
+            let value = match indexer.value(0)? {
+                Some(v) => v,
+                None => bail!("expected a value but got None")
+            };
+            assert_eq!(0, value.input_start_pos);
+            let mut expected = JSMap::new();
+            expected.insert("name".to_string(), JSValue::String("fork".to_string()));
+            expected.insert("size".to_string(), JSValue::String("1 inch".to_string()));
+            expected.insert("price".to_string(), JSValue::Number(JSNumber::from_f64(12.34f64).unwrap()));
+            expected.insert("color".to_string(), JSValue::String("red".to_string()));
+            assert_eq!(expected, indexer.parse_input(&value)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_skip_duplicate_headers() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            let buf = "\
+                name,size\n\
+                fork,\"1 inch\"\n\
+                name,size\n\
+                keyboard,medium\n\
+                name,size\n\
+                mouse,\"12 cm\"\n\
+            ".as_bytes();
+            create_file_with_bytes(&indexer.input_path, buf)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.skip_duplicate_headers = true;
+            indexer.index()?;
+            assert_eq!(3, indexer.header.indexed_count);
+            assert_eq!(2, indexer.skipped_header_rows);
+
+            // records following a repeated header are indexed as usual
+            let mut expected = JSMap::new();
+            expected.insert("name".to_string(), JSValue::String("keyboard".to_string()));
+            expected.insert("size".to_string(), JSValue::String("medium".to_string()));
+            let value = indexer.value(1)?.unwrap();
+            assert_eq!(expected, indexer.parse_input(&value)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_keep_duplicate_headers() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            let buf = "name,size\nfork,small\nname,size\nmouse,big\n".as_bytes();
+            create_file_with_bytes(&indexer.input_path, buf)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.index()?;
+            assert_eq!(3, indexer.header.indexed_count);
+            assert_eq!(0, indexer.skipped_header_rows);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_without_header_nor_input_fields() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.has_header = false;
+            match indexer.index() {
+                Ok(v) => panic!("expected IndexError::NoInputFields but got {:?}", v),
+                Err(e) => assert_eq!("the input doesn't have any fields", e.to_string())
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn line_ending_report_mixed() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            let buf = "name,size\r\nfork,1\nknife,2\r\nspoon,3\n".as_bytes();
+            create_file_with_bytes(&indexer.input_path, buf)?;
+
+            let expected = LineEndingReport{
+                lf: 2,
+                crlf: 2,
+                cr: 0
+            };
+            let report = indexer.line_ending_report()?;
+            assert_eq!(expected, report);
+            assert!(report.is_mixed());
+            assert_eq!(
+                Some("mixed line endings found: 2 \\n, 2 \\r\\n and 0 \\r".to_string()),
+                report.warning()
+            );
+
+            // lone carriage returns
+            let buf = "name,size\r\nfork,1\rknife,2\r".as_bytes();
+            create_file_with_bytes(&indexer.input_path, buf)?;
+            let expected = LineEndingReport{
+                lf: 0,
+                crlf: 1,
+                cr: 2
+            };
+            assert_eq!(expected, indexer.line_ending_report()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn line_ending_report_consistent() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            let buf = "name,size\r\nfork,1\r\nknife,2\r\n".as_bytes();
+            create_file_with_bytes(&indexer.input_path, buf)?;
+
+            let expected = LineEndingReport{
+                lf: 0,
+                crlf: 3,
+                cr: 0
+            };
+            let report = indexer.line_ending_report()?;
+            assert_eq!(expected, report);
+            assert!(!report.is_mixed());
+
+            // quoted line breaks are counted too
+            create_fake_input(&indexer.input_path)?;
+            let report = indexer.line_ending_report()?;
+            assert_eq!(5, report.lf);
+            assert!(!report.is_mixed());
+            assert_eq!(None, report.warning());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_entries() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            let values = create_fake_index(&indexer.index_path, true)?;
+            indexer.load_header_from(&mut indexer.new_index_reader()?)?;
+
+            // record a couple decisions
+            indexer.save_data(1, &Data{
+                match_flag: MatchFlag::Yes,
+                spent_time: 34
+            })?;
+            indexer.save_data(3, &Data{
+                match_flag: MatchFlag::No,
+                spent_time: 12
+            })?;
+
+            // test
+            let entries = indexer.index_entries()?.collect::<Result<Vec<IndexEntry>>>()?;
+            let flags = [MatchFlag::None, MatchFlag::Yes, MatchFlag::None, MatchFlag::No];
+            let times = [0, 34, 0, 12];
+            assert_eq!(4, entries.len());
+            for (index, entry) in entries.iter().enumerate() {
+                let expected = IndexEntry{
+                    index: index as u64,
+                    input_start_pos: values[index].input_start_pos,
+                    input_end_pos: values[index].input_end_pos,
+                    match_flag: flags[index],
+                    spent_time: times[index]
+                };
+                assert_eq!(&expected, entry);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_entries_without_values() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_index(&indexer.index_path, true)?;
+            indexer.load_header_from(&mut indexer.new_index_reader()?)?;
+            indexer.header.indexed_count = 0;
+            assert_eq!(0, indexer.index_entries()?.count());
+
+            // not indexed
+            indexer.header.indexed = false;
+            match indexer.index_entries() {
+                Ok(_) => panic!("expected error but got an iterator"),
+                Err(e) => assert_eq!("input file must be indexed before reading values", e.to_string())
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn save_data_writes_in_place() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_index(&indexer.index_path, true)?;
+            indexer.load_header_from(&mut indexer.new_index_reader()?)?;
+            let mut old_bytes = std::fs::read(&indexer.index_path)?;
+
+            // test
+            for index in 0..indexer.header.indexed_count {
+                indexer.save_data(index, &Data{
+                    match_flag: MatchFlag::Yes,
+                    spent_time: index + 1
+                })?;
+                let new_bytes = std::fs::read(&indexer.index_path)?;
+                assert_eq!(old_bytes.len(), new_bytes.len());
+                let start = Indexer::calc_value_pos(index) as usize + Value::DATA_OFFSET;
+                let end = Indexer::calc_value_pos(index + 1) as usize;
+                assert_eq!(old_bytes[..start], new_bytes[..start]);
+                assert_eq!(old_bytes[end..], new_bytes[end..]);
+                assert_eq!(&value::test_helper::build_data_bytes(index + 1, b'Y')[..], &new_bytes[start..end]);
+                old_bytes = new_bytes;
+            }
+            Ok(())
+        });
+    }
+
+    /// Writes an index file as left by a crash after `committed_count`
+    /// values were committed and `uncommitted_bytes` more value bytes were
+    /// written, then returns the expected complete index bytes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexer` - Indexer to write the crashed index file for.
+    /// * `committed_count` - Committed value count on the index header.
+    /// * `uncommitted_bytes` - Value bytes written after the last commit.
+    fn create_crashed_index(indexer: &Indexer, committed_count: u64, uncommitted_bytes: usize) -> Result<Vec<u8>> {
+        let mut writer = Cursor::new(Vec::new());
+        write_fake_index(&mut writer, true)?;
+        let expected = writer.into_inner();
+
+        let mut header = Header::new();
+        header.input_type = InputType::CSV;
+        header.hash = Some(fake_input_hash());
+        header.indexed_count = committed_count;
+        let mut buf = Vec::new();
+        header.write_to(&mut buf)?;
+        let end = Indexer::calc_value_pos(committed_count) as usize + uncommitted_bytes;
+        buf.extend_from_slice(&expected[Header::BYTES..end]);
+        create_file_with_bytes(&indexer.index_path, &buf)?;
+        Ok(with_checksum_trailer(expected))
+    }
+
+    #[test]
+    fn index_resume_after_crash() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            let expected = create_crashed_index(indexer, 2, Value::BYTES + 10)?;
+            assert_eq!(Status::Incomplete, indexer.healthcheck()?);
+
+            // test
+            indexer.index()?;
+            assert_eq!(expected, std::fs::read(&indexer.index_path)?);
+            assert_eq!(Status::Indexed, indexer.healthcheck()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_resume_without_committed_values() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            let expected = create_crashed_index(indexer, 0, 7)?;
+
+            // test
+            indexer.index()?;
+            assert_eq!(expected, std::fs::read(&indexer.index_path)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_recover_header_journal() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            // crash after writing the journal but before the header commit
+            create_fake_input(&indexer.input_path)?;
+            let expected = create_crashed_index(indexer, 2, Value::BYTES * 2)?;
+            create_file_with_bytes(&indexer.header_journal_path(), &expected[..Header::BYTES])?;
+            assert_eq!(Status::Corrupted, indexer.healthcheck()?);
+
+            // test
+            indexer.index()?;
+            assert!(!indexer.header_journal_path().exists());
+
+            // the restored header was committed before the checksum trailer
+            assert_eq!(expected[..expected.len() - HASH_SIZE], std::fs::read(&indexer.index_path)?);
+            assert!(indexer.header.indexed);
+            assert_eq!(4, indexer.header.indexed_count);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_discard_partial_header_journal() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            // crash while writing the journal
+            create_fake_input(&indexer.input_path)?;
+            let expected = create_crashed_index(indexer, 3, Value::BYTES)?;
+            create_file_with_bytes(&indexer.header_journal_path(), &expected[..5])?;
+
+            // test
+            assert!(!indexer.recover_header()?);
+            assert!(!indexer.header_journal_path().exists());
+            indexer.index()?;
+            assert_eq!(expected, std::fs::read(&indexer.index_path)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_reject_crash_without_recovery() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            let expected = create_crashed_index(indexer, 2, 10)?;
+            indexer.recover_on_open = false;
+            assert_eq!(Status::Corrupted, indexer.healthcheck()?);
+
+            // test uncommitted values
+            match indexer.index() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("unavailable due status \"corrupted\"", e.to_string())
+            }
+
+            // test pending header journal
+            create_crashed_index(indexer, 2, 0)?;
+            assert_eq!(Status::Incomplete, indexer.healthcheck()?);
+            create_file_with_bytes(&indexer.header_journal_path(), &expected[..Header::BYTES])?;
+            match indexer.index() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("unavailable due status \"corrupted\"", e.to_string())
+            }
+            assert!(indexer.header_journal_path().exists());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_removes_header_journal() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.checkpoint_size = 1;
+
+            // test
+            indexer.index()?;
+            assert!(!indexer.header_journal_path().exists());
+            assert_eq!(4, indexer.header.indexed_count);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_without_checkpoints() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.checkpoint_size = 0;
+
+            // test
+            indexer.index()?;
+            assert!(!indexer.header_journal_path().exists());
+            assert!(indexer.header.indexed);
+            assert_eq!(4, indexer.header.indexed_count);
+            assert_eq!(Status::Indexed, indexer.healthcheck()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn index_with_batch_size() {
+        for batch_size in [0, 1, 3] {
+            with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+                create_fake_input(&indexer.input_path)?;
+                indexer.header.input_type = InputType::CSV;
+                indexer.batch_size = batch_size;
+                indexer.checkpoint_size = 2;
+
+                // test
+                indexer.index()?;
+                assert!(!indexer.header_journal_path().exists());
+                assert_eq!(4, indexer.header.indexed_count);
+                assert_eq!(Status::Indexed, indexer.healthcheck()?);
+                Ok(())
+            });
+        }
+    }
+
+    #[test]
+    fn index_truncated_input() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.index()?;
+            assert_eq!(4, indexer.header.indexed_count);
+
+            // truncate the input and reopen the index
+            let input = fake_input_bytes();
+            create_file_with_bytes(&indexer.input_path, &input[..input.len() - 20])?;
+            let mut indexer = Indexer::new(
+                indexer.input_path.clone(),
+                indexer.index_path.clone(),
+                InputType::CSV
+            );
+
+            // test
+            let expected = format!(
+                "the index has 4 records up to byte {} but the input file is {} bytes long, the input file must be reindexed",
+                input.len(),
+                input.len() - 20
+            );
+            match indexer.index() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn input_seeks_record_offsets() {
+        with_tmpdir_and_indexer(&|_, indexer| -> Result<()> {
+            create_fake_input(&indexer.input_path)?;
+            indexer.header.input_type = InputType::CSV;
+            indexer.index()?;
+
+            // overwrite the records before the target record so any scan
+            // from the input start would fail to parse
+            let target = indexer.value(2)?.unwrap();
+            let mut input = fake_input_bytes();
+            let header_size = b"name,size,price,color\n".len();
+            for byte in input[header_size..target.input_start_pos as usize].iter_mut() {
+                *byte = b'"';
+            }
+            create_file_with_bytes(&indexer.input_path, &input)?;
+
+            // test
+            let mut expected = JSMap::new();
+            expected.insert("name".to_string(), JSValue::String("mouse".to_string()));
+            expected.insert("size".to_string(), JSValue::String("12 cm".to_string()));
+            expected.insert("price".to_string(), JSValue::Number(JSNumber::from_f64(98.76f64).unwrap()));
+            expected.insert("color".to_string(), JSValue::String("white".to_string()));
+            assert_eq!(Some(expected), indexer.input(2)?);
+            assert_eq!(None, indexer.input(4)?);
+            Ok(())
+        });
+    }
+}
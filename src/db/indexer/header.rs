@@ -2,6 +2,7 @@ use std::io::{Read, Write};
 use std::convert::TryFrom;
 use anyhow::{bail, Result};
 use super::VERSION;
+use crate::HashAlgorithm;
 use crate::error::ParseError;
 use crate::traits::{ByteSized, FromByteSlice, WriteAsBytes, ReadFrom, WriteTo, LoadFrom};
 
@@ -83,6 +84,11 @@ pub struct Header {
     /// Input file hash.
     pub hash: Option<[u8; HASH_SIZE]>,
 
+    /// Algorithm used to generate the input file hash, `None` on index
+    /// files created before the algorithm was recorded, which store a
+    /// legacy hash generated by [crate::generate_legacy_hash].
+    pub hash_algorithm: Option<HashAlgorithm>,
+
     /// Indexed records count.
     pub indexed_count: u64,
 
@@ -96,11 +102,42 @@ impl Header {
         Self{
             indexed: false,
             hash: None,
+            hash_algorithm: Some(HashAlgorithm::Sha3_256),
             indexed_count: 0,
             input_type: InputType::Unknown
         }
     }
 
+    /// Hash flag byte value used for a hash algorithm.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `algorithm` - Hash algorithm, `None` for legacy hashes.
+    fn hash_flag(algorithm: Option<HashAlgorithm>) -> u8 {
+        match algorithm {
+            None => 1,
+            Some(HashAlgorithm::Sha3_256) => 2,
+            Some(HashAlgorithm::Sha256) => 3,
+            Some(HashAlgorithm::Blake3) => 4
+        }
+    }
+
+    /// Hash algorithm from a non zero hash flag byte value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Hash flag byte value.
+    fn hash_algorithm_from_flag(flag: u8) -> Result<Option<HashAlgorithm>, ParseError> {
+        let algorithm = match flag {
+            1 => None,
+            2 => Some(HashAlgorithm::Sha3_256),
+            3 => Some(HashAlgorithm::Sha256),
+            4 => Some(HashAlgorithm::Blake3),
+            _ => return Err(ParseError::InvalidFormat)
+        };
+        Ok(algorithm)
+    }
+
     /// Clone input file hash value.
     /// 
     /// # Arguments
@@ -142,9 +179,9 @@ impl Header {
         buf[carry] = self.input_type.into();
         carry += InputType::BYTES;
 
-        // save hash flag and value
+        // save hash flag along the hash algorithm and value
         if let Some(hash_bytes) = self.hash {
-            buf[carry] = 1;
+            buf[carry] = Self::hash_flag(self.hash_algorithm);
             carry += 1;
             let hash_buf = &mut buf[carry..carry+HASH_SIZE];
             hash_buf.copy_from_slice(&hash_bytes);
@@ -157,7 +194,8 @@ impl ByteSized for Header {
     /// Index header size in bytes.
     /// 
     /// Byte Format
-    /// `<magic_number:11><version:4><indexed:1><indexed_count:8><input_type:1><hash_valid:1><hash:32>`.
+    /// `<magic_number:11><version:4><indexed:1><indexed_count:8><input_type:1><hash_algorithm:1><hash:32>`,
+    /// the hash algorithm is `0` when there is no hash.
     const BYTES: usize = 47 + MAGIC_NUMBER_SIZE;
 }
 
@@ -193,8 +231,9 @@ impl LoadFrom for Header {
         let input_type = buf[carry].try_into()?;
         carry += InputType::BYTES;
 
-        // extract hash
+        // extract hash along the hash algorithm
         let hash = if buf[carry] > 0 {
+            self.hash_algorithm = Self::hash_algorithm_from_flag(buf[carry])?;
             carry += 1;
             Some(Self::clone_hash(&buf[carry..carry+HASH_SIZE])?)
         } else {
@@ -284,6 +323,7 @@ pub mod test_helper {
             indexed,
             indexed_count,
             hash,
+            hash_algorithm: Some(HashAlgorithm::Sha3_256),
             input_type
         }.as_bytes()
     }
@@ -341,6 +381,7 @@ mod tests {
                 Header{
                     indexed: false,
                     hash: None,
+                    hash_algorithm: Some(HashAlgorithm::Sha3_256),
                     indexed_count: 0,
                     input_type: InputType::Unknown
                 },
@@ -379,8 +420,8 @@ mod tests {
                 32, 19, 242, 78, 103, 5, 196, 233,
                 // input type
                 1,
-                // valid hash
-                1,
+                // hash algorithm
+                2,
                 // hash value placeholder
                 0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0
             ];
@@ -396,6 +437,7 @@ mod tests {
                 indexed: true,
                 indexed_count: 2311457452320998633,
                 hash: Some(random_hash_buf),
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 input_type: InputType::CSV
             };
             assert_eq!(expected, header.as_bytes());
@@ -412,7 +454,7 @@ mod tests {
                 62, 205, 47, 180, 235, 228, 244, 171,
                 // input type
                 2,
-                // valid hash
+                // no hash
                 0,
                 // empty hash value
                 0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0
@@ -423,6 +465,7 @@ mod tests {
                 indexed: false,
                 indexed_count: 4525325654675485867,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 input_type: InputType::JSON,
             };
             assert_eq!(expected, header.as_bytes());
@@ -439,6 +482,7 @@ mod tests {
             let mut header = Header{
                 indexed: false,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 0,
                 input_type: InputType::Unknown
             };
@@ -446,6 +490,7 @@ mod tests {
             let expected = Header{
                 indexed: true,
                 hash: Some(hash),
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 4535435,
                 input_type: InputType::JSON
             };
@@ -461,12 +506,14 @@ mod tests {
             let mut header = Header{
                 indexed: false,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 0,
                 input_type: InputType::Unknown
             };
             let expected = Header{
                 indexed: false,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 6572646535124,
                 input_type: InputType::JSON
             };
@@ -484,6 +531,7 @@ mod tests {
             let mut header = Header{
                 indexed: false,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 0,
                 input_type: InputType::Unknown
             };
@@ -507,6 +555,7 @@ mod tests {
             let expected = Header{
                 indexed: true,
                 hash: Some(hash),
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 2341234,
                 input_type: InputType::CSV
             };
@@ -524,6 +573,7 @@ mod tests {
             let expected = Header{
                 indexed: false,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 9879873495743,
                 input_type: InputType::Unknown
             };
@@ -545,6 +595,7 @@ mod tests {
             let expected = Header{
                 indexed: false,
                 hash: Some(hash),
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 974734838473874,
                 input_type: InputType::CSV
             };
@@ -563,6 +614,7 @@ mod tests {
             let expected = Header{
                 indexed: true,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 3434232315645344,
                 input_type: InputType::JSON
             };
@@ -585,6 +637,7 @@ mod tests {
             let expected = Header{
                 indexed: false,
                 hash: Some(hash),
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 32412342134234,
                 input_type: InputType::CSV
             };
@@ -602,6 +655,7 @@ mod tests {
             let expected = Header{
                 indexed: true,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 56535423143214,
                 input_type: InputType::JSON
             };
@@ -624,6 +678,7 @@ mod tests {
             let header = Header{
                 indexed: false,
                 hash: Some(hash),
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 788477630402843,
                 input_type: InputType::CSV
             };
@@ -640,6 +695,7 @@ mod tests {
             let header = Header{
                 indexed: true,
                 hash: None,
+                hash_algorithm: Some(HashAlgorithm::Sha3_256),
                 indexed_count: 63439320337562938,
                 input_type: InputType::JSON
             };
@@ -651,5 +707,37 @@ mod tests {
             };
             assert_eq!(expected, buf);
         }
+
+        #[test]
+        fn hash_algorithm_flag() -> Result<()> {
+            let hash = random_hash();
+            let algorithms = [
+                (None, 1u8),
+                (Some(HashAlgorithm::Sha3_256), 2u8),
+                (Some(HashAlgorithm::Sha256), 3u8),
+                (Some(HashAlgorithm::Blake3), 4u8)
+            ];
+            for (algorithm, flag) in algorithms {
+                let header = Header{
+                    indexed: true,
+                    hash: Some(hash),
+                    hash_algorithm: algorithm,
+                    indexed_count: 10,
+                    input_type: InputType::CSV
+                };
+                let buf = header.as_bytes();
+                assert_eq!(flag, buf[25]);
+                assert_eq!(header, Header::from_byte_slice(&buf)?);
+            }
+
+            // unknown hash flag
+            let mut buf = build_header_bytes(true, &hash, true, 10, InputType::CSV);
+            buf[25] = 5;
+            match Header::from_byte_slice(&buf) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(ParseError::InvalidFormat.to_string(), e.to_string())
+            }
+            Ok(())
+        }
     }
 }
\ No newline at end of file
@@ -6,7 +6,7 @@ use crate::error::ParseError;
 use crate::traits::{ByteSized, FromByteSlice, WriteAsBytes, ReadFrom, WriteTo, LoadFrom};
 
 /// Match flag enumerator.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum MatchFlag {
     Yes = b'Y' as isize,
     No = b'N' as isize,
@@ -82,6 +82,24 @@ impl TryFrom<u8> for MatchFlag {
     }
 }
 
+impl std::str::FromStr for MatchFlag {
+    type Err = ParseError;
+
+    /// Parses either the flag byte char or its display name, case
+    /// insensitive, an empty string is parsed as [MatchFlag::None].
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let match_flag = match s.trim().to_lowercase().as_str() {
+            "y" | "yes" => Self::Yes,
+            "n" | "no" => Self::No,
+            "s" | "skip" => Self::Skip,
+            "" => Self::None,
+            _ => return Err(ParseError::InvalidFormat)
+        };
+
+        Ok(match_flag)
+    }
+}
+
 impl From<&MatchFlag> for u8 {
     fn from(v: &MatchFlag) -> Self {
         match v {
@@ -394,6 +412,22 @@ mod tests {
             }
         }
 
+        #[test]
+        fn from_str() -> Result<()> {
+            assert_eq!(MatchFlag::Yes, "Y".parse()?);
+            assert_eq!(MatchFlag::Yes, "yes".parse()?);
+            assert_eq!(MatchFlag::No, "N".parse()?);
+            assert_eq!(MatchFlag::No, "No".parse()?);
+            assert_eq!(MatchFlag::Skip, "s".parse()?);
+            assert_eq!(MatchFlag::Skip, " SKIP ".parse()?);
+            assert_eq!(MatchFlag::None, "".parse()?);
+            match "maybe".parse::<MatchFlag>() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("invalid format", e.to_string())
+            }
+            Ok(())
+        }
+
         #[test]
         fn into_u8() {
             assert_eq!(b'Y', u8::from(MatchFlag::Yes));
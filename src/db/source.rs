@@ -5,12 +5,17 @@ use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::{Seek, SeekFrom, Read, Write, BufReader, BufWriter};
 use std::path::PathBuf;
-use crate::error::{ParseError, IndexError};
+use crate::error::{ParseError, IndexError, TableError};
+use crate::{is_same_file, parse_index};
 use crate::traits::{ReadFrom, WriteTo};
 use super::indexer::{Indexer, Status as IndexStatus};
+use super::indexer::header::Header as IndexHeader;
 use super::indexer::value::{MatchFlag, Data as IndexData, Value as IndexValue};
 use super::table::Table;
-use super::table::record::Record;
+use super::table::record::{Record, Value};
+
+/// Table field name used to store decision comments.
+pub const COMMENTS_FIELD: &str = "comments";
 
 /// Represents a data source single record.
 #[derive(Debug, Serialize, PartialEq)]
@@ -20,6 +25,19 @@ pub struct Data {
     pub record: Record
 }
 
+/// Represents a data source record along its closest pending records.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ComparePayload {
+    /// Record data, `None` when there is no record at the index.
+    pub data: Option<Data>,
+
+    /// Closest pending record index before the record.
+    pub prev_pending: Option<u64>,
+
+    /// Closest pending record index after the record.
+    pub next_pending: Option<u64>
+}
+
 /// Represents a source readers involved in a join operation.
 pub struct SourceJoinItem<R, T> {
     pub index: R,
@@ -77,17 +95,15 @@ impl Source {
                     IndexError::Unavailable(status) => match status {
                         IndexStatus::Indexing => bail!(IndexError::Unavailable(IndexStatus::Indexing)),
                         _ => if override_on_error {
-                            // truncate the file then index again
-                            let file = OpenOptions::new()
-                                .create(true)
-                                .open(&self.index.index_path)?;
-                            file.set_len(0)?;
-                            self.index.index()?;
+                            self.reindex()?;
                         }
                     },
                     err => bail!(err)
                 },
-                Err(ex) => bail!(ex)
+                Err(ex) => match ex.downcast_ref::<ParseError>() {
+                    Some(ParseError::InputLengthMismatch{..}) if override_on_error => self.reindex()?,
+                    _ => bail!(ex)
+                }
             }
         }
         if self.table.header.record_count < 1 {
@@ -97,6 +113,19 @@ impl Source {
         Ok(())
     }
 
+    /// Truncates the index file then index the input file again.
+    fn reindex(&mut self) -> Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(&self.index.index_path)?;
+        file.set_len(0)?;
+        let input_type = self.index.header.input_type;
+        self.index.header = IndexHeader::new();
+        self.index.header.input_type = input_type;
+        self.index.index()
+    }
+
     /// Search the next unprocessed record an return the index if any.
     /// 
     /// # Arguments
@@ -106,6 +135,23 @@ impl Source {
         self.index.find_pending(from_index)
     }
 
+    /// Returns the decision progress as `(decided_count, total_count)`.
+    /// An empty source returns `(0, 0)`.
+    pub fn progress(&self) -> Result<(u64, u64)> {
+        self.index.progress()
+    }
+
+    /// Retrieves a table record from a specific index. Returns `None`
+    /// when there is no record at the index, so a missing record can be
+    /// told apart from a record with default values.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    pub fn record(&self, index: u64) -> Result<Option<Record>> {
+        self.table.record(index)
+    }
+
     /// Retrive a record input data from a specific index.
     /// 
     /// $ Arguments
@@ -128,6 +174,61 @@ impl Source {
         }))
     }
 
+    /// Retrieves a record data along the closest pending record indexes
+    /// before and after it by using a single pass over the index.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    pub fn get_with_neighbors(&self, index: u64) -> Result<ComparePayload> {
+        let mut value = None;
+        let mut prev_pending = None;
+        let mut next_pending = None;
+        for entry in self.index.index_entries()? {
+            let entry = entry?;
+            let pending = entry.match_flag == MatchFlag::None;
+            if entry.index < index {
+                if pending {
+                    prev_pending = Some(entry.index);
+                }
+                continue;
+            }
+            if entry.index == index {
+                value = Some(IndexValue{
+                    input_start_pos: entry.input_start_pos,
+                    input_end_pos: entry.input_end_pos,
+                    data: IndexData{
+                        spent_time: entry.spent_time,
+                        match_flag: entry.match_flag
+                    }
+                });
+                continue;
+            }
+            if pending {
+                next_pending = Some(entry.index);
+                break;
+            }
+        }
+
+        // read the record data
+        let data = match value {
+            Some(index_value) => match self.table.record(index)? {
+                Some(record) => Some(Data{
+                    input: self.index.parse_input(&index_value)?,
+                    index: index_value.data,
+                    record
+                }),
+                None => None
+            },
+            None => None
+        };
+        Ok(ComparePayload{
+            data,
+            prev_pending,
+            next_pending
+        })
+    }
+
     /// Check if the source is indexed.
     pub fn is_indexed(&self) -> bool {
         // check that the index has been indexed
@@ -163,6 +264,11 @@ impl Source {
             return (false, "indexed count doesn't match")
         }
 
+        // ensure tables has the same record layout
+        if self.table.header.user_flags != source.table.header.user_flags {
+            return (false, "table user flags setting doesn't match")
+        }
+
         // ensure tables has the same fields count
         if self.table.record_header.len() != source.table.record_header.len() {
             return (false, "table field count doesn't match")
@@ -204,6 +310,9 @@ impl Source {
                 bail!(err_msg)
             }
         }
+        if !base_source.table.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
         if limit > 1 {
             for i in 1..limit {
                 // ensure all sources are indexed
@@ -225,6 +334,8 @@ impl Source {
         let mut target = base_source.clone();
         target.index.index_path = index_path.clone();
         target.table.path = table_path.clone();
+        target.table.header.checksum = None;
+        target.table.header.upgrade_version();
         let mut to_process_count = 0u64;
 
         // create target writers and write the target headers
@@ -253,7 +364,7 @@ impl Source {
         // iterate and join sources
         let total_sources = sources.len() as f64;
         let match_values = MatchFlag::as_array();
-        let record_size = target.table.record_header.record_byte_size() as usize;
+        let record_size = target.table.record_size() as usize;
         let mut base_record_buf = vec![0u8; record_size as usize];
         let mut record_buf = vec![0u8; record_size as usize];
         for index in 0..target.index.header.indexed_count {
@@ -326,6 +437,218 @@ impl Source {
         }
         Ok((target, to_process_count))
     }
+
+    /// Extracts the records with a specific match flag into a new table
+    /// file with the same headers and returns the extracted record count.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Match flag to extract.
+    /// * `out` - Target table file path.
+    pub fn extract_by_flag(&self, flag: MatchFlag, out: &PathBuf) -> Result<u64> {
+        if !self.is_indexed() {
+            bail!("source must be indexed to extract records");
+        }
+        if is_same_file(&self.table.path, out) || is_same_file(&self.index.index_path, out) {
+            bail!("source files can't be the same as the target files");
+        }
+        if self.table.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.table.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+
+        // create the target table and write its headers
+        let mut target = self.table.clone();
+        target.path = out.clone();
+        target.header.record_count = 0;
+        target.header.checksum = None;
+        target.header.upgrade_version();
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(out)?;
+        let mut writer = BufWriter::new(file);
+        target.save_headers_into(&mut writer)?;
+        writer.seek(SeekFrom::Start(target.calc_record_pos(0)))?;
+
+        // create readers and move them to the first record position
+        let mut readers = SourceJoinItem::as_reader_from(self)?;
+        readers.index.seek(SeekFrom::Start(Indexer::calc_value_pos(0)))?;
+        readers.table.seek(SeekFrom::Start(self.table.calc_record_pos(0)))?;
+
+        // copy the matching record bytes as is
+        let record_size = self.table.record_size() as usize;
+        let mut record_buf = vec![0u8; record_size];
+        for _ in 0..self.table.header.record_count {
+            let value = IndexValue::read_from(&mut readers.index)?;
+            readers.table.read_exact(&mut record_buf)?;
+            if value.data.match_flag == flag {
+                writer.write_all(&record_buf)?;
+                target.header.record_count += 1;
+            }
+        }
+
+        // save the extracted record count
+        target.save_headers_into(&mut writer)?;
+        writer.flush()?;
+        Ok(target.header.record_count)
+    }
+
+    /// Applies the decisions from a CSV file with rows as
+    /// `index,flag,track_time,comments` and returns the applied count.
+    /// The comments are saved into the table "comments" field when the
+    /// table has one. Every row is validated before any decision is
+    /// applied, so an invalid row leaves both the index and the table
+    /// untouched, and the applied decisions are rolled back when a write
+    /// fails. Errors report the row CSV line number.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `csv_path` - Decisions CSV file path.
+    pub fn import_decisions(&mut self, csv_path: &PathBuf) -> Result<u64> {
+        if !self.is_indexed() {
+            bail!("source must be indexed to import decisions");
+        }
+        let comments_field = self.table.record_header.get(COMMENTS_FIELD).cloned();
+        if comments_field.is_some() {
+            if self.table.header.compressed {
+                bail!(TableError::Compressed)
+            }
+            if !self.table.record_header.is_fixed_length() {
+                bail!(TableError::VariableLength)
+            }
+        }
+        let trim = self.table.record_header.trim_mode();
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .from_path(csv_path)?;
+
+        // parse and validate every decision first
+        let mut decisions = Vec::new();
+        for item in csv_reader.records() {
+            let item = item?;
+            let row = item.position().map_or(0, |v| v.line());
+            let column = |i: usize| item.get(i).unwrap_or("").trim();
+
+            // parse decision
+            let index = match parse_index(column(0)) {
+                Ok(v) => v,
+                Err(e) => bail!("row {}: invalid index: {}", row, e)
+            };
+            if index >= self.index.header.indexed_count {
+                bail!("row {}: index {} is out of bounds", row, index);
+            }
+            let match_flag: MatchFlag = match column(1).parse() {
+                Ok(v) => v,
+                Err(_) => bail!("row {}: invalid match flag \"{}\"", row, column(1))
+            };
+            let spent_time = match column(2) {
+                "" => 0,
+                v => match v.parse::<u64>() {
+                    Ok(v) => v,
+                    Err(e) => bail!("row {}: invalid track time \"{}\": {}", row, v, e)
+                }
+            };
+            let comments = match &comments_field {
+                Some(field) => {
+                    if index >= self.table.header.record_count {
+                        bail!("row {}: record index {} is out of bounds", row, index);
+                    }
+                    let comments = Value::Str(column(3).to_string());
+                    if let Err(e) = field.validate_value_with(&comments, trim) {
+                        bail!("row {}: error saving field \"{}\": {}", row, COMMENTS_FIELD, e);
+                    }
+                    Some(comments)
+                },
+                None => None
+            };
+            decisions.push((row, index, IndexData{match_flag, spent_time}, comments));
+        }
+
+        // apply decisions, rolling back the applied writes on error
+        let mut applied = Vec::new();
+        for (row, index, data, comments) in decisions.iter() {
+            if let Err(e) = self.apply_decision(*index, data, comments.as_ref(), &mut applied) {
+                self.rollback_decisions(&applied)?;
+                bail!("row {}: {}", row, e);
+            }
+        }
+        Ok(decisions.len() as u64)
+    }
+
+    /// Applies a single decision and keeps the original data of every
+    /// write done so it can be rolled back.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Value index.
+    /// * `data` - Decision data to save.
+    /// * `comments` - Comments to save into the table comments field.
+    /// * `applied` - Applied writes along their original data.
+    fn apply_decision(&mut self, index: u64, data: &IndexData, comments: Option<&Value>, applied: &mut Vec<AppliedWrite>) -> Result<()> {
+        let original = match self.index.value(index)? {
+            Some(v) => v.data,
+            None => bail!("index {} is out of bounds", index)
+        };
+        self.index.save_data(index, data)?;
+        applied.push(AppliedWrite::Index(index, original));
+        if let Some(comments) = comments {
+            let pos = self.comments_pos(index)?;
+            let mut buf = vec![0u8; self.table.record_header.field_span(COMMENTS_FIELD)?.1 as usize];
+            let mut reader = self.table.new_reader()?;
+            reader.seek(SeekFrom::Start(pos))?;
+            reader.read_exact(&mut buf)?;
+            self.table.update_field(index, COMMENTS_FIELD, comments)?;
+            applied.push(AppliedWrite::Comments(index, buf));
+        }
+        Ok(())
+    }
+
+    /// Restores the original data of applied decision writes in reverse
+    /// order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `applied` - Applied writes along their original data.
+    fn rollback_decisions(&mut self, applied: &[AppliedWrite]) -> Result<()> {
+        for write in applied.iter().rev() {
+            match write {
+                AppliedWrite::Index(index, data) => self.index.save_data(*index, data)?,
+                AppliedWrite::Comments(index, buf) => {
+                    let pos = self.comments_pos(*index)?;
+                    let mut writer = self.table.new_writer(false)?;
+                    writer.seek(SeekFrom::Start(pos))?;
+                    writer.write_all(buf)?;
+                    writer.flush()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the comments field byte position within the table file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    fn comments_pos(&self, index: u64) -> Result<u64> {
+        let offset = self.table.record_header.field_offset(COMMENTS_FIELD)?;
+        Ok(self.table.calc_record_pos(index) + self.table.record_prefix_size() + offset)
+    }
+}
+
+/// Decision write applied by [Source::import_decisions] along the
+/// original data needed to roll it back.
+enum AppliedWrite {
+    /// Index value data write.
+    Index(u64, IndexData),
+
+    /// Table comments field write holding the original field bytes.
+    Comments(u64, Vec<u8>)
 }
 
 #[cfg(test)]
@@ -375,11 +698,12 @@ mod tests {
     use super::*;
     use super::test_helper::*;
     // use crate::test_helper::*;
-    use crate::db::indexer::test_helper::{create_fake_index};
-    use crate::db::table::test_helper::create_fake_table;
-    use crate::db::indexer::header::{Header as IndexHeader};
+    use crate::db::indexer::test_helper::{create_fake_index, create_fake_input, fake_input_bytes};
+    use crate::db::table::test_helper::{create_fake_table, add_fields};
+    use crate::db::indexer::header::InputType;
     use crate::db::table::header::{Header as TableHeader};
-    use crate::db::table::record::header::{Header as RecordHeader};
+    use crate::db::table::record::header::{Header as RecordHeader, FieldType};
+    use crate::test_helper::create_file_with_bytes;
 
     mod source_join_item {
         use super::*;
@@ -411,4 +735,357 @@ mod tests {
             });
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn extract_by_flag() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, false)?;
+            let records = create_fake_table(&source.table.path, false)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+
+            // mark the third record as matched too
+            source.index.save_data(2, &IndexData{
+                match_flag: MatchFlag::Yes,
+                spent_time: 12
+            })?;
+
+            // test
+            let out = dir.path().join("yes.fmtable");
+            assert_eq!(2, source.extract_by_flag(MatchFlag::Yes, &out)?);
+            let table = Table::from_file(out)?;
+            assert_eq!(source.table.record_header, table.record_header);
+            assert_eq!(2, table.header.record_count);
+            assert_eq!(Some(&records[0]), table.record(0)?.as_ref());
+            assert_eq!(Some(&records[2]), table.record(1)?.as_ref());
+            assert_eq!(None, table.record(2)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn extract_by_flag_without_matches() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, true)?;
+            create_fake_table(&source.table.path, true)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+
+            let out = dir.path().join("yes.fmtable");
+            assert_eq!(0, source.extract_by_flag(MatchFlag::Yes, &out)?);
+            let table = Table::from_file(out)?;
+            assert_eq!(0, table.header.record_count);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn extract_by_flag_same_file() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, false)?;
+            create_fake_table(&source.table.path, false)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            let table_bytes = std::fs::read(&source.table.path)?;
+            let index_bytes = std::fs::read(&source.index.index_path)?;
+
+            // test
+            let table_name = source.table.path.file_name().unwrap();
+            let index_name = source.index.index_path.file_name().unwrap();
+            let outs = [
+                source.table.path.clone(),
+                dir.path().join(".").join(table_name),
+                dir.path().join(".").join(index_name)
+            ];
+            for out in outs.iter() {
+                match source.extract_by_flag(MatchFlag::Yes, out) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!("source files can't be the same as the target files", e.to_string())
+                }
+            }
+            assert_eq!(table_bytes, std::fs::read(&source.table.path)?);
+            assert_eq!(index_bytes, std::fs::read(&source.index.index_path)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn extract_by_flag_compressed() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, false)?;
+            create_fake_table(&source.table.path, false)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            let records: Vec<Result<Record>> = source.table.read_records()?.collect();
+            Table::write_compressed_from_iter(&source.table.path, &source.table.record_header, records.into_iter())?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+
+            // test
+            let out = dir.path().join("yes.fmtable");
+            match source.extract_by_flag(MatchFlag::Yes, &out) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(TableError::Compressed.to_string(), e.to_string())
+            }
+            assert!(!out.exists());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn import_decisions() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, true)?;
+            create_fake_table(&source.table.path, true)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            let csv_path = dir.path().join("decisions.csv");
+            create_file_with_bytes(&csv_path, b"index,flag,track_time,comments\n\
+                1,Y,150,good\n\
+                3,No,20,\n\
+                0,s,,skipped\n")?;
+
+            // test
+            assert_eq!(3, source.import_decisions(&csv_path)?);
+            let expected = [
+                (MatchFlag::Skip, 0),
+                (MatchFlag::Yes, 150),
+                (MatchFlag::None, 0),
+                (MatchFlag::No, 20)
+            ];
+            for (index, (match_flag, spent_time)) in expected.into_iter().enumerate() {
+                let value = source.index.value(index as u64)?.unwrap();
+                assert_eq!(IndexData{match_flag, spent_time}, value.data);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn import_decisions_with_comments() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, true)?;
+            let mut header = RecordHeader::new();
+            header.add("foo", FieldType::I32)?;
+            header.add(COMMENTS_FIELD, FieldType::Str(10))?;
+            let records = (0..4).map(|_| header.new_record());
+            Table::write_from_iter(&source.table.path, &header, records)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            let csv_path = dir.path().join("decisions.csv");
+            create_file_with_bytes(&csv_path, b"index,flag,track_time,comments\n2,N,7,too far\n")?;
+
+            // test
+            assert_eq!(1, source.import_decisions(&csv_path)?);
+            let record = source.table.record(2)?.unwrap();
+            assert_eq!(Some(&Value::Str("too far".to_string())), record.get(COMMENTS_FIELD));
+            let value = source.index.value(2)?.unwrap();
+            assert_eq!(MatchFlag::No, value.data.match_flag);
+
+            // oversized comments are rejected before applying any decision
+            create_file_with_bytes(&csv_path, b"index,flag,track_time,comments\n1,Y,7,ok\n3,N,7,way too long\n")?;
+            match source.import_decisions(&csv_path) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "row 3: error saving field \"comments\": string value (12 bytes) exceeds field size (10 bytes)",
+                    e.to_string()
+                )
+            }
+            let record = source.table.record(1)?.unwrap();
+            assert_eq!(Some(&Value::Str("".to_string())), record.get(COMMENTS_FIELD));
+            let value = source.index.value(1)?.unwrap();
+            assert_eq!(MatchFlag::None, value.data.match_flag);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn import_decisions_rollback() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, true)?;
+            let mut header = RecordHeader::new();
+            header.add("foo", FieldType::I32)?;
+            header.add(COMMENTS_FIELD, FieldType::Str(10))?;
+            let records = (0..4).map(|_| header.new_record());
+            Table::write_from_iter(&source.table.path, &header, records)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+
+            // truncate the last record so writing its decision fails
+            let file = std::fs::OpenOptions::new().write(true).open(&source.table.path)?;
+            file.set_len(source.table.calc_record_pos(3))?;
+            drop(file);
+            let csv_path = dir.path().join("decisions.csv");
+            create_file_with_bytes(&csv_path, b"index,flag,track_time,comments\n1,Y,7,ok\n3,N,7,nope\n")?;
+
+            // test
+            match source.import_decisions(&csv_path) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("row 3: failed to fill whole buffer", e.to_string())
+            }
+            let record = source.table.record(1)?.unwrap();
+            assert_eq!(Some(&Value::Str("".to_string())), record.get(COMMENTS_FIELD));
+            let value = source.index.value(1)?.unwrap();
+            assert_eq!(IndexData{match_flag: MatchFlag::None, spent_time: 0}, value.data);
+            let value = source.index.value(3)?.unwrap();
+            assert_eq!(IndexData{match_flag: MatchFlag::None, spent_time: 0}, value.data);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn import_decisions_with_invalid_flag() {
+        with_tmpdir_and_source(&|dir, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, true)?;
+            create_fake_table(&source.table.path, true)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            let csv_path = dir.path().join("decisions.csv");
+            create_file_with_bytes(&csv_path, b"index,flag,track_time,comments\n1,Y,1,\n2,maybe,1,\n")?;
+
+            // test
+            match source.import_decisions(&csv_path) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("row 3: invalid match flag \"maybe\"", e.to_string())
+            }
+
+            // the valid rows before the invalid one aren't applied
+            let value = source.index.value(1)?.unwrap();
+            assert_eq!(IndexData{match_flag: MatchFlag::None, spent_time: 0}, value.data);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn progress() {
+        with_tmpdir_and_source(&|_, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, true)?;
+            create_fake_table(&source.table.path, true)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            assert_eq!((0, 4), source.progress()?);
+
+            // record a few decisions
+            source.index.save_data(1, &IndexData{
+                match_flag: MatchFlag::Yes,
+                spent_time: 10
+            })?;
+            source.index.save_data(3, &IndexData{
+                match_flag: MatchFlag::Skip,
+                spent_time: 5
+            })?;
+            assert_eq!((2, 4), source.progress()?);
+
+            // undoing a decision
+            source.index.save_data(1, &IndexData{
+                match_flag: MatchFlag::None,
+                spent_time: 0
+            })?;
+            assert_eq!((1, 4), source.progress()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn progress_empty() {
+        with_tmpdir_and_source(&|_, source| -> Result<()> {
+            source.index.header.indexed = true;
+            source.index.header.indexed_count = 0;
+            source.index.new_index_writer(true)?;
+            source.index.save_header()?;
+
+            // test
+            assert_eq!((0, 0), source.progress()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn init_reindex_truncated_input() {
+        with_tmpdir_and_source(&|_, source| -> Result<()> {
+            create_fake_input(&source.index.input_path)?;
+            source.index.header.input_type = InputType::CSV;
+            source.index.index()?;
+            let input = fake_input_bytes();
+            create_file_with_bytes(&source.index.input_path, &input[..input.len() - 33])?;
+            add_fields(&mut source.table.record_header)?;
+
+            // test
+            source.index.header = IndexHeader::new();
+            source.index.header.input_type = InputType::CSV;
+            source.init(true, false)?;
+            assert!(source.index.header.indexed);
+            assert_eq!(3, source.index.header.indexed_count);
+            assert_eq!(3, source.table.header.record_count);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn record() {
+        with_tmpdir_and_source(&|_, source| -> Result<()> {
+            create_fake_index(&source.index.index_path, false)?;
+            create_fake_table(&source.table.path, false)?;
+            source.index.load_header_from(&mut source.index.new_index_reader()?)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            let mut record = source.table.record_header.new_record()?;
+            record.set("foo", Value::I32(7))?;
+            source.table.save_record(1, &record, false)?;
+
+            // test an existing record with a default value
+            let mut expected = source.table.record_header.new_record()?;
+            expected.set("foo", Value::I32(7))?;
+            expected.set("bar", Value::Str("".to_string()))?;
+            assert_eq!(Some(expected), source.record(1)?);
+
+            // test past the end
+            assert_eq!(None, source.record(4)?);
+            assert_eq!(None, source.data(4)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn get_with_neighbors() {
+        with_tmpdir_and_source(&|_, source| -> Result<()> {
+            create_fake_input(&source.index.input_path)?;
+            source.index.header.input_type = InputType::CSV;
+            source.index.index()?;
+            create_fake_table(&source.table.path, true)?;
+            source.table.load_headers_from(&mut source.table.new_reader()?)?;
+            source.index.save_data(1, &IndexData{
+                match_flag: MatchFlag::Yes,
+                spent_time: 10
+            })?;
+            source.index.save_data(2, &IndexData{
+                match_flag: MatchFlag::No,
+                spent_time: 5
+            })?;
+
+            // test around decided records
+            let payload = source.get_with_neighbors(2)?;
+            assert_eq!(source.data(2)?, payload.data);
+            assert_eq!(Some(0), payload.prev_pending);
+            assert_eq!(Some(3), payload.next_pending);
+            let payload = source.get_with_neighbors(1)?;
+            assert_eq!(Some(MatchFlag::Yes), payload.data.map(|v| v.index.match_flag));
+            assert_eq!(Some(0), payload.prev_pending);
+            assert_eq!(Some(3), payload.next_pending);
+
+            // test on the edges
+            let payload = source.get_with_neighbors(0)?;
+            assert_eq!(source.data(0)?, payload.data);
+            assert_eq!(None, payload.prev_pending);
+            assert_eq!(Some(3), payload.next_pending);
+            let payload = source.get_with_neighbors(3)?;
+            assert_eq!(Some(0), payload.prev_pending);
+            assert_eq!(None, payload.next_pending);
+
+            // test past the end
+            let payload = source.get_with_neighbors(4)?;
+            assert_eq!(None, payload.data);
+            assert_eq!(Some(3), payload.prev_pending);
+            assert_eq!(None, payload.next_pending);
+            Ok(())
+        });
+    }
+}
@@ -1,21 +1,41 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "tokio")]
+pub mod async_sink;
 pub mod header;
+#[cfg(feature = "mmap")]
+pub mod mapped;
+pub mod reader;
 pub mod record;
+pub mod transaction;
+pub mod writer;
 
 use anyhow::{bail, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
 use regex::Regex;
+use serde_json::{Map as JSMap, Value as JSValue};
+use std::collections::HashMap;
+use sha3::{Digest, Sha3_256};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::fs::{File, OpenOptions};
-use std::io::{Seek, SeekFrom, Read, Write, BufReader, BufWriter};
-use std::path::PathBuf;
-use crate::{file_size, fill_file};
-use crate::error::TableError;
-use crate::traits::{ByteSized, LoadFrom, WriteTo};
+use std::io::{self, Seek, SeekFrom, Read, Write, BufReader, BufWriter};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use crate::{file_size, fill_file, is_same_file};
+use crate::error::{ParseError, TableError};
+use crate::db::indexer::header::HASH_SIZE;
+use crate::traits::{ByteSized, LoadFrom, ReadFrom, WriteTo};
 use header::Header;
-use record::header::{Header as RecordHeader};
-use record::Record;
+use record::header::{Field, FieldType, Header as RecordHeader, TrimMode};
+use record::{Record, Value};
+use reader::RecordReader;
 
 /// Table engine version.
-pub const VERSION: u32 = 1;
+pub const VERSION: u32 = 2;
 
 /// Table file extension.
 pub const FILE_EXTENSION: &str = "fmtable";
@@ -40,6 +60,19 @@ impl Display for Status{
     }
 }
 
+/// Table validation report.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValidationReport {
+    /// Checked record count.
+    pub checked: u64,
+
+    /// Records that couldn't be decoded as `(index, error message)` sorted by index.
+    pub errors: Vec<(u64, String)>,
+
+    /// `true` when the validation stopped before checking every record.
+    pub truncated: bool
+}
+
 /// Table engine.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Table {
@@ -50,7 +83,11 @@ pub struct Table {
     pub header: Header,
 
     // Record header. It contains information about the fields.
-    pub record_header: RecordHeader
+    pub record_header: RecordHeader,
+
+    /// Max byte size a variable length record can declare to be decoded,
+    /// no limit when `None`. Fixed length records aren't checked.
+    pub max_record_bytes: Option<u64>
 }
 
 impl Table {
@@ -70,7 +107,8 @@ impl Table {
         Ok(Self{
             path,
             header: Header::new(name)?,
-            record_header: RecordHeader::new()
+            record_header: RecordHeader::new(),
+            max_record_bytes: None
         })
     }
 
@@ -90,6 +128,83 @@ impl Table {
         }
     }
 
+    /// Opens a table by reading only its headers, records are never read
+    /// until requested. Unlike [Self::from_file], the file size isn't
+    /// validated against the header record count.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Table file path.
+    pub fn open_lazy(path: PathBuf) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(&path)?);
+        Self::open_lazy_from(path, &mut reader)
+    }
+
+    /// Same as [Self::open_lazy] but reads the headers from a reader
+    /// instead of opening the table file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Table file path.
+    /// * `reader` - Table file byte reader.
+    pub fn open_lazy_from(path: PathBuf, reader: &mut (impl Read + Seek)) -> Result<Self> {
+        let mut table = Self::new(path, "")?;
+        table.load_headers_from(reader)?;
+        Ok(table)
+    }
+
+    /// Returns the complete record count stored on the table file by
+    /// using its size, so it doesn't read any record.
+    pub fn record_count(&self) -> Result<u64> {
+        if self.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        let data_size = file_size(&self.path)?.saturating_sub(self.calc_record_pos(0));
+        Ok(data_size / self.record_size())
+    }
+
+    /// Returns the exact byte size of an uncompressed table file holding
+    /// a number of records, headers included.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `record_count` - Record count.
+    pub fn expected_size(&self, record_count: u64) -> u64 {
+        self.calc_record_pos(record_count)
+    }
+
+    /// Same as [Self::record_count] but fails when the table file holds a
+    /// partial record or is smaller than its headers, since it means the
+    /// file is corrupted.
+    pub fn actual_record_count(&self) -> Result<u64> {
+        if self.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        let real_size = file_size(&self.path)?;
+        let headers_size = self.calc_record_pos(0);
+        if real_size < headers_size {
+            bail!("table file size {} is smaller than its headers size {}", real_size, headers_size);
+        }
+        let data_size = real_size - headers_size;
+        let remainder = data_size % self.record_size();
+        if remainder > 0 {
+            bail!("table file has {} trailing bytes after the last complete record", remainder);
+        }
+        Ok(data_size / self.record_size())
+    }
+
     /// Returns a table file buffered reader.
     pub fn new_reader(&self) -> Result<BufReader<File>> {
         let file = File::open(&self.path)?;
@@ -117,8 +232,133 @@ impl Table {
     /// 
     /// * `index` - Record index.
     pub fn calc_record_pos(&self, index: u64) -> u64 {
-        let data_size = self.record_header.record_byte_size() as u64;
-        Header::BYTES as u64 + self.record_header.size_as_bytes() + index * data_size
+        self.header.size_as_bytes() + self.record_header.size_as_bytes() + index * self.record_size()
+    }
+
+    /// Returns the byte size of the record prefix, the user flags byte
+    /// when enabled.
+    pub fn record_prefix_size(&self) -> u64 {
+        if self.header.user_flags {
+            return u8::BYTES as u64;
+        }
+        0
+    }
+
+    /// Clears the stored record data checksum and saves the headers when
+    /// it was set, so in place writes don't leave a stale checksum. Must
+    /// be called before the record data is modified.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    fn invalidate_checksum_into(&mut self, writer: &mut (impl Write + Seek)) -> Result<()> {
+        if self.header.checksum.is_some() {
+            self.header.checksum = None;
+            self.save_headers_into(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the byte size a record takes within the table file
+    /// including its prefix.
+    pub fn record_size(&self) -> u64 {
+        self.record_prefix_size() + self.record_header.record_byte_size()
+    }
+
+    /// Reads a record from a reader positioned at the record prefix.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    fn read_record_from(&self, reader: &mut impl Read) -> Result<Record> {
+        if self.header.user_flags {
+            u8::read_from(reader)?;
+        }
+        self.record_header.read_record_with_max(reader, self.max_record_bytes)
+    }
+
+    /// Validates the table has user flags enabled and the record exists.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    fn validate_flags_access(&self, index: u64) -> Result<()> {
+        if !self.header.user_flags {
+            bail!("user flags are disabled on this table");
+        }
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        if index >= self.header.record_count {
+            bail!("record index {} is out of bounds", index);
+        }
+        Ok(())
+    }
+
+    /// Reads the user flags of a record.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    pub fn get_flags(&self, index: u64) -> Result<u8> {
+        self.validate_flags_access(index)?;
+        let mut reader = self.new_reader()?;
+        reader.seek(SeekFrom::Start(self.calc_record_pos(index)))?;
+        u8::read_from(&mut reader)
+    }
+
+    /// Overwrites the user flags of a record.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    /// * `flags` - User flags.
+    pub fn set_flags(&mut self, index: u64, flags: u8) -> Result<()> {
+        self.validate_flags_access(index)?;
+        let mut writer = self.new_writer(false)?;
+        self.invalidate_checksum_into(&mut writer)?;
+        writer.seek(SeekFrom::Start(self.calc_record_pos(index)))?;
+        flags.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the indexes of the records having every bit of a mask set
+    /// on their user flags.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mask` - User flags bit mask.
+    pub fn filter_by_flags(&self, mask: u8) -> Result<Vec<u64>> {
+        if !self.header.user_flags {
+            bail!("user flags are disabled on this table");
+        }
+        let mut indexes = Vec::new();
+        let mut reader = self.new_reader()?;
+        for index in 0..self.header.record_count {
+            reader.seek(SeekFrom::Start(self.calc_record_pos(index)))?;
+            if u8::read_from(&mut reader)? & mask == mask {
+                indexes.push(index);
+            }
+        }
+        Ok(indexes)
+    }
+
+    /// Checks whether a table file record header matches an expected
+    /// schema hash.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Table file path.
+    /// * `expected` - Expected schema hash as returned by `schema_hash`.
+    pub fn verify_schema(path: &Path, expected: [u8; HASH_SIZE]) -> Result<bool> {
+        let mut table = Self::new(path.to_path_buf(), "")?;
+        let mut reader = table.new_reader()?;
+        table.load_headers_from(&mut reader)?;
+        Ok(table.record_header.schema_hash()? == expected)
     }
 
     /// Get the record's headers.
@@ -143,9 +383,15 @@ impl Table {
         if self.record_header.len() < 1 {
             bail!(TableError::NoFields)
         }
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
 
         if self.header.record_count > index {
-            let pos = self.calc_record_pos(index);
+            let pos = self.calc_record_pos(index) + self.record_prefix_size();
             reader.seek(SeekFrom::Start(pos))?;
             return Ok(Some(self.record_header.read_record(reader)?));
         }
@@ -162,7 +408,18 @@ impl Table {
         self.seek_record_from(&mut reader, index)
     }
 
-    /// Updates or append a record into a writer.
+    /// Read the last record from the table file, returns `None` when
+    /// the table is empty.
+    pub fn last_record(&self) -> Result<Option<Record>> {
+        if self.header.record_count < 1 {
+            return Ok(None);
+        }
+        self.record(self.header.record_count - 1)
+    }
+
+    /// Updates or append a record into a writer. The record is encoded
+    /// before anything is written, so an invalid record leaves the writer
+    /// untouched.
     /// 
     /// # Arguments
     /// 
@@ -175,14 +432,32 @@ impl Table {
         if self.record_header.len() < 1 {
             bail!(TableError::NoFields)
         }
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
         if index > self.header.record_count {
             bail!("can't write or append the record, the table file is too small");
         }
 
-        // seek and write record
+        // encode the record first so nothing is written on error
+        let mut buf = Vec::with_capacity(self.record_header.record_byte_size() as usize);
+        self.record_header.write_record(&mut buf, record)?;
+
+        // seek and write record, appended records get empty user flags
+        self.invalidate_checksum_into(writer)?;
         let pos = self.calc_record_pos(index);
         writer.seek(SeekFrom::Start(pos))?;
-        self.record_header.write_record(writer, &record)?;
+        if self.header.user_flags {
+            if index < self.header.record_count {
+                writer.seek(SeekFrom::Current(self.record_prefix_size() as i64))?;
+            } else {
+                0u8.write_to(writer)?;
+            }
+        }
+        writer.write_all(&buf)?;
         
         // exit when no append
         if index < self.header.record_count {
@@ -211,6 +486,73 @@ impl Table {
         Ok(())
     }
 
+    /// Overwrites a single field value of an existing record in place
+    /// without rewriting the rest of the record.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    /// * `name` - Field name.
+    /// * `value` - New field value.
+    pub fn update_field(&mut self, index: u64, name: &str, value: &Value) -> Result<()> {
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        if index >= self.header.record_count {
+            bail!("record index {} is out of bounds", index);
+        }
+        let field = match self.record_header.get(name) {
+            Some(v) => v.clone(),
+            None => bail!("field \"{}\" doesn't exists within the header", name)
+        };
+
+        // seek and write field value
+        let pos = self.calc_record_pos(index) + self.record_prefix_size() + self.record_header.field_offset(name)?;
+        let mut writer = self.new_writer(false)?;
+        self.invalidate_checksum_into(&mut writer)?;
+        writer.seek(SeekFrom::Start(pos))?;
+        let trim = self.record_header.trim_mode();
+        if let Err(e) = field.write_value_endian(&mut writer, value, trim, self.record_header.endianness()) {
+            bail!("error saving field \"{}\": {}", name, e);
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Overwrites an existing record in place by using
+    /// [Self::save_record_into], so an invalid record leaves the table
+    /// file untouched, and the record user flags are preserved. Unlike
+    /// [Self::save_record], the record can't be appended and its fields
+    /// must match the header fields.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    /// * `record` - Record to write, its fields must match the header fields order.
+    pub fn overwrite_record(&mut self, index: u64, record: &Record) -> Result<()> {
+        if self.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if index >= self.header.record_count {
+            bail!("record index {} is out of bounds", index);
+        }
+        if record.len() != self.record_header.len() {
+            bail!(ParseError::FieldCountMismatch{
+                expected: self.record_header.len(),
+                got: record.len()
+            });
+        }
+        for (field, (name, _)) in self.record_header.iter().zip(record.iter()) {
+            if field.get_name() != name {
+                bail!("record field \"{}\" doesn't match the header field \"{}\"", name, field.get_name());
+            }
+        }
+        self.save_record(index, record, false)
+    }
+
     /// Perform a healthckeck over the table file by reading
     /// the headers and checking the file size.
     pub fn healthcheck(&mut self) -> Result<Status> {
@@ -250,12 +592,15 @@ impl Table {
             }
         };
 
-        // validate corrupted table
-        let real_size = file_size(&self.path)?;
-        let expected_size = self.calc_record_pos(self.header.record_count);
-        if real_size != expected_size {
-            // sizes don't match, the file is corrupted
-            return Ok(Status::Corrupted);
+        // validate corrupted table, compressed and variable length record
+        // sizes can't be predicted
+        if !self.header.compressed && self.record_header.is_fixed_length() {
+            let real_size = file_size(&self.path)?;
+            let expected_size = self.expected_size(self.header.record_count);
+            if real_size != expected_size {
+                // sizes don't match, the file is corrupted
+                return Ok(Status::Corrupted);
+            }
         }
         
         // validate field count
@@ -315,6 +660,7 @@ impl Table {
 
         // create table file when required
         if should_create {
+            self.header.upgrade_version();
             let mut writer = self.new_writer(true)?;
             let size = self.calc_record_pos(self.header.record_count);
             fill_file(&self.path, size, true)?;
@@ -323,761 +669,2631 @@ impl Table {
         }
         Ok(())
     }
-}
-
-#[cfg(test)]
-pub mod test_helper {
-    use super::*;
-    use crate::test_helper::*;
-    use crate::db::table::record::header::{FieldType, Field};
-    use crate::db::table::record::value::{Value};
-    use crate::db::table::header::test_helper::build_header_bytes;
-    use tempfile::TempDir;
-
-    /// It's the size of a record header without any field.
-    pub const EMPTY_RECORD_HEADER_BYTES: usize = u32::BYTES;
-
-    /// Record header size generated by add_fields function.
-    pub const ADD_FIELDS_HEADER_BYTES: usize = Field::BYTES * 2 + u32::BYTES;
-
-    /// Record size generated by aADD_FIELDS_RECORD_BYTESdd_fields function.
-    pub const ADD_FIELDS_RECORD_BYTES: usize = 13;
-
-    /// Fake records bytes size generated by fake_records.
-    pub const FAKE_RECORDS_BYTES: usize = ADD_FIELDS_RECORD_BYTES * 3;
-
-    /// Fake index with fields byte size.
-    pub const FAKE_INDEX_BYTES: usize = Header::BYTES + ADD_FIELDS_HEADER_BYTES + FAKE_RECORDS_BYTES;
-
-    /// Byte slice that represents an empty record header.
-    pub const EMPTY_RECORD_HEADER_BYTE_SLICE: [u8; EMPTY_RECORD_HEADER_BYTES] = [
-        // field count
-        0, 0, 0, 0u8
-    ];
 
-    /// Byte slice to be generated by the record header generated by add_fields_function.
-    pub const ADD_FIELDS_HEADER_BYTE_SLICE: [u8; ADD_FIELDS_HEADER_BYTES] = [
-        // field count
-        0, 0, 0, 2u8,
+    /// Splits the record range into contiguous chunks, one per worker.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `parallelism` - Worker count.
+    fn record_chunks(&self, parallelism: usize) -> Vec<Range<u64>> {
+        let count = self.header.record_count;
+        let chunk_size = count.div_ceil(parallelism.max(1) as u64).max(1);
+        let mut chunks = Vec::new();
+        let mut start = 0u64;
+        while start < count {
+            let end = (start + chunk_size).min(count);
+            chunks.push(start..end);
+            start = end;
+        }
+        chunks
+    }
 
-        // foo field name value size
-        0, 0, 0, 3u8,
-        // foo field name value
-        102u8, 111u8, 111u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0,
-        // foo field type
-        4u8, 0, 0, 0, 0,
+    /// Executes a function over each record chunk using a thread pool. Each
+    /// chunk gets its own reader already positioned at the chunk's first
+    /// record, and the results are returned in chunk order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `parallelism` - Worker count, chunks are processed serially when `< 2`.
+    /// * `f` - Function to execute on each chunk.
+    fn process_chunks<T, F>(&self, parallelism: usize, f: F) -> Result<Vec<T>>
+    where
+        T: Send,
+        F: Fn(&mut BufReader<File>, Range<u64>) -> Result<T> + Sync + Send
+    {
+        if self.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
 
-        // bar field name value size
-        0, 0, 0, 3u8,
-        // bar field name value
-        98u8, 97u8, 114u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0,
-        // bar field type
-        12u8, 0, 0, 0, 5u8
-    ];
+        let chunks = self.record_chunks(parallelism);
+        let process = |range: Range<u64>| -> Result<T> {
+            let mut reader = self.new_reader()?;
+            reader.seek(SeekFrom::Start(self.calc_record_pos(range.start)))?;
+            f(&mut reader, range)
+        };
+        if parallelism < 2 {
+            return chunks.into_iter().map(process).collect();
+        }
+        let pool = ThreadPoolBuilder::new().num_threads(parallelism).build()?;
+        pool.install(|| chunks.into_par_iter().map(process).collect())
+    }
 
-    pub const FAKE_RECORDS_BYTE_SLICE: [u8; FAKE_RECORDS_BYTES] = [
-        // first record
-        // foo field
-        13u8, 246u8, 33u8, 122u8,
-        // bar field
-        0, 0, 0, 3u8, 97u8, 98u8, 99u8, 0, 0,
+    /// Decodes every record on the table and reports the ones that failed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `parallelism` - Worker count.
+    pub fn validate(&self, parallelism: usize) -> Result<ValidationReport> {
+        let chunks = self.process_chunks(parallelism, |reader, range| {
+            let mut errors = Vec::new();
+            for index in range {
+                if let Err(e) = self.read_record_from(reader) {
+                    errors.push((index, e.to_string()));
+
+                    // realign the reader with the next record
+                    reader.seek(SeekFrom::Start(self.calc_record_pos(index + 1)))?;
+                }
+            }
+            Ok(errors)
+        })?;
+
+        // merge chunk results
+        let mut report = ValidationReport{
+            checked: self.header.record_count,
+            errors: Vec::new(),
+            truncated: false
+        };
+        for mut errors in chunks {
+            report.errors.append(&mut errors);
+        }
+        Ok(report)
+    }
 
-        // second record
-        // foo field
-        20u8, 149u8, 141u8, 65u8,
-        // bar field
-        0, 0, 0, 4u8, 100u8, 102u8, 101u8, 103u8, 0,
+    /// Decodes the table records sequentially and stops as soon as
+    /// `max_errors` records failed, marking the report as truncated when
+    /// there were records left to check.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `max_errors` - Max error count to report, `0` means no limit so every record is checked.
+    pub fn validate_limited(&self, max_errors: usize) -> Result<ValidationReport> {
+        let mut chunks = self.process_chunks(1, |reader, range| {
+            let mut report = ValidationReport{
+                checked: 0,
+                errors: Vec::new(),
+                truncated: false
+            };
+            for index in range {
+                if max_errors > 0 && report.errors.len() >= max_errors {
+                    report.truncated = true;
+                    break;
+                }
+                report.checked += 1;
+                if let Err(e) = self.read_record_from(reader) {
+                    report.errors.push((index, e.to_string()));
 
-        // third record
-        // foo field
-        51u8, 29u8, 39u8, 30u8,
-        // bar field
-        0, 0, 0, 5u8, 104u8, 105u8, 49u8, 50u8, 51u8
-    ];
+                    // realign the reader with the next record
+                    reader.seek(SeekFrom::Start(self.calc_record_pos(index + 1)))?;
+                }
+            }
+            Ok(report)
+        })?;
+
+        // a single chunk is used, an empty table has none
+        Ok(chunks.pop().unwrap_or(ValidationReport{
+            checked: 0,
+            errors: Vec::new(),
+            truncated: false
+        }))
+    }
 
-    /// Add test fields into record header.
+    /// Reads every record on the table and executes a function on it.
     /// 
     /// # Arguments
     /// 
-    /// * `header` - Record header to add fields into.
-    pub fn add_fields(header: &mut RecordHeader) -> Result<()> {
-        header.add("foo", FieldType::I32)?;
-        header.add("bar", FieldType::Str(5))?;
-
+    /// * `parallelism` - Worker count.
+    /// * `f` - Function to execute on each record along with its index.
+    pub fn scan<F>(&self, parallelism: usize, f: F) -> Result<()>
+    where
+        F: Fn(u64, Record) -> Result<()> + Sync + Send
+    {
+        self.process_chunks(parallelism, |reader, range| {
+            for index in range {
+                let record = self.read_record_from(reader)?;
+                f(index, record)?;
+            }
+            Ok(())
+        })?;
         Ok(())
     }
 
-    /// Create fake records based on the fields added by add_fields.
+    /// Streams a string field values and returns `(max_len_used, declared_size)`
+    /// in bytes, useful to decide whether the field size can be shrunk.
     /// 
     /// # Arguments
     /// 
-    /// * `records` - Record vector to add records into.
-    pub fn fake_records() -> Result<Vec<Record>> {
-        let mut header = RecordHeader::new();
-        add_fields(&mut header)?;
-        let mut records = Vec::new();
+    /// * `name` - String field name.
+    pub fn str_field_usage(&self, name: &str) -> Result<(u32, u32)> {
+        let (index, declared_size) = match self.record_header.iter().position(|v| v.get_name() == name) {
+            Some(index) => match self.record_header.get_by_index(index).unwrap().get_type().str_size() {
+                Ok(size) => (index, size),
+                Err(e) => bail!("field \"{}\": {}", name, e)
+            },
+            None => bail!("field \"{}\" doesn't exists within the header", name)
+        };
+        let mut max_len = 0;
+        for record in self.read_records()? {
+            if let Some(Value::Str(v)) = record?.get_by_index(index) {
+                max_len = max_len.max(v.len() as u32);
+            }
+        }
+        Ok((max_len, declared_size))
+    }
 
-        // add first record
-        let mut record = header.new_record()?;
-        record.set_by_index(0, Value::I32(234234234i32));
-        record.set_by_index(1, Value::Str("abc".to_string()));
-        records.push(record);
+    /// Streams the table once and returns per field statistics as a JSON
+    /// object keyed by field name. Every field reports `count`, the non
+    /// default value count, and numeric fields also report the `min` and
+    /// `max` of those values, being `null` when there are none. Default
+    /// values are stored as zeros, so a stored zero counts as default.
+    pub fn field_stats(&self) -> Result<JSValue> {
+        // (stored default value, numeric field, count, min, max)
+        let mut stats = Vec::with_capacity(self.record_header.len());
+        for field in self.record_header.iter() {
+            let mut buf = Vec::with_capacity(field.value_byte_size());
+            field.write_value_with(&mut buf, &Value::Default, TrimMode::None)?;
+            let default = field.read_value(&mut &buf[..]).unwrap_or(Value::Default);
+            let numeric = matches!(field.get_type(),
+                FieldType::I8 | FieldType::I16 | FieldType::I32 | FieldType::I64 |
+                FieldType::U8 | FieldType::U16 | FieldType::U32 | FieldType::U64 |
+                FieldType::F32 | FieldType::F64);
+            stats.push((default, numeric, 0u64, None::<Value>, None::<Value>));
+        }
 
-        // add second record
-        let mut record = header.new_record()?;
-        record.set_by_index(0, Value::I32(345345345i32));
-        record.set_by_index(1, Value::Str("dfeg".to_string()));
-        records.push(record);
+        for record in self.read_records()? {
+            let record = record?;
+            for (index, (default, numeric, count, min, max)) in stats.iter_mut().enumerate() {
+                let value = match record.get_by_index(index) {
+                    Some(Value::Null) | Some(Value::Default) | None => continue,
+                    Some(v) if v == default => continue,
+                    Some(v) => v
+                };
+                *count += 1;
+                if !*numeric {
+                    continue;
+                }
+                if min.as_ref().is_none_or(|m| value < m) {
+                    *min = Some(value.clone());
+                }
+                if max.as_ref().is_none_or(|m| value > m) {
+                    *max = Some(value.clone());
+                }
+            }
+        }
 
-        // add third record
-        let mut record = header.new_record()?;
-        record.set_by_index(0, Value::I32(857548574i32));
-        record.set_by_index(1, Value::Str("hi123".to_string()));
-        records.push(record);
+        let mut obj = JSMap::new();
+        for (field, (_, numeric, count, min, max)) in self.record_header.iter().zip(stats) {
+            let mut field_obj = JSMap::new();
+            field_obj.insert("count".to_string(), count.into());
+            if numeric {
+                field_obj.insert("min".to_string(), min.map_or(JSValue::Null, Value::into_json));
+                field_obj.insert("max".to_string(), max.map_or(JSValue::Null, Value::into_json));
+            }
+            obj.insert(field.get_name().to_string(), JSValue::Object(field_obj));
+        }
+        Ok(JSValue::Object(obj))
+    }
 
-        Ok(records)
+    /// Generates a hash from the raw record data region of the table file.
+    pub fn data_checksum(&self) -> Result<[u8; HASH_SIZE]> {
+        let mut reader = self.new_reader()?;
+        reader.seek(SeekFrom::Start(self.calc_record_pos(0)))?;
+        let mut hasher = Sha3_256::new();
+        io::copy(&mut reader, &mut hasher)?;
+        Ok(hasher.finalize().into())
     }
 
-    /// Return a fake table file with fields as byte slice and the record count.
-    pub fn fake_table_with_fields() -> Result<([u8; FAKE_INDEX_BYTES], u64)> {
-        // init buffer
-        let mut buf = [0u8; FAKE_INDEX_BYTES];
-        let header_buf = build_header_bytes("my_table", 3245634545244324234u64);
-        copy_bytes(&mut buf, &header_buf, 0)?;
-        copy_bytes(&mut buf, &ADD_FIELDS_HEADER_BYTE_SLICE, Header::BYTES)?;
-        copy_bytes(&mut buf, &FAKE_RECORDS_BYTE_SLICE, Header::BYTES + ADD_FIELDS_HEADER_BYTES)?;
-        Ok((buf, 3))
+    /// Creates a record reader positioned at the first table record.
+    pub fn record_reader(&self) -> Result<RecordReader<BufReader<File>>> {
+        if self.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if self.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !self.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        let mut reader = self.new_reader()?;
+        reader.seek(SeekFrom::Start(self.calc_record_pos(0)))?;
+        Ok(RecordReader::with_prefix(reader, self.record_header.clone(), self.record_prefix_size() as usize))
     }
 
-    /// Write a fake table bytes into a writer.
+    /// Writes the table records as CSV and returns the exported record
+    /// count. The first line contains the field names and every value is
+    /// rendered by using [Value::to_display_string].
     /// 
     /// # Arguments
     /// 
     /// * `writer` - Byte writer.
-    /// * `unprocessed` - If `true` then build all records with MatchFlag::None.
-    pub fn write_fake_table(writer: &mut (impl Seek + Write), unprocessed: bool) -> Result<Vec<Record>> {
-        let mut records = Vec::new();
+    pub fn export_csv(&self, writer: &mut impl Write) -> Result<u64> {
+        let records = self.record_reader()?;
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record(self.record_header.iter().map(|v| v.get_name()))?;
+        let mut count = 0;
+        for record in records {
+            let record = record?;
+            csv_writer.write_record(record.iter().map(|(_, v)| v.to_display_string(None)))?;
+            count += 1;
+        }
+        csv_writer.flush()?;
+        Ok(count)
+    }
 
-        // write table header
-        let mut header = Header::new("my_table")?;
-        header.record_count = 4;
-        header.write_to(writer)?;
+    /// Reads every record on the table sequentially. This is the only way
+    /// to read records from a compressed table.
+    pub fn read_records(&self) -> Result<impl Iterator<Item = Result<Record>>> {
+        if self.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        let mut reader = self.new_reader()?;
+        reader.seek(SeekFrom::Start(self.calc_record_pos(0)))?;
+        let mut source: Box<dyn Read> = if self.header.compressed {
+            Box::new(GzDecoder::new(reader))
+        } else {
+            Box::new(reader)
+        };
+        let record_header = self.record_header.clone();
+        let user_flags = self.header.user_flags;
+        let max_record_bytes = self.max_record_bytes;
+        Ok((0..self.header.record_count).map(move |_| {
+            if user_flags {
+                u8::read_from(&mut source)?;
+            }
+            record_header.read_record_with_max(&mut source, max_record_bytes)
+        }))
+    }
 
-        // write record header
-        let mut record_header = RecordHeader::new();
-        add_fields(&mut record_header)?;
-        record_header.write_to(writer)?;
-        
-        // write first record
-        let mut record = record_header.new_record()?;
-        if !unprocessed {
-            record.set("foo", Value::I32(111i32))?;
-            record.set("bar", Value::Str("first".to_string()))?;
+    /// Writes records from an iterator into a new table file using the
+    /// file stem as table name, and returns the written record count. The
+    /// table file is removed on the first error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `out` - Target table file path.
+    /// * `header` - Record header.
+    /// * `records` - Records to write.
+    pub fn write_from_iter(out: &PathBuf, header: &RecordHeader, records: impl Iterator<Item = Result<Record>>) -> Result<u64> {
+        Self::write_from_iter_with(out, header, records, false)
+    }
+
+    /// Same as `write_from_iter` but the records are written as a gzip
+    /// stream. Compressed tables can only be read sequentially by using
+    /// `read_records`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `out` - Target table file path.
+    /// * `header` - Record header.
+    /// * `records` - Records to write.
+    pub fn write_compressed_from_iter(out: &PathBuf, header: &RecordHeader, records: impl Iterator<Item = Result<Record>>) -> Result<u64> {
+        Self::write_from_iter_with(out, header, records, true)
+    }
+
+    /// Creates a table file from a CSV file and returns the imported
+    /// record count. Each CSV cell is parsed into its field type, numeric
+    /// cells are coerced into the field type when they don't parse as is,
+    /// see [Value::coerce]. Empty cells are stored as [Value::Default], or
+    /// [Value::Null] on nullable fields, except for string fields. Fields
+    /// without a mapped column are left as default.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `out` - Target table file path.
+    /// * `path` - CSV file path, its first line must be the column names.
+    /// * `header` - Record header.
+    /// * `mapping` - CSV column names mapped to field names.
+    pub fn import_csv(out: &PathBuf, path: &Path, header: &RecordHeader, mapping: &HashMap<String, String>) -> Result<u64> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .from_path(path)?;
+
+        // resolve the mapped columns
+        let columns = reader.headers()?.clone();
+        let mut names: Vec<&String> = mapping.keys().collect();
+        names.sort();
+        let mut targets = Vec::new();
+        for column in names {
+            let index = match columns.iter().position(|v| v == column) {
+                Some(v) => v,
+                None => bail!("column \"{}\" doesn't exists within the CSV file", column)
+            };
+            let field = match header.get(&mapping[column]) {
+                Some(v) => v,
+                None => bail!("field \"{}\" doesn't exists within the header", mapping[column])
+            };
+            targets.push((index, column.as_str(), field));
         }
-        record_header.write_record(writer, &record)?;
-        records.push(record);
-        
-        // write second record date
-        let mut record = record_header.new_record()?;
-        if !unprocessed {
-            record.set("foo", Value::I32(222i32))?;
-            record.set("bar", Value::Str("2th".to_string()))?;
+
+        let records = reader.into_records().enumerate().map(|(row, item)| -> Result<Record> {
+            let item = item?;
+            let mut record = header.new_record()?;
+            for (index, column, field) in targets.iter() {
+                let cell = item.get(*index).unwrap_or("");
+                match Self::parse_csv_cell(field, cell) {
+                    Ok(v) => record.set(field.get_name(), v)?,
+                    Err(e) => bail!("row {}, column '{}': {}", row, column, e)
+                };
+            }
+            Ok(record)
+        });
+        Self::write_from_iter(out, header, records)
+    }
+
+    /// Parses a CSV cell into a field value. Numeric cells that don't
+    /// parse as the field type are coerced into it, the field type parse
+    /// error is returned when the coercion fails too.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `field` - Target field.
+    /// * `cell` - CSV cell.
+    fn parse_csv_cell(field: &Field, cell: &str) -> Result<Value> {
+        let field_type = field.get_type();
+        if cell.is_empty() && !matches!(field_type, FieldType::Str(_)) {
+            if field.is_nullable() {
+                return Ok(Value::Null);
+            }
+            return Ok(Value::Default);
         }
-        record_header.write_record(writer, &record)?;
-        records.push(record);
-        
-        // write third record date
-        let mut record = record_header.new_record()?;
-        if !unprocessed {
-            record.set("foo", Value::I32(333i32))?;
-            record.set("bar", Value::Str("3rd".to_string()))?;
+        let e = match field_type.parse_value(cell) {
+            Ok(v) => return Ok(v),
+            Err(e) => e
+        };
+        if let Ok(v) = cell.parse::<f64>() {
+            if let Ok(v) = Value::F64(v).coerce(field_type) {
+                return Ok(v);
+            }
         }
-        record_header.write_record(writer, &record)?;
-        records.push(record);
+        bail!("can't parse \"{}\" as {}: {}", cell, field_type.type_name(), e)
+    }
 
-        // write fourth record date
-        let mut record = record_header.new_record()?;
-        if !unprocessed {
-            record.set("foo", Value::I32(444i32))?;
-            record.set("bar", Value::Str("4th".to_string()))?;
+    /// Writes records from an iterator into a new table file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `out` - Target table file path.
+    /// * `header` - Record header.
+    /// * `records` - Records to write.
+    /// * `compressed` - Writes the records as a gzip stream when true.
+    fn write_from_iter_with(out: &PathBuf, header: &RecordHeader, records: impl Iterator<Item = Result<Record>>, compressed: bool) -> Result<u64> {
+        if header.len() < 1 {
+            bail!(TableError::NoFields)
         }
-        record_header.write_record(writer, &record)?;
-        records.push(record);
+        let name = match out.file_stem() {
+            Some(v) => v.to_string_lossy().to_string(),
+            None => bail!("invalid table file path \"{}\"", out.to_string_lossy())
+        };
+        let mut table = Self::new(out.clone(), &name)?;
+        table.record_header = header.clone();
+        table.header.compressed = compressed;
 
-        Ok(records)
+        match table.write_records(records) {
+            Ok(count) => Ok(count),
+            Err(e) => {
+                // remove the partially written table file
+                if out.exists() {
+                    std::fs::remove_file(out)?;
+                }
+                Err(e)
+            }
+        }
     }
 
-    /// Create a fake table file.
+    /// Creates the table file and writes the headers and records into it.
+    /// Records are validated before being written so errors include the
+    /// row (record index) and field that caused them.
     /// 
     /// # Arguments
     /// 
-    /// * `path` - Table file path.
-    /// * `empty` - If `true` then build all records as empty records.
-    pub fn create_fake_table(path: &PathBuf, unprocessed: bool) -> Result<Vec<Record>> {
+    /// * `records` - Records to write.
+    fn write_records(&mut self, records: impl Iterator<Item = Result<Record>>) -> Result<u64> {
         let file = OpenOptions::new()
             .create(true)
             .truncate(true)
             .write(true)
-            .open(path)?;
+            .open(&self.path)?;
         let mut writer = BufWriter::new(file);
-        let records = write_fake_table(&mut writer, unprocessed)?;
-        writer.flush()?;
+        self.header.record_count = 0;
+        self.save_headers_into(&mut writer)?;
+        writer.seek(SeekFrom::Start(self.calc_record_pos(0)))?;
+
+        // write records
+        if self.header.compressed {
+            let mut encoder = GzEncoder::new(&mut writer, Compression::default());
+            self.write_record_stream(&mut encoder, records)?;
+            encoder.finish()?;
+        } else {
+            self.write_record_stream(&mut writer, records)?;
+        }
 
-        Ok(records)
+        // save the final record count
+        self.save_headers_into(&mut writer)?;
+        writer.flush()?;
+        Ok(self.header.record_count)
     }
 
-    /// Execute a function with both a temp directory and a new table.
+    /// Validates and writes records into a writer while counting them.
     /// 
     /// # Arguments
     /// 
-    /// * `f` - Function to execute.
-    pub fn with_tmpdir_and_table(f: &impl Fn(&TempDir, &mut Table) -> Result<()>) {
-        let sub = |dir: &TempDir| -> Result<()> {
-            // create Table and execute
-            let mut table = Table::new(
-                dir.path().join("t.fmtable"),
-                "my_table"
-            )?;
-
-            // execute function
-            match f(&dir, &mut table) {
-                Ok(_) => Ok(()),
-                Err(e) => bail!(e)
+    /// * `writer` - Byte writer.
+    /// * `records` - Records to write.
+    fn write_record_stream(&mut self, writer: &mut impl Write, records: impl Iterator<Item = Result<Record>>) -> Result<()> {
+        for record in records {
+            let record = record?;
+            let row = self.header.record_count;
+            if self.record_header.len() != record.len() {
+                bail!(ParseError::FieldCountMismatch{
+                    expected: self.record_header.len(),
+                    got: record.len()
+                });
             }
-        };
-        with_tmpdir(&sub)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use test_helper::*;
-    use std::io::Cursor;
-    use crate::test_helper::*;
-    use crate::db::table::record::Value;
-    use crate::db::table::header::test_helper::build_header_bytes;
-
-    #[test]
-    fn file_extension_regex() {
-        let rx = Table::file_extension_regex();
-        assert!(rx.is_match("hello.fmtable"), "expected to match \"hello.fmtable\" but got false");
-        assert!(rx.is_match("/path/to/hello.fmtable"), "expected to match \"/path/to/hello.fmtable\" but got false");
-        assert!(!rx.is_match("hello.table"), "expected to not match \"hello.table\" but got true");
+            for (index, field) in self.record_header.iter().enumerate() {
+                if let Some(value) = record.get_by_index(index) {
+                    if let Err(e) = field.validate_value_with(value, self.record_header.trim_mode()) {
+                        bail!("row {}, field '{}': {}", row, field.get_name(), e);
+                    }
+                }
+            }
+            self.record_header.write_record(writer, &record)?;
+            self.header.record_count += 1;
+        }
+        Ok(())
     }
 
-    #[test]
-    fn new() {
-        let header = Header::new("my_table").unwrap();
-        let expected = Table{
-            path: "my_table.fmtable".into(),
-            header,
-            record_header: RecordHeader::new()
-        };
-        match Table::new("my_table.fmtable".into(), "my_table") {
-            Ok(v) => assert_eq!(expected, v),
-            Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+    /// Copies a table file into a new table file renaming a field. The
+    /// record layout doesn't change on a rename, so the record data is
+    /// copied as is without decoding it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `old` - Source table file path.
+    /// * `new` - Target table file path.
+    /// * `from` - Field name to rename.
+    /// * `to` - New field name.
+    pub fn rename_and_copy(old: &PathBuf, new: &PathBuf, from: &str, to: &str) -> Result<()> {
+        if is_same_file(old, new) {
+            bail!("source and target table files must be different");
         }
-    }
+        let source = Self::from_file(old.clone())?;
 
-    #[test]
-    fn calc_record_pos_with_fields() {
-        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
+        // rename the field in place so every other field attribute is kept
+        let mut record_header = source.record_header.clone();
+        record_header.rename(from, to)?;
 
-        // add fields
-        if let Err(e) = add_fields(&mut table.record_header) {
-            assert!(false, "expected to add fields, but got error: {:?}", e);
-        }
-        assert_eq!(225, table.calc_record_pos(2));
-        assert_eq!(238, table.calc_record_pos(3));
+        // write the headers into the new table file
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(new)?;
+        let mut writer = BufWriter::new(file);
+        let mut header = source.header.clone();
+        header.upgrade_version();
+        header.write_to(&mut writer)?;
+        record_header.write_to(&mut writer)?;
+
+        // copy the record data verbatim
+        let mut reader = source.new_reader()?;
+        reader.seek(SeekFrom::Start(source.calc_record_pos(0)))?;
+        io::copy(&mut reader, &mut writer)?;
+        writer.flush()?;
+        Ok(())
     }
 
-    #[test]
-    fn calc_record_pos_without_fields() {
-        let table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
-        let pos = Header::BYTES as u64 + table.record_header.size_as_bytes();
-        assert_eq!(pos, table.calc_record_pos(1));
-        assert_eq!(pos, table.calc_record_pos(2));
-        assert_eq!(pos, table.calc_record_pos(3));
-    }
+    /// Concatenates the records from several tables into a new table file
+    /// using the file stem as table name, and returns the written record
+    /// count. Every input table must have the same fields. The record data
+    /// is copied as is when no input table is compressed and all of them
+    /// share the same fixed length record layout, otherwise records are
+    /// decoded and written one by one. The table file is removed on error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `out` - Target table file path.
+    /// * `inputs` - Input table file paths.
+    pub fn concat_into(out: &PathBuf, inputs: &[&PathBuf]) -> Result<u64> {
+        if inputs.is_empty() {
+            bail!("no input tables to concatenate");
+        }
 
-    #[test]
-    fn load_headers_from() {
-        // create buffer
-        let mut buf = [0u8; Header::BYTES + ADD_FIELDS_HEADER_BYTES];
-        let index_header_buf = build_header_bytes("my_table", 3245634545244324234u64);
-        if let Err(e) = copy_bytes(&mut buf, &index_header_buf, 0) {
-            assert!(false, "{:?}", e);
+        // compare canonical paths to catch aliases to the same file, a new
+        // target file can't be any of the inputs
+        if let Ok(target) = out.canonicalize() {
+            if inputs.iter().any(|v| v.canonicalize().is_ok_and(|v| v == target)) {
+                bail!("source and target table files must be different");
+            }
         }
-        if let Err(e) = copy_bytes(&mut buf, &ADD_FIELDS_HEADER_BYTE_SLICE, Header::BYTES) {
-            assert!(false, "{:?}", e);
+        let mut tables = Vec::with_capacity(inputs.len());
+        for path in inputs {
+            tables.push(Self::from_file((*path).clone())?);
         }
-        let mut reader = Cursor::new(buf.to_vec());
 
-        // test load_headers
-        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
-        if let Err(e) = table.load_headers_from(&mut reader) {
-            assert!(false, "expected success but got error: {:?}", e);
+        // validate the input schemas, records using another byte order
+        // are decoded below
+        let first = &tables[0];
+        for table in tables[1..].iter() {
+            if !first.record_header.iter().eq(table.record_header.iter()) {
+                bail!(
+                    "table \"{}\" fields don't match table \"{}\" fields",
+                    table.path.to_string_lossy(),
+                    first.path.to_string_lossy()
+                );
+            }
         }
 
-        // check expected index header
-        let mut expected = Header::new("my_table").unwrap();
-        expected.record_count = 3245634545244324234u64;
-        assert_eq!(expected, table.header);
-
-        // check expected record header
-        let mut expected = RecordHeader::new();
-        if let Err(e) = add_fields(&mut expected) {
-            assert!(false, "expected to add fields, but got error: {:?}", e);
+        // decode the records when the data can't be copied as is
+        let verbatim = tables.iter().all(|v| {
+            !v.header.compressed
+                && v.record_header.is_fixed_length()
+                && v.header.user_flags == first.header.user_flags
+                && v.record_header.endianness() == first.record_header.endianness()
+        });
+        if !verbatim {
+            let mut sources = Vec::with_capacity(tables.len());
+            for table in tables.iter() {
+                sources.push(table.read_records()?);
+            }
+            return Self::write_from_iter(out, &first.record_header, sources.into_iter().flatten());
         }
-        assert_eq!(expected, table.record_header);
-    }
 
-    #[test]
-    fn seek_record_from_with_fields() {
-        // init buffer
-        let (buf, record_count) = match fake_table_with_fields() {
-            Ok(v) => v,
+        let name = match out.file_stem() {
+            Some(v) => v.to_string_lossy().to_string(),
+            None => bail!("invalid table file path \"{}\"", out.to_string_lossy())
+        };
+        let mut table = Self::new(out.clone(), &name)?;
+        table.record_header = first.record_header.clone();
+        table.header.user_flags = first.header.user_flags;
+        match table.copy_records_from(&tables) {
+            Ok(count) => Ok(count),
             Err(e) => {
-                assert!(false, "{:?}", e);
-                return;
+                // remove the partially written table file
+                if out.exists() {
+                    std::fs::remove_file(out)?;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Creates the table file and copies the record data from other
+    /// tables with the same record layout into it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tables` - Tables to copy the records from.
+    fn copy_records_from(&mut self, tables: &[Table]) -> Result<u64> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        self.header.record_count = 0;
+        self.save_headers_into(&mut writer)?;
+        writer.seek(SeekFrom::Start(self.calc_record_pos(0)))?;
+
+        // copy the record data verbatim
+        for table in tables {
+            let start = table.calc_record_pos(0);
+            let size = table.calc_record_pos(table.header.record_count) - start;
+            let mut reader = table.new_reader()?;
+            reader.seek(SeekFrom::Start(start))?;
+            let copied = io::copy(&mut reader.take(size), &mut writer)?;
+            if copied != size {
+                bail!("table \"{}\" is shorter than its record count", table.path.to_string_lossy());
+            }
+            self.header.record_count += table.header.record_count;
+        }
+
+        // save the final record count
+        self.save_headers_into(&mut writer)?;
+        writer.flush()?;
+        Ok(self.header.record_count)
+    }
+}
+
+#[cfg(test)]
+pub mod test_helper {
+    use super::*;
+    use crate::test_helper::*;
+    use crate::db::table::record::header::{FieldType, Field};
+    use crate::db::table::record::value::{Value};
+    use crate::db::table::header::test_helper::build_header_bytes;
+    use tempfile::TempDir;
+
+    /// It's the size of a record header without any field.
+    pub const EMPTY_RECORD_HEADER_BYTES: usize = u32::BYTES;
+
+    /// Record header size generated by add_fields function.
+    pub const ADD_FIELDS_HEADER_BYTES: usize = Field::BYTES * 2 + u32::BYTES;
+
+    /// Record size generated by aADD_FIELDS_RECORD_BYTESdd_fields function.
+    pub const ADD_FIELDS_RECORD_BYTES: usize = 13;
+
+    /// Fake records bytes size generated by fake_records.
+    pub const FAKE_RECORDS_BYTES: usize = ADD_FIELDS_RECORD_BYTES * 3;
+
+    /// Fake index with fields byte size.
+    pub const FAKE_INDEX_BYTES: usize = Header::BYTES + ADD_FIELDS_HEADER_BYTES + FAKE_RECORDS_BYTES;
+
+    /// Byte slice that represents an empty record header.
+    pub const EMPTY_RECORD_HEADER_BYTE_SLICE: [u8; EMPTY_RECORD_HEADER_BYTES] = [
+        // field count
+        0, 0, 0, 0u8
+    ];
+
+    /// Byte slice to be generated by the record header generated by add_fields_function.
+    pub const ADD_FIELDS_HEADER_BYTE_SLICE: [u8; ADD_FIELDS_HEADER_BYTES] = [
+        // field count
+        0, 0, 0, 2u8,
+
+        // foo field name value size
+        0, 0, 0, 3u8,
+        // foo field name value
+        102u8, 111u8, 111u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0,
+        // foo field type
+        4u8, 0, 0, 0, 0,
+
+        // bar field name value size
+        0, 0, 0, 3u8,
+        // bar field name value
+        98u8, 97u8, 114u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0,
+        // bar field type
+        12u8, 0, 0, 0, 5u8
+    ];
+
+    pub const FAKE_RECORDS_BYTE_SLICE: [u8; FAKE_RECORDS_BYTES] = [
+        // first record
+        // foo field
+        13u8, 246u8, 33u8, 122u8,
+        // bar field
+        0, 0, 0, 3u8, 97u8, 98u8, 99u8, 0, 0,
+
+        // second record
+        // foo field
+        20u8, 149u8, 141u8, 65u8,
+        // bar field
+        0, 0, 0, 4u8, 100u8, 102u8, 101u8, 103u8, 0,
+
+        // third record
+        // foo field
+        51u8, 29u8, 39u8, 30u8,
+        // bar field
+        0, 0, 0, 5u8, 104u8, 105u8, 49u8, 50u8, 51u8
+    ];
+
+    /// Add test fields into record header.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `header` - Record header to add fields into.
+    pub fn add_fields(header: &mut RecordHeader) -> Result<()> {
+        header.add("foo", FieldType::I32)?;
+        header.add("bar", FieldType::Str(5))?;
+
+        Ok(())
+    }
+
+    /// Create fake records based on the fields added by add_fields.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `records` - Record vector to add records into.
+    pub fn fake_records() -> Result<Vec<Record>> {
+        let mut header = RecordHeader::new();
+        add_fields(&mut header)?;
+        let mut records = Vec::new();
+
+        // add first record
+        let mut record = header.new_record()?;
+        record.set_by_index(0, Value::I32(234234234i32))?;
+        record.set_by_index(1, Value::Str("abc".to_string()))?;
+        records.push(record);
+
+        // add second record
+        let mut record = header.new_record()?;
+        record.set_by_index(0, Value::I32(345345345i32))?;
+        record.set_by_index(1, Value::Str("dfeg".to_string()))?;
+        records.push(record);
+
+        // add third record
+        let mut record = header.new_record()?;
+        record.set_by_index(0, Value::I32(857548574i32))?;
+        record.set_by_index(1, Value::Str("hi123".to_string()))?;
+        records.push(record);
+
+        Ok(records)
+    }
+
+    /// Return a fake table file with fields as byte slice and the record count.
+    pub fn fake_table_with_fields() -> Result<([u8; FAKE_INDEX_BYTES], u64)> {
+        // init buffer
+        let mut buf = [0u8; FAKE_INDEX_BYTES];
+        let header_buf = build_header_bytes("my_table", 3245634545244324234u64);
+        copy_bytes(&mut buf, &header_buf, 0)?;
+        copy_bytes(&mut buf, &ADD_FIELDS_HEADER_BYTE_SLICE, Header::BYTES)?;
+        copy_bytes(&mut buf, &FAKE_RECORDS_BYTE_SLICE, Header::BYTES + ADD_FIELDS_HEADER_BYTES)?;
+        Ok((buf, 3))
+    }
+
+    /// Write a fake table bytes into a writer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `unprocessed` - If `true` then build all records with MatchFlag::None.
+    pub fn write_fake_table(writer: &mut (impl Seek + Write), unprocessed: bool) -> Result<Vec<Record>> {
+        let mut records = Vec::new();
+
+        // write table header
+        let mut header = Header::new("my_table")?;
+        header.record_count = 4;
+        header.write_to(writer)?;
+
+        // write record header
+        let mut record_header = RecordHeader::new();
+        add_fields(&mut record_header)?;
+        record_header.write_to(writer)?;
+        
+        // write first record
+        let mut record = record_header.new_record()?;
+        if !unprocessed {
+            record.set("foo", Value::I32(111i32))?;
+            record.set("bar", Value::Str("first".to_string()))?;
+        }
+        record_header.write_record(writer, &record)?;
+        records.push(record);
+        
+        // write second record date
+        let mut record = record_header.new_record()?;
+        if !unprocessed {
+            record.set("foo", Value::I32(222i32))?;
+            record.set("bar", Value::Str("2th".to_string()))?;
+        }
+        record_header.write_record(writer, &record)?;
+        records.push(record);
+        
+        // write third record date
+        let mut record = record_header.new_record()?;
+        if !unprocessed {
+            record.set("foo", Value::I32(333i32))?;
+            record.set("bar", Value::Str("3rd".to_string()))?;
+        }
+        record_header.write_record(writer, &record)?;
+        records.push(record);
+
+        // write fourth record date
+        let mut record = record_header.new_record()?;
+        if !unprocessed {
+            record.set("foo", Value::I32(444i32))?;
+            record.set("bar", Value::Str("4th".to_string()))?;
+        }
+        record_header.write_record(writer, &record)?;
+        records.push(record);
+
+        Ok(records)
+    }
+
+    /// Create a fake table file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Table file path.
+    /// * `empty` - If `true` then build all records as empty records.
+    pub fn create_fake_table(path: &PathBuf, unprocessed: bool) -> Result<Vec<Record>> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(path)?;
+        let mut writer = BufWriter::new(file);
+        let records = write_fake_table(&mut writer, unprocessed)?;
+        writer.flush()?;
+
+        Ok(records)
+    }
+
+    /// Execute a function with both a temp directory and a new table.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `f` - Function to execute.
+    pub fn with_tmpdir_and_table(f: &impl Fn(&TempDir, &mut Table) -> Result<()>) {
+        let sub = |dir: &TempDir| -> Result<()> {
+            // create Table and execute
+            let mut table = Table::new(
+                dir.path().join("t.fmtable"),
+                "my_table"
+            )?;
+
+            // execute function
+            match f(&dir, &mut table) {
+                Ok(_) => Ok(()),
+                Err(e) => bail!(e)
+            }
+        };
+        with_tmpdir(&sub)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_helper::*;
+    use std::io::Cursor;
+    use crate::test_helper::*;
+    use crate::traits::Endianness;
+    use crate::db::table::record::Value;
+    use crate::db::table::record::header::{FieldType, TrimMode};
+    use crate::db::table::header::test_helper::build_header_bytes;
+
+    #[test]
+    fn file_extension_regex() {
+        let rx = Table::file_extension_regex();
+        assert!(rx.is_match("hello.fmtable"), "expected to match \"hello.fmtable\" but got false");
+        assert!(rx.is_match("/path/to/hello.fmtable"), "expected to match \"/path/to/hello.fmtable\" but got false");
+        assert!(!rx.is_match("hello.table"), "expected to not match \"hello.table\" but got true");
+    }
+
+    #[test]
+    fn new() {
+        let header = Header::new("my_table").unwrap();
+        let expected = Table{
+            path: "my_table.fmtable".into(),
+            header,
+            record_header: RecordHeader::new(),
+            max_record_bytes: None
+        };
+        match Table::new("my_table.fmtable".into(), "my_table") {
+            Ok(v) => assert_eq!(expected, v),
+            Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+        }
+    }
+
+    #[test]
+    fn calc_record_pos_with_fields() {
+        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
+
+        // add fields
+        if let Err(e) = add_fields(&mut table.record_header) {
+            assert!(false, "expected to add fields, but got error: {:?}", e);
+        }
+        assert_eq!(258, table.calc_record_pos(2));
+        assert_eq!(271, table.calc_record_pos(3));
+    }
+
+    #[test]
+    fn calc_record_pos_without_fields() {
+        let table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
+        let pos = Header::BYTES as u64 + table.record_header.size_as_bytes();
+        assert_eq!(pos, table.calc_record_pos(1));
+        assert_eq!(pos, table.calc_record_pos(2));
+        assert_eq!(pos, table.calc_record_pos(3));
+    }
+
+    #[test]
+    fn load_headers_from() {
+        // create buffer
+        let mut buf = [0u8; Header::BYTES + ADD_FIELDS_HEADER_BYTES];
+        let index_header_buf = build_header_bytes("my_table", 3245634545244324234u64);
+        if let Err(e) = copy_bytes(&mut buf, &index_header_buf, 0) {
+            assert!(false, "{:?}", e);
+        }
+        if let Err(e) = copy_bytes(&mut buf, &ADD_FIELDS_HEADER_BYTE_SLICE, Header::BYTES) {
+            assert!(false, "{:?}", e);
+        }
+        let mut reader = Cursor::new(buf.to_vec());
+
+        // test load_headers
+        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
+        if let Err(e) = table.load_headers_from(&mut reader) {
+            assert!(false, "expected success but got error: {:?}", e);
+        }
+
+        // check expected index header
+        let mut expected = Header::new("my_table").unwrap();
+        expected.record_count = 3245634545244324234u64;
+        assert_eq!(expected, table.header);
+
+        // check expected record header
+        let mut expected = RecordHeader::new();
+        if let Err(e) = add_fields(&mut expected) {
+            assert!(false, "expected to add fields, but got error: {:?}", e);
+        }
+        assert_eq!(expected, table.record_header);
+    }
+
+    #[test]
+    fn load_legacy_version_table() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            let mut buf = Vec::new();
+            table.new_reader()?.read_to_end(&mut buf)?;
+
+            // rebuild the table file using a version 1 header
+            let mut legacy = buf[..Header::LEGACY_BYTES].to_vec();
+            legacy[14] = 1;
+            legacy.extend_from_slice(&buf[Header::BYTES..]);
+            create_file_with_bytes(&table.path, &legacy)?;
+
+            // test load and read
+            let mut table = Table::from_file(table.path.clone())?;
+            assert_eq!(1, table.header.get_version());
+            assert_eq!(None, table.header.checksum);
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), table.record(index as u64)?.as_ref());
+            }
+
+            // test in place header saves keep the version 1 layout
+            table.header.record_count = 3;
+            table.save_headers()?;
+            assert_eq!(legacy.len() as u64, file_size(&table.path)?);
+            let mut loaded = Table::new(table.path.clone(), "")?;
+            loaded.load_headers_from(&mut loaded.new_reader()?)?;
+            assert_eq!(1, loaded.header.get_version());
+            assert_eq!(3, loaded.header.record_count);
+            assert_eq!(Some(&records[2]), loaded.record(2)?.as_ref());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn seek_record_from_with_fields() {
+        // init buffer
+        let (buf, record_count) = match fake_table_with_fields() {
+            Ok(v) => v,
+            Err(e) => {
+                assert!(false, "{:?}", e);
+                return;
+            }
+        };
+        let mut reader = Cursor::new(buf.to_vec());
+
+        // init table and expected records
+        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
+        table.header.record_count = record_count;
+        if let Err(e) = add_fields(&mut table.record_header) {
+            assert!(false, "{:?}", e);
+        }
+        let expected = match fake_records() {
+            Ok(v) => v,
+            Err(e) => {
+                assert!(false, "{:?}", e);
+                return;
+            }
+        };
+
+        // test first record
+        let record = match table.seek_record_from(&mut reader, 0) {
+            Ok(opt) => match opt {
+                Some(v) => v,
+                None => {
+                    assert!(false, "expected {:?} but got None", expected[0]);
+                    return;
+                }
+            },
+            Err(e) => {
+                assert!(false, "{:?}", e);
+                return;
+            }
+        };
+        assert_eq!(expected[0], record);
+
+        // test second record
+        let record = match table.seek_record_from(&mut reader, 1) {
+            Ok(opt) => match opt {
+                Some(v) => v,
+                None => {
+                    assert!(false, "expected {:?} but got None", expected[0]);
+                    return;
+                }
+            },
+            Err(e) => {
+                assert!(false, "{:?}", e);
+                return;
+            }
+        };
+        assert_eq!(expected[1], record);
+
+        // test third record
+        let record = match table.seek_record_from(&mut reader, 2) {
+            Ok(opt) => match opt {
+                Some(v) => v,
+                None => {
+                    assert!(false, "expected {:?} but got None", expected[0]);
+                    return;
+                }
+            },
+            Err(e) => {
+                assert!(false, "{:?}", e);
+                return;
+            }
+        };
+        assert_eq!(expected[2], record);
+    }
+
+    #[test]
+    fn seek_record_from_without_fields() {
+        // init buffer
+        let buf = [0u8];
+        let mut reader = Cursor::new(buf.to_vec());
+
+        // init table
+        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
+        table.header.record_count = 4;
+
+        // test
+        match table.seek_record_from(&mut reader, 0) {
+            Ok(v) => assert!(false, "expected TableError::NoFields but got {:?}", v),
+            Err(e) => match e.downcast::<TableError>() {
+                Ok(ex) => match ex {
+                    TableError::NoFields => {},
+                    te => assert!(false, "expected TableError::NoFields but got TableError::{:?}", te)
+                },
+                Err(ex) => assert!(false, "expected TableError::NoFields but got error: {:?}", ex)
+            }
+        }
+    }
+
+    #[test]
+    fn record_with_fields() {
+        with_tmpdir_and_table(&|_, table| {
+            // init buffer
+            let (buf, record_count) = match fake_table_with_fields() {
+                Ok(v) => v,
+                Err(e) => {
+                    assert!(false, "{:?}", e);
+                    bail!(e)
+                }
+            };
+            create_file_with_bytes(&table.path, &buf)?;
+
+            // init table and expected records
+            table.header.record_count = record_count;
+            if let Err(e) = add_fields(&mut table.record_header) {
+                assert!(false, "{:?}", e);
+            }
+            let expected = match fake_records() {
+                Ok(v) => v,
+                Err(e) => {
+                    assert!(false, "{:?}", e);
+                    bail!(e)
+                }
+            };
+
+            // test first record
+            let data = match table.record(0) {
+                Ok(opt) => match opt {
+                    Some(v) => v,
+                    None => {
+                        assert!(false, "expected {:?} but got None", expected[0]);
+                        bail!("");
+                    }
+                },
+                Err(e) => {
+                    assert!(false, "{:?}", e);
+                    bail!(e);
+                }
+            };
+            assert_eq!(expected[0], data);
+
+            // test second record
+            let data = match table.record(1) {
+                Ok(opt) => match opt {
+                    Some(v) => v,
+                    None => {
+                        assert!(false, "expected {:?} but got None", expected[0]);
+                        bail!("")
+                    }
+                },
+                Err(e) => {
+                    assert!(false, "{:?}", e);
+                    bail!(e)
+                }
+            };
+            assert_eq!(expected[1], data);
+
+            // test third record
+            let data = match table.record(2) {
+                Ok(opt) => match opt {
+                    Some(v) => v,
+                    None => {
+                        assert!(false, "expected {:?} but got None", expected[0]);
+                        bail!("")
+                    }
+                },
+                Err(e) => {
+                    assert!(false, "{:?}", e);
+                    bail!(e)
+                }
+            };
+            assert_eq!(expected[2], data);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn last_record() {
+        with_tmpdir_and_table(&|_, table| {
+            let records = create_fake_table(&table.path, false)?;
+            let mut reader = table.new_reader()?;
+            table.load_headers_from(&mut reader)?;
+
+            // test
+            assert_eq!(Some(&records[3]), table.last_record()?.as_ref());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn last_record_with_empty_table() {
+        with_tmpdir_and_table(&|_, table| {
+            add_fields(&mut table.record_header)?;
+            table.load_or_create(false, true)?;
+
+            // test
+            assert_eq!(None, table.last_record()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn record_with_max_record_bytes() {
+        with_tmpdir_and_table(&|_, table| {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // fixed length records are never checked
+            table.max_record_bytes = Some(1);
+            assert_eq!(Some(&records[1]), table.record(1)?.as_ref());
+            assert_eq!(records.len(), table.read_records()?.collect::<Result<Vec<_>>>()?.len());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn variable_length_record_with_max_record_bytes() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.add("notes", FieldType::StrVar)?;
+            let records = [(1, "short"), (2, "a much longer note")].into_iter().map(|(id, notes)| {
+                let mut record = header.new_record()?;
+                record.set_by_index(0, Value::I32(id))?;
+                record.set_by_index(1, Value::Str(notes.to_string()))?;
+                Ok(record)
+            });
+            let path = dir.path().join("variable.fmtable");
+            Table::write_from_iter(&path, &header, records)?;
+            let mut table = Table::from_file(path.clone())?;
+
+            // records fitting the max record size are read
+            table.max_record_bytes = Some(13);
+            let mut iter = table.read_records()?;
+            assert_eq!(Value::Str("short".to_string()), *iter.next().unwrap()?.get("notes").unwrap());
+
+            // the declared record size is bigger than the max record size
+            let expected = "record size 26 bytes exceeds the max record size 13 bytes";
+            match iter.next().unwrap() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+
+            // a corrupted declared size is rejected before reading
+            let mut buf = std::fs::read(&path)?;
+            let pos = table.calc_record_pos(0) as usize;
+            buf[pos..pos + 4].copy_from_slice(&[255u8; 4]);
+            std::fs::write(&path, &buf)?;
+            table.max_record_bytes = Some(1024 * 1024);
+            match table.read_records()?.next().unwrap() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => match e.downcast::<ParseError>() {
+                    Ok(ParseError::RecordTooBig{size, max}) => {
+                        assert_eq!(u32::MAX as u64, size);
+                        assert_eq!(1024 * 1024, max);
+                    },
+                    Ok(ex) => panic!("expected ParseError::RecordTooBig but got {:?}", ex),
+                    Err(ex) => panic!("expected ParseError::RecordTooBig but got {:?}", ex)
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn record_without_fields() {
+        with_tmpdir_and_table(&|_, table| {
+            // init buffer
+            let buf = [0u8];
+            create_file_with_bytes(&table.path, &buf)?;
+
+            // init table
+            table.header.record_count = 4;
+
+            // test
+            match table.record(0) {
+                Ok(v) => assert!(false, "expected TableError::NoFields but got {:?}", v),
+                Err(e) => match e.downcast::<TableError>() {
+                    Ok(ex) => match ex {
+                        TableError::NoFields => {},
+                        te => assert!(false, "expected TableError::NoFields but got TableError::{:?}", te)
+                    },
+                    Err(ex) => assert!(false, "expected TableError::NoFields but got error: {:?}", ex)
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn save_record_into_smaller_file() {
+        with_tmpdir_and_table(&|_, table| {
+            // create table
+            let mut records = create_fake_table(&table.path, false)?;
+            add_fields(&mut table.record_header)?;
+
+            // set record count to trigger the error
+            table.header.record_count = 1;
+
+            // test
+            let expected = "can't write or append the record, the table file is too small";
+            records[2].set("foo", Value::I32(11))?;
+            records[2].set("bar", Value::Str("hello".to_string()))?;
+            match table.save_record(2, &records[2], true) {
+                Ok(v) => assert!(false, "expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn save_record_into_with_fields() {
+        with_tmpdir_and_table(&|_, table| {
+            // create table and check original value
+            let mut records = create_fake_table(&table.path, false)?;
+            add_fields(&mut table.record_header)?;
+            table.header.record_count = records.len() as u64;
+
+            // read old record value
+            let pos = table.calc_record_pos(2);
+            let mut buf = [0u8; ADD_FIELDS_RECORD_BYTES];
+            let file = File::open(&table.path)?;
+            let mut reader = BufReader::new(file);
+            let mut old_bytes_before = vec!(0u8; pos as usize);
+            let mut old_bytes_after = vec!(0u8; ADD_FIELDS_RECORD_BYTES);
+            reader.read_exact(&mut old_bytes_before)?;
+            reader.read_exact(&mut buf)?;
+            reader.read_exact(&mut old_bytes_after)?;
+            let expected = [
+                // foo field
+                0, 0, 1u8, 77u8,
+                // bar field
+                0, 0, 0, 3u8, 51u8, 114u8, 100u8, 0, 0
+            ];
+            assert_eq!(expected, buf);
+
+            // save record and check saved record value
+            let expected = [
+                // foo field
+                0, 0, 0, 11u8,
+                // bar field
+                0, 0, 0, 5u8, 104u8, 101u8, 108u8, 108u8, 111u8
+            ];
+            records[2].set("foo", Value::I32(11))?;
+            records[2].set("bar", Value::Str("hello".to_string()))?;
+            if let Err(e) = table.save_record(2, &records[2], true) {
+                assert!(false, "expected success but got error: {:?}", e)
+            }
+            reader.seek(SeekFrom::Start(0))?;
+            let mut new_bytes_before = vec!(0u8; pos as usize);
+            let mut new_bytes_after = vec!(0u8; ADD_FIELDS_RECORD_BYTES);
+            reader.read_exact(&mut new_bytes_before)?;
+            reader.read_exact(&mut buf)?;
+            reader.read_exact(&mut new_bytes_after)?;
+            assert_eq!(old_bytes_before, new_bytes_before);
+            assert_eq!(expected, buf);
+            assert_eq!(old_bytes_after, new_bytes_after);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn save_record_into_without_fields() {
+        with_tmpdir_and_table(&|_, table| {
+            // create table and create expected table file contents
+            let mut records = create_fake_table(&table.path, true)?;
+            let mut expected = Vec::new();
+            let file = File::open(&table.path)?;
+            let mut reader = BufReader::new(file);
+            reader.read_to_end(&mut expected)?;
+
+            // test
+            records[2].set("foo", Value::I32(11))?;
+            records[2].set("bar", Value::Str("hello".to_string()))?;
+            match table.save_record(2, &records[2], true) {
+                Ok(()) => assert!(false, "expected TableError::NoFields but got success"),
+                Err(e) => match e.downcast::<TableError>() {
+                    Ok(ex) => match ex {
+                        TableError::NoFields => {},
+                        te => assert!(false, "expected TableError::NoFields but got TableError::{:?}", te)
+                    },
+                    Err(ex) => assert!(false, "expected TableError::NoFields but got error: {:?}", ex)
+                }
+            }
+
+            // check file after invalid save, it shouldn't change
+            let mut buf = Vec::new();
+            let file = File::open(&table.path)?;
+            let mut reader = BufReader::new(file);
+            reader.read_to_end(&mut buf)?;
+            assert_eq!(expected, buf);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn open_lazy() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let path = dir.path().join("t.fmtable");
+            let records = create_fake_table(&path, false)?;
+
+            // test
+            let table = Table::open_lazy(path)?;
+            assert_eq!(4, table.header.record_count);
+            assert_eq!(4, table.record_count()?);
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), table.record(index as u64)?.as_ref());
+            }
+            Ok(())
+        });
+    }
+
+    /// Byte reader wrapper counting the bytes read.
+    struct CountingReader<R: Read + Seek> {
+        inner: R,
+        count: u64
+    }
+
+    impl<R: Read + Seek> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let size = self.inner.read(buf)?;
+            self.count += size as u64;
+            Ok(size)
+        }
+    }
+
+    impl<R: Read + Seek> Seek for CountingReader<R> {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn open_lazy_reads_only_headers() {
+        with_tmpdir(&|dir| -> Result<()> {
+            for record_count in [4u64, 1_000_000u64] {
+                // preallocate the table records
+                let path = dir.path().join(format!("t{}.fmtable", record_count));
+                let mut table = Table::new(path.clone(), "my_table")?;
+                add_fields(&mut table.record_header)?;
+                table.header.record_count = record_count;
+                {
+                    let mut writer = table.new_writer(true)?;
+                    table.header.write_to(&mut writer)?;
+                    table.record_header.write_to(&mut writer)?;
+                    writer.flush()?;
+                }
+                fill_file(&path, table.calc_record_pos(record_count), false)?;
+
+                // test, the file isn't buffered so only the header bytes are read
+                let mut reader = CountingReader{inner: File::open(&path)?, count: 0};
+                let lazy = Table::open_lazy_from(path, &mut reader)?;
+                assert_eq!(record_count, lazy.header.record_count);
+                assert_eq!(record_count, lazy.record_count()?);
+                assert_eq!(lazy.calc_record_pos(0), reader.count);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn open_lazy_without_records() {
+        with_tmpdir(&|dir| -> Result<()> {
+            // table headers claiming records the file doesn't hold
+            let path = dir.path().join("t.fmtable");
+            let mut table = Table::new(path.clone(), "my_table")?;
+            add_fields(&mut table.record_header)?;
+            table.header.record_count = 1_000_000;
+            {
+                let mut writer = table.new_writer(true)?;
+                table.header.write_to(&mut writer)?;
+                table.record_header.write_to(&mut writer)?;
+                writer.flush()?;
+            }
+
+            // test, records aren't read so the table opens anyway
+            let lazy = Table::open_lazy(path.clone())?;
+            assert_eq!(1_000_000, lazy.header.record_count);
+            assert_eq!(0, lazy.record_count()?);
+            assert!(Table::from_file(path).is_err());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn record_count_with_partial_record() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            fill_file(&table.path, table.calc_record_pos(4) + 3, false)?;
+
+            // test
+            assert_eq!(4, table.record_count()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn actual_record_count() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // test
+            let expected_size = (Header::BYTES + ADD_FIELDS_HEADER_BYTES + ADD_FIELDS_RECORD_BYTES * 4) as u64;
+            assert_eq!(expected_size, table.expected_size(4));
+            assert_eq!(file_size(&table.path)?, table.expected_size(4));
+            assert_eq!(4, table.actual_record_count()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn actual_record_count_with_trailing_bytes() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            fill_file(&table.path, table.expected_size(4) + 3, false)?;
+
+            // test
+            match table.actual_record_count() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("table file has 3 trailing bytes after the last complete record", e.to_string())
+            }
+            assert_eq!(4, table.record_count()?);
+
+            // test truncated headers
+            let file = OpenOptions::new().write(true).open(&table.path)?;
+            file.set_len(Header::BYTES as u64)?;
+            let expected = format!(
+                "table file size {} is smaller than its headers size {}",
+                Header::BYTES,
+                table.calc_record_pos(0)
+            );
+            match table.actual_record_count() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn healthcheck_new_table() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            // test healthcheck status
+            let expected = Status::New;
+            match table.healthcheck() {
+                Ok(status) => assert_eq!(expected , status),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn healthcheck_new_index_with_empty_file() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            // test healthcheck status
+            table.new_writer(true)?;
+            let expected = Status::New;
+            match table.healthcheck() {
+                Ok(status) => assert_eq!(expected , status),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn healthcheck_corrupted_headers() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let buf = [0u8; 5];
+            create_file_with_bytes(&table.path, &buf)?;
+            let expected = Status::Corrupted;
+            match table.healthcheck() {
+                Ok(status) => assert_eq!(expected , status),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            }
+            Ok(())
+        });
+    }
+    
+    #[test]
+    fn healthcheck_corrupted() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let mut buf = [0u8; Header::BYTES+EMPTY_RECORD_HEADER_BYTES+5];
+            let mut writer = &mut buf as &mut [u8];
+            let mut header = Header::new("my_table")?;
+            header.record_count = 10;
+            header.write_to(&mut writer)?;
+
+            create_file_with_bytes(&table.path, &buf)?;
+            add_fields(&mut table.record_header)?;
+            assert_eq!(Status::Corrupted, table.healthcheck()?);
+            Ok(())
+        });
+    }
+    
+    #[test]
+    fn healthcheck_good() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            assert_eq!(Status::Good, table.healthcheck()?);
+            Ok(())
+        });
+    }
+    
+    #[test]
+    fn healthcheck_no_fields() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let mut writer = table.new_writer(true)?;
+            table.save_headers_into(&mut writer)?;
+            assert_eq!(Status::NoFields, table.healthcheck()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn save_headers_into() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            // create table file and read table header data
+            create_fake_table(&table.path, false)?;
+            let mut reader = table.new_reader()?;
+            let size = Header::BYTES + 122;
+            let mut expected = vec![0u8; size];
+            reader.read_exact(&mut expected)?;
+            reader.rewind()?;
+            table.header.load_from(&mut reader)?;
+            table.record_header.load_from(&mut reader)?;
+
+            // test save table header
+            let mut buf = vec![0u8; size];
+            let wrt = &mut buf as &mut [u8];
+            let mut writer = Cursor::new(wrt);
+            if let Err(e) = table.save_headers_into(&mut writer) {
+                assert!(false, "expected success but got error: {:?}", e);
+            };
+            assert_eq!(expected, buf);
+            
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn save_headers() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            // create table file and read table header data
+            create_fake_table(&table.path, false)?;
+            let mut reader = table.new_reader()?;
+            let size = Header::BYTES + 122;
+            let mut expected = vec![0u8; size];
+            reader.read_exact(&mut expected)?;
+            reader.rewind()?;
+            table.header.load_from(&mut reader)?;
+            table.record_header.load_from(&mut reader)?;
+
+            // test save table header
+            assert_eq!(4, table.header.record_count);
+            table.header.record_count = 5;
+            if let Err(e) = table.save_headers() {
+                assert!(false, "expected success but got error: {:?}", e);
+            };
+            table.header.record_count = 4;
+            assert_eq!(4, table.header.record_count);
+            reader.rewind()?;
+            table.header.load_from(&mut reader)?;
+            assert_eq!(5, table.header.record_count);
+            
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rename_and_copy() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            let new_path = dir.path().join("renamed.fmtable");
+            Table::rename_and_copy(&table.path, &new_path, "bar", "baz")?;
+
+            // check the new headers
+            let renamed = Table::from_file(new_path)?;
+            assert_eq!("my_table", renamed.header.get_name());
+            assert_eq!(4, renamed.header.record_count);
+            let mut expected = RecordHeader::new();
+            expected.add("foo", FieldType::I32)?;
+            expected.add("baz", FieldType::Str(5))?;
+            assert_eq!(expected, renamed.record_header);
+
+            // check the records are the same under the new name
+            for (index, record) in records.iter().enumerate() {
+                let copy = match renamed.record(index as u64)? {
+                    Some(v) => v,
+                    None => bail!("expected record {} but got None", index)
+                };
+                assert_eq!(record.get("foo"), copy.get("foo"));
+                assert_eq!(record.get("bar"), copy.get("baz"));
+                assert_eq!(None, copy.get("bar"));
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rename_and_copy_with_nullable_fields() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add_nullable("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            header.set_endianness(Endianness::Little);
+            let mut record = header.new_record()?;
+            record.set("foo", Value::Null)?;
+            record.set("bar", Value::Str("abc".to_string()))?;
+            let path = dir.path().join("nullable.fmtable");
+            Table::write_from_iter(&path, &header, vec![Ok(record)].into_iter())?;
+
+            // test
+            let new_path = dir.path().join("renamed.fmtable");
+            Table::rename_and_copy(&path, &new_path, "bar", "baz")?;
+            let renamed = Table::from_file(new_path)?;
+            let mut expected = header.clone();
+            expected.rename("bar", "baz")?;
+            assert_eq!(expected, renamed.record_header);
+            let record = renamed.record(0)?.unwrap();
+            assert_eq!(Some(&Value::Null), record.get("foo"));
+            assert_eq!(Some(&Value::Str("abc".to_string())), record.get("baz"));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rename_and_copy_same_file() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            let expected = std::fs::read(&table.path)?;
+
+            // test
+            let alias = dir.path().join("..").join(dir.path().file_name().unwrap()).join("t.fmtable");
+            for new_path in [&table.path, &alias] {
+                match Table::rename_and_copy(&table.path, new_path, "bar", "baz") {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!("source and target table files must be different", e.to_string())
+                }
+            }
+            assert_eq!(expected, std::fs::read(&table.path)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rename_and_copy_with_unknown_field() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            let new_path = dir.path().join("renamed.fmtable");
+            let expected = "field \"abc\" doesn't exists within the header";
+            match Table::rename_and_copy(&table.path, &new_path, "abc", "baz") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
             }
-        };
-        let mut reader = Cursor::new(buf.to_vec());
+            Ok(())
+        });
+    }
 
-        // init table and expected records
-        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
-        table.header.record_count = record_count;
-        if let Err(e) = add_fields(&mut table.record_header) {
-            assert!(false, "{:?}", e);
-        }
-        let expected = match fake_records() {
-            Ok(v) => v,
-            Err(e) => {
-                assert!(false, "{:?}", e);
-                return;
+    /// Corrupts the "bar" field size of a fake table record.
+    fn corrupt_record(table: &Table, index: u64) -> Result<()> {
+        let mut writer = table.new_writer(false)?;
+        writer.seek(SeekFrom::Start(table.calc_record_pos(index) + i32::BYTES as u64))?;
+        writer.write_all(&[0, 0, 0, 9u8])?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_parallel_matches_serial() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            corrupt_record(table, 1)?;
+            corrupt_record(table, 3)?;
+
+            let serial = table.validate(1)?;
+            assert_eq!(4, serial.checked);
+            assert_eq!(vec![1, 3], serial.errors.iter().map(|(i, _)| *i).collect::<Vec<u64>>());
+            assert_eq!(serial, table.validate(2)?);
+            assert_eq!(serial, table.validate(3)?);
+            assert_eq!(serial, table.validate(8)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn validate_good_table() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let expected = ValidationReport{
+                checked: 4,
+                errors: Vec::new(),
+                truncated: false
+            };
+            assert_eq!(expected, table.validate(4)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn validate_limited() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            corrupt_record(table, 0)?;
+            corrupt_record(table, 1)?;
+            corrupt_record(table, 3)?;
+
+            // test
+            let report = table.validate_limited(2)?;
+            assert_eq!(2, report.checked);
+            assert_eq!(vec![0, 1], report.errors.iter().map(|(i, _)| *i).collect::<Vec<u64>>());
+            assert!(report.truncated);
+
+            // enough room to report every error
+            let report = table.validate_limited(3)?;
+            assert_eq!(4, report.checked);
+            assert_eq!(3, report.errors.len());
+            assert!(!report.truncated);
+
+            // no limit
+            let report = table.validate_limited(0)?;
+            assert_eq!(4, report.checked);
+            assert_eq!(3, report.errors.len());
+            assert!(!report.truncated);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn scan_parallel() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            let scanned = std::sync::Mutex::new(Vec::new());
+            table.scan(3, |index, record| {
+                scanned.lock().unwrap().push((index, record));
+                Ok(())
+            })?;
+            let mut scanned = scanned.into_inner().unwrap();
+            scanned.sort_by_key(|(index, _)| *index);
+            let expected: Vec<(u64, Record)> = records.into_iter()
+                .enumerate()
+                .map(|(index, record)| (index as u64, record))
+                .collect();
+            assert_eq!(expected, scanned);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn write_from_iter() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            add_fields(&mut header)?;
+            let records = fake_records()?;
+
+            // test
+            let out = dir.path().join("etl.fmtable");
+            let iter = fake_records()?.into_iter().map(Ok);
+            assert_eq!(3, Table::write_from_iter(&out, &header, iter)?);
+
+            // read the records back
+            let table = Table::from_file(out)?;
+            assert_eq!("etl", table.header.get_name());
+            assert_eq!(header, table.record_header);
+            assert_eq!(3, table.header.record_count);
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), table.record(index as u64)?.as_ref());
             }
-        };
+            Ok(())
+        });
+    }
 
-        // test first record
-        let record = match table.seek_record_from(&mut reader, 0) {
-            Ok(opt) => match opt {
-                Some(v) => v,
-                None => {
-                    assert!(false, "expected {:?} but got None", expected[0]);
-                    return;
-                }
-            },
-            Err(e) => {
-                assert!(false, "{:?}", e);
-                return;
+    #[test]
+    fn write_from_iter_with_error() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            add_fields(&mut header)?;
+            let mut records = fake_records()?;
+            records[1].set("foo", Value::Str("abc".to_string()))?;
+
+            // test
+            let out = dir.path().join("etl.fmtable");
+            let iter = records.into_iter().map(Ok);
+            match Table::write_from_iter(&out, &header, iter) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("row 1, field 'foo': value Str(\"abc\") doesn't match the field type I32", e.to_string())
             }
-        };
-        assert_eq!(expected[0], record);
+            assert!(!out.exists());
 
-        // test second record
-        let record = match table.seek_record_from(&mut reader, 1) {
-            Ok(opt) => match opt {
-                Some(v) => v,
-                None => {
-                    assert!(false, "expected {:?} but got None", expected[0]);
-                    return;
+            // test iterator error
+            let iter = vec![Ok(header.new_record()?), Err(anyhow::anyhow!("bad row"))].into_iter();
+            match Table::write_from_iter(&out, &header, iter) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("bad row", e.to_string())
+            }
+            assert!(!out.exists());
+
+            // test field count mismatch
+            let mut record = Record::new();
+            record.add("foo", Value::I32(1))?;
+            let iter = vec![Ok(record)].into_iter();
+            match Table::write_from_iter(&out, &header, iter) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => match e.downcast() {
+                    Ok(ParseError::FieldCountMismatch{expected, got}) => assert_eq!((2, 1), (expected, got)),
+                    Ok(ex) => panic!("expected ParseError::FieldCountMismatch but got error: {:?}", ex),
+                    Err(ex) => panic!("expected ParseError::FieldCountMismatch but got error: {:?}", ex)
                 }
-            },
-            Err(e) => {
-                assert!(false, "{:?}", e);
-                return;
             }
-        };
-        assert_eq!(expected[1], record);
+            assert!(!out.exists());
+            Ok(())
+        });
+    }
 
-        // test third record
-        let record = match table.seek_record_from(&mut reader, 2) {
-            Ok(opt) => match opt {
-                Some(v) => v,
-                None => {
-                    assert!(false, "expected {:?} but got None", expected[0]);
-                    return;
-                }
-            },
-            Err(e) => {
-                assert!(false, "{:?}", e);
-                return;
+    #[test]
+    fn update_field() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let mut records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut expected = Vec::new();
+            table.new_reader()?.read_to_end(&mut expected)?;
+
+            // test
+            table.update_field(2, "bar", &Value::Str("new".to_string()))?;
+            records[2].set("bar", Value::Str("new".to_string()))?;
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), table.record(index as u64)?.as_ref());
             }
-        };
-        assert_eq!(expected[2], record);
+
+            // only the field bytes should change
+            let mut buf = Vec::new();
+            table.new_reader()?.read_to_end(&mut buf)?;
+            let pos = (table.calc_record_pos(2) + i32::BYTES as u64) as usize;
+            let field_size = FieldType::Str(5).value_byte_size();
+            assert_eq!(expected.len(), buf.len());
+            assert_eq!(expected[..pos], buf[..pos]);
+            assert_eq!(expected[pos+field_size..], buf[pos+field_size..]);
+            assert_eq!([0, 0, 0, 3u8, 110u8, 101u8, 119u8, 0, 0], buf[pos..pos+field_size]);
+            Ok(())
+        });
     }
 
     #[test]
-    fn seek_record_from_without_fields() {
-        // init buffer
-        let buf = [0u8];
-        let mut reader = Cursor::new(buf.to_vec());
+    fn update_field_with_invalid_value() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            match table.update_field(1, "foo", &Value::Bool(true)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("error saving field \"foo\": value must be a Value::I32", e.to_string())
+            }
+            match table.update_field(4, "foo", &Value::I32(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("record index 4 is out of bounds", e.to_string())
+            }
+            match table.update_field(1, "baz", &Value::I32(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"baz\" doesn't exists within the header", e.to_string())
+            }
+            Ok(())
+        });
+    }
 
-        // init table
-        let mut table = Table::new("my_table.fmtable".into(), "my_table").unwrap();
-        table.header.record_count = 4;
+    #[test]
+    fn overwrite_record() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let mut records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut expected = Vec::new();
+            table.new_reader()?.read_to_end(&mut expected)?;
 
-        // test
-        match table.seek_record_from(&mut reader, 0) {
-            Ok(v) => assert!(false, "expected TableError::NoFields but got {:?}", v),
-            Err(e) => match e.downcast::<TableError>() {
-                Ok(ex) => match ex {
-                    TableError::NoFields => {},
-                    te => assert!(false, "expected TableError::NoFields but got TableError::{:?}", te)
-                },
-                Err(ex) => assert!(false, "expected TableError::NoFields but got error: {:?}", ex)
+            // test
+            let mut record = table.record_header.new_record()?;
+            record.set("foo", Value::I32(999i32))?;
+            record.set("bar", Value::Str("mid".to_string()))?;
+            table.overwrite_record(1, &record)?;
+            records[1] = record;
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), table.record(index as u64)?.as_ref());
             }
-        }
+
+            // only the record bytes should change
+            let mut buf = Vec::new();
+            table.new_reader()?.read_to_end(&mut buf)?;
+            let start = table.calc_record_pos(1) as usize;
+            let end = table.calc_record_pos(2) as usize;
+            assert_eq!(expected.len(), buf.len());
+            assert_eq!(expected[..start], buf[..start]);
+            assert_eq!(expected[end..], buf[end..]);
+            Ok(())
+        });
     }
 
     #[test]
-    fn record_with_fields() {
-        with_tmpdir_and_table(&|_, table| {
-            // init buffer
-            let (buf, record_count) = match fake_table_with_fields() {
-                Ok(v) => v,
-                Err(e) => {
-                    assert!(false, "{:?}", e);
-                    bail!(e)
-                }
-            };
-            create_file_with_bytes(&table.path, &buf)?;
+    fn overwrite_record_with_errors() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut expected = Vec::new();
+            table.new_reader()?.read_to_end(&mut expected)?;
 
-            // init table and expected records
-            table.header.record_count = record_count;
-            if let Err(e) = add_fields(&mut table.record_header) {
-                assert!(false, "{:?}", e);
+            // test
+            let record = table.record_header.new_record()?;
+            match table.overwrite_record(4, &record) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("record index 4 is out of bounds", e.to_string())
+            }
+            let mut record = Record::new();
+            record.add("foo", Value::I32(1))?;
+            match table.overwrite_record(1, &record) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "header field count mismatch the record value count, expected 2 values but got 1",
+                    e.to_string()
+                )
+            }
+            let mut record = Record::new();
+            record.add("bar", Value::Str("abc".to_string()))?;
+            record.add("foo", Value::I32(1))?;
+            match table.overwrite_record(1, &record) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("record field \"bar\" doesn't match the header field \"foo\"", e.to_string())
+            }
+            let mut record = table.record_header.new_record()?;
+            record.set("foo", Value::I32(1))?;
+            record.set("bar", Value::Str("too long".to_string()))?;
+            if let Ok(v) = table.overwrite_record(1, &record) {
+                panic!("expected error but got {:?}", v);
             }
-            let expected = match fake_records() {
-                Ok(v) => v,
-                Err(e) => {
-                    assert!(false, "{:?}", e);
-                    bail!(e)
-                }
-            };
 
-            // test first record
-            let data = match table.record(0) {
-                Ok(opt) => match opt {
-                    Some(v) => v,
-                    None => {
-                        assert!(false, "expected {:?} but got None", expected[0]);
-                        bail!("");
-                    }
-                },
-                Err(e) => {
-                    assert!(false, "{:?}", e);
-                    bail!(e);
-                }
-            };
-            assert_eq!(expected[0], data);
+            // the table file should be untouched
+            let mut buf = Vec::new();
+            table.new_reader()?.read_to_end(&mut buf)?;
+            assert_eq!(expected, buf);
+            Ok(())
+        });
+    }
 
-            // test second record
-            let data = match table.record(1) {
-                Ok(opt) => match opt {
-                    Some(v) => v,
-                    None => {
-                        assert!(false, "expected {:?} but got None", expected[0]);
-                        bail!("")
-                    }
-                },
-                Err(e) => {
-                    assert!(false, "{:?}", e);
-                    bail!(e)
-                }
-            };
-            assert_eq!(expected[1], data);
+    #[test]
+    fn import_csv() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.add("price", FieldType::F64)?;
+            header.add("name", FieldType::Str(5))?;
+            let path = dir.path().join("products.csv");
+            let csv = "product_id,product_name,ignored,cost\n\
+                1,foo,x,9.5\n\
+                2.0,bar,y,\n\
+                -3,,z,12\n";
+            create_file_with_bytes(&path, csv.as_bytes())?;
+            let mut mapping = HashMap::new();
+            mapping.insert("product_id".to_string(), "id".to_string());
+            mapping.insert("product_name".to_string(), "name".to_string());
+            mapping.insert("cost".to_string(), "price".to_string());
 
-            // test third record
-            let data = match table.record(2) {
-                Ok(opt) => match opt {
-                    Some(v) => v,
-                    None => {
-                        assert!(false, "expected {:?} but got None", expected[0]);
-                        bail!("")
-                    }
-                },
-                Err(e) => {
-                    assert!(false, "{:?}", e);
-                    bail!(e)
-                }
-            };
-            assert_eq!(expected[2], data);
+            // test
+            let out = dir.path().join("products.fmtable");
+            assert_eq!(3, Table::import_csv(&out, &path, &header, &mapping)?);
+            let table = Table::from_file(out)?;
+            let expected = [
+                (Value::I32(1), Value::F64(9.5), Value::Str("foo".to_string())),
+                (Value::I32(2), Value::F64(0f64), Value::Str("bar".to_string())),
+                (Value::I32(-3), Value::F64(12f64), Value::Str("".to_string()))
+            ];
+            for (index, (id, price, name)) in expected.iter().enumerate() {
+                let record = table.record(index as u64)?.unwrap();
+                assert_eq!(Some(id), record.get("id"));
+                assert_eq!(Some(price), record.get("price"));
+                assert_eq!(Some(name), record.get("name"));
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn export_csv() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // test
+            let mut buf = Vec::new();
+            assert_eq!(4, table.export_csv(&mut buf)?);
+            let expected = "foo,bar\n111,first\n222,2th\n333,3rd\n444,4th\n";
+            assert_eq!(expected, String::from_utf8(buf.clone())?);
+
+            // the exported file can be imported back
+            let path = dir.path().join("export.csv");
+            create_file_with_bytes(&path, &buf)?;
+            let mut mapping = HashMap::new();
+            mapping.insert("foo".to_string(), "foo".to_string());
+            mapping.insert("bar".to_string(), "bar".to_string());
+            let out = dir.path().join("import.fmtable");
+            assert_eq!(4, Table::import_csv(&out, &path, &table.record_header, &mapping)?);
+            let imported = Table::from_file(out)?;
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), imported.record(index as u64)?.as_ref());
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn import_csv_with_errors() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.add("price", FieldType::F64)?;
+            let path = dir.path().join("products.csv");
+            create_file_with_bytes(&path, b"id,price\n1,9.5\n2.5,3\n")?;
+            let out = dir.path().join("products.fmtable");
+
+            // test non numeric cell
+            let mut mapping = HashMap::new();
+            mapping.insert("price".to_string(), "price".to_string());
+            let bad_path = dir.path().join("bad_products.csv");
+            create_file_with_bytes(&bad_path, b"id,price\n1,9.5\n2,abc\n")?;
+            match Table::import_csv(&out, &bad_path, &header, &mapping) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("row 1, column 'price': can't parse \"abc\" as f64: invalid float literal", e.to_string())
+            }
+            assert!(!out.exists());
+
+            // test invalid cell
+            let mut mapping = HashMap::new();
+            mapping.insert("id".to_string(), "id".to_string());
+            mapping.insert("price".to_string(), "price".to_string());
+            match Table::import_csv(&out, &path, &header, &mapping) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("row 1, column 'id': can't parse \"2.5\" as i32: invalid digit found in string", e.to_string())
+            }
+            assert!(!out.exists());
+
+            // test unknown column
+            mapping.insert("cost".to_string(), "price".to_string());
+            match Table::import_csv(&out, &path, &header, &mapping) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("column \"cost\" doesn't exists within the CSV file", e.to_string())
+            }
+
+            // test unknown field
+            mapping.remove("cost");
+            mapping.insert("price".to_string(), "cost".to_string());
+            match Table::import_csv(&out, &path, &header, &mapping) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"cost\" doesn't exists within the header", e.to_string())
+            }
+            assert!(!out.exists());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn write_from_iter_with_oversized_str() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            add_fields(&mut header)?;
+            let mut records = fake_records()?;
+            records[2].set("bar", Value::Str("abcdefgh".to_string()))?;
+
+            // test
+            let out = dir.path().join("etl.fmtable");
+            let expected = "row 2, field 'bar': string value (8 bytes) exceeds field size (5 bytes)";
+            match Table::write_from_iter(&out, &header, records.into_iter().map(Ok)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            assert!(!out.exists());
             Ok(())
         });
     }
 
     #[test]
-    fn record_without_fields() {
-        with_tmpdir_and_table(&|_, table| {
-            // init buffer
-            let buf = [0u8];
-            create_file_with_bytes(&table.path, &buf)?;
+    fn verify_schema() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let path = dir.path().join("t.fmtable");
+            create_fake_table(&path, false)?;
+            let mut header = RecordHeader::new();
+            add_fields(&mut header)?;
+            assert!(Table::verify_schema(&path, header.schema_hash()?)?);
+
+            // different schema
+            header.add("extra", FieldType::I32)?;
+            assert!(!Table::verify_schema(&path, header.schema_hash()?)?);
+            Ok(())
+        });
+    }
 
-            // init table
-            table.header.record_count = 4;
+    #[test]
+    fn write_compressed_from_iter() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            add_fields(&mut header)?;
+            let plain_path = dir.path().join("plain.fmtable");
+            let compressed_path = dir.path().join("compressed.fmtable");
+            Table::write_from_iter(&plain_path, &header, fake_records()?.into_iter().map(Ok))?;
+            let count = Table::write_compressed_from_iter(&compressed_path, &header, fake_records()?.into_iter().map(Ok))?;
+            assert_eq!(3, count);
+
+            // test
+            let plain = Table::from_file(plain_path)?;
+            let compressed = Table::from_file(compressed_path)?;
+            assert!(!plain.header.compressed);
+            assert!(compressed.header.compressed);
+            assert_eq!(3, compressed.header.record_count);
+            let expected: Vec<Record> = plain.read_records()?.collect::<Result<_>>()?;
+            let records: Vec<Record> = compressed.read_records()?.collect::<Result<_>>()?;
+            assert_eq!(fake_records()?, expected);
+            assert_eq!(expected, records);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn record_on_compressed_table() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            add_fields(&mut header)?;
+            let path = dir.path().join("compressed.fmtable");
+            Table::write_compressed_from_iter(&path, &header, fake_records()?.into_iter().map(Ok))?;
 
             // test
+            let mut table = Table::from_file(path)?;
             match table.record(0) {
-                Ok(v) => assert!(false, "expected TableError::NoFields but got {:?}", v),
-                Err(e) => match e.downcast::<TableError>() {
-                    Ok(ex) => match ex {
-                        TableError::NoFields => {},
-                        te => assert!(false, "expected TableError::NoFields but got TableError::{:?}", te)
-                    },
-                    Err(ex) => assert!(false, "expected TableError::NoFields but got error: {:?}", ex)
-                }
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("random access isn't supported on compressed tables", e.to_string())
             }
+            let record = header.new_record()?;
+            match table.save_record(0, &record, false) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("random access isn't supported on compressed tables", e.to_string())
+            }
+            Ok(())
+        });
+    }
 
+    #[test]
+    fn variable_length_table() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.add("notes", FieldType::StrVar)?;
+            let build_records = || [(1, "a"), (2, "a much longer note"), (3, "")].into_iter().map(|(id, notes)| {
+                let mut record = header.new_record()?;
+                record.set_by_index(0, Value::I32(id))?;
+                record.set_by_index(1, Value::Str(notes.to_string()))?;
+                Ok(record)
+            });
+            let records: Vec<Record> = build_records().collect::<Result<_>>()?;
+            let path = dir.path().join("variable.fmtable");
+            assert_eq!(3, Table::write_from_iter(&path, &header, build_records())?);
+
+            // records are read sequentially
+            let mut table = Table::from_file(path)?;
+            let read: Vec<Record> = table.read_records()?.collect::<Result<_>>()?;
+            assert_eq!(records, read);
+
+            // random access is rejected
+            let expected = TableError::VariableLength.to_string();
+            match table.record(1) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            match table.record_count() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            match table.save_record(0, &records[0], false) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            match table.update_field(0, "id", &Value::I32(5)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            match table.validate(1) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            match table.record_reader() {
+                Ok(_) => panic!("expected error but got a record reader"),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
             Ok(())
         });
     }
 
     #[test]
-    fn save_record_into_smaller_file() {
-        with_tmpdir_and_table(&|_, table| {
-            // create table
-            let mut records = create_fake_table(&table.path, false)?;
+    fn user_flags() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
             add_fields(&mut table.record_header)?;
-
-            // set record count to trigger the error
-            table.header.record_count = 1;
+            table.header.user_flags = true;
+            table.load_or_create(false, true)?;
+            let records = fake_records()?;
+            for (index, record) in records.iter().enumerate() {
+                table.save_record(index as u64, record, true)?;
+            }
+            assert_eq!(ADD_FIELDS_RECORD_BYTES as u64 + 1, table.record_size());
 
             // test
-            let expected = "can't write or append the record, the table file is too small";
-            records[2].set("foo", Value::I32(11))?;
-            records[2].set("bar", Value::Str("hello".to_string()))?;
-            match table.save_record(2, &records[2], true) {
-                Ok(v) => assert!(false, "expected error but got {:?}", v),
-                Err(e) => assert_eq!(expected, e.to_string())
+            table.set_flags(0, 0b0000_0011)?;
+            table.set_flags(2, 0b0000_0001)?;
+            assert_eq!(0b0000_0011, table.get_flags(0)?);
+            assert_eq!(0, table.get_flags(1)?);
+            assert_eq!(0b0000_0001, table.get_flags(2)?);
+            assert_eq!(vec![0, 2], table.filter_by_flags(0b0000_0001)?);
+            assert_eq!(vec![0], table.filter_by_flags(0b0000_0011)?);
+            assert_eq!(vec![0, 1, 2], table.filter_by_flags(0)?);
+
+            // flags don't affect the record values
+            let table = Table::from_file(table.path.clone())?;
+            assert!(table.header.user_flags);
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), table.record(index as u64)?.as_ref());
             }
-            
+            let read: Vec<Record> = table.record_reader()?.collect::<Result<_>>()?;
+            assert_eq!(records, read);
+            assert!(table.validate(2)?.errors.is_empty());
+
+            // updating a record keeps its flags
+            table.clone().save_record(0, &records[1], false)?;
+            assert_eq!(0b0000_0011, table.get_flags(0)?);
+            assert_eq!(Some(&records[1]), table.record(0)?.as_ref());
             Ok(())
         });
     }
 
     #[test]
-    fn save_record_into_with_fields() {
-        with_tmpdir_and_table(&|_, table| {
-            // create table and check original value
-            let mut records = create_fake_table(&table.path, false)?;
+    fn in_place_writes_clear_checksum() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
             add_fields(&mut table.record_header)?;
-            table.header.record_count = records.len() as u64;
-
-            // read old record value
-            let pos = table.calc_record_pos(2);
-            let mut buf = [0u8; ADD_FIELDS_RECORD_BYTES];
-            let file = File::open(&table.path)?;
-            let mut reader = BufReader::new(file);
-            let mut old_bytes_before = vec!(0u8; pos as usize);
-            let mut old_bytes_after = vec!(0u8; ADD_FIELDS_RECORD_BYTES);
-            reader.read_exact(&mut old_bytes_before)?;
-            reader.read_exact(&mut buf)?;
-            reader.read_exact(&mut old_bytes_after)?;
-            let expected = [
-                // foo field
-                0, 0, 1u8, 77u8,
-                // bar field
-                0, 0, 0, 3u8, 51u8, 114u8, 100u8, 0, 0
-            ];
-            assert_eq!(expected, buf);
+            table.header.user_flags = true;
+            table.load_or_create(false, true)?;
+            let records = fake_records()?;
+            for (index, record) in records.iter().enumerate() {
+                table.save_record(index as u64, record, true)?;
+            }
 
-            // save record and check saved record value
-            let expected = [
-                // foo field
-                0, 0, 0, 11u8,
-                // bar field
-                0, 0, 0, 5u8, 104u8, 101u8, 108u8, 108u8, 111u8
+            // a stored checksum must either be absent or match the data
+            let verify = |path: &PathBuf| -> Result<bool> {
+                let table = Table::from_file(path.clone())?;
+                Ok(match table.header.checksum {
+                    Some(checksum) => checksum == table.data_checksum()?,
+                    None => true
+                })
+            };
+            type Edit<'a> = &'a dyn Fn(&mut Table) -> Result<()>;
+            let edits: [Edit; 5] = [
+                &|t| t.update_field(1, "bar", &Value::Str("xyz".to_string())),
+                &|t| t.overwrite_record(0, &records[2]),
+                &|t| t.save_record(2, &records[0], true),
+                &|t| t.save_record(3, &records[1], true),
+                &|t| t.set_flags(1, 1)
             ];
-            records[2].set("foo", Value::I32(11))?;
-            records[2].set("bar", Value::Str("hello".to_string()))?;
-            if let Err(e) = table.save_record(2, &records[2], true) {
-                assert!(false, "expected success but got error: {:?}", e)
+            for edit in edits {
+                table.header.checksum = Some(table.data_checksum()?);
+                table.save_headers()?;
+                assert!(verify(&table.path)?);
+                edit(table)?;
+                assert_eq!(None, table.header.checksum);
+                assert!(verify(&table.path)?);
             }
-            reader.seek(SeekFrom::Start(0))?;
-            let mut new_bytes_before = vec!(0u8; pos as usize);
-            let mut new_bytes_after = vec!(0u8; ADD_FIELDS_RECORD_BYTES);
-            reader.read_exact(&mut new_bytes_before)?;
-            reader.read_exact(&mut buf)?;
-            reader.read_exact(&mut new_bytes_after)?;
-            assert_eq!(old_bytes_before, new_bytes_before);
-            assert_eq!(expected, buf);
-            assert_eq!(old_bytes_after, new_bytes_after);
-
             Ok(())
         });
     }
 
     #[test]
-    fn save_record_into_without_fields() {
-        with_tmpdir_and_table(&|_, table| {
-            // create table and create expected table file contents
-            let mut records = create_fake_table(&table.path, true)?;
-            let mut expected = Vec::new();
-            let file = File::open(&table.path)?;
-            let mut reader = BufReader::new(file);
-            reader.read_to_end(&mut expected)?;
+    fn user_flags_disabled() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
 
             // test
-            records[2].set("foo", Value::I32(11))?;
-            records[2].set("bar", Value::Str("hello".to_string()))?;
-            match table.save_record(2, &records[2], true) {
-                Ok(()) => assert!(false, "expected TableError::NoFields but got success"),
-                Err(e) => match e.downcast::<TableError>() {
-                    Ok(ex) => match ex {
-                        TableError::NoFields => {},
-                        te => assert!(false, "expected TableError::NoFields but got TableError::{:?}", te)
-                    },
-                    Err(ex) => assert!(false, "expected TableError::NoFields but got error: {:?}", ex)
-                }
+            let expected = "user flags are disabled on this table";
+            match table.get_flags(0) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            match table.set_flags(0, 1) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            match table.filter_by_flags(1) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
             }
+            Ok(())
+        });
+    }
 
-            // check file after invalid save, it shouldn't change
-            let mut buf = Vec::new();
-            let file = File::open(&table.path)?;
-            let mut reader = BufReader::new(file);
-            reader.read_to_end(&mut buf)?;
-            assert_eq!(expected, buf);
+    #[test]
+    fn write_from_iter_with_trim_mode() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add("name", FieldType::Str(6))?;
+            let new_records = |header: &RecordHeader| -> Result<Vec<Result<Record>>> {
+                let mut record = header.new_record()?;
+                record.set("name", Value::Str("abc   ".to_string()))?;
+                Ok(vec![Ok(record)])
+            };
 
+            // test trim off
+            let path = dir.path().join("padded.fmtable");
+            Table::write_from_iter(&path, &header, new_records(&header)?.into_iter())?;
+            let table = Table::from_file(path)?;
+            let record = table.record(0)?.unwrap();
+            assert_eq!(Some(&Value::Str("abc   ".to_string())), record.get("name"));
+
+            // test trim on
+            header.set_trim_mode(TrimMode::End);
+            let path = dir.path().join("trimmed.fmtable");
+            Table::write_from_iter(&path, &header, new_records(&header)?.into_iter())?;
+            let table = Table::from_file(path)?;
+            let record = table.record(0)?.unwrap();
+            assert_eq!(Some(&Value::Str("abc".to_string())), record.get("name"));
             Ok(())
         });
     }
 
     #[test]
-    fn healthcheck_new_table() {
-        with_tmpdir_and_table(&|_, table| -> Result<()> {
-            // test healthcheck status
-            let expected = Status::New;
-            match table.healthcheck() {
-                Ok(status) => assert_eq!(expected , status),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            }
-
+    fn write_from_iter_with_endianness() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.set_endianness(Endianness::Little);
+            let mut record = header.new_record()?;
+            record.set("id", Value::I32(2064390957i32))?;
+            let path = dir.path().join("little.fmtable");
+            Table::write_from_iter(&path, &header, vec![Ok(record)].into_iter())?;
+
+            // the record bytes are stored in little-endian order
+            let mut table = Table::from_file(path.clone())?;
+            assert_eq!(Endianness::Little, table.record_header.endianness());
+            let buf = std::fs::read(&path)?;
+            let pos = table.calc_record_pos(0) as usize;
+            assert_eq!([45u8, 27u8, 12u8, 123u8], buf[pos..pos+4]);
+            let record = table.record(0)?.unwrap();
+            assert_eq!(Some(&Value::I32(2064390957i32)), record.get("id"));
+
+            // random access writes keep the byte order
+            table.update_field(0, "id", &Value::I32(1))?;
+            let buf = std::fs::read(&path)?;
+            assert_eq!([1u8, 0u8, 0u8, 0u8], buf[pos..pos+4]);
             Ok(())
         });
     }
 
     #[test]
-    fn healthcheck_new_index_with_empty_file() {
+    fn str_field_usage() {
         with_tmpdir_and_table(&|_, table| -> Result<()> {
-            // test healthcheck status
-            table.new_writer(true)?;
-            let expected = Status::New;
-            match table.healthcheck() {
-                Ok(status) => assert_eq!(expected , status),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.add("name", FieldType::Str(50))?;
+            let mut records = Vec::new();
+            for (id, name) in ["foo", "longest name", "", "bar baz"].iter().enumerate() {
+                let mut record = header.new_record()?;
+                record.set("id", Value::I32(id as i32))?;
+                record.set("name", Value::Str(name.to_string()))?;
+                records.push(Ok(record));
             }
+            Table::write_from_iter(&table.path, &header, records.into_iter())?;
+            table.load_headers_from(&mut table.new_reader()?)?;
 
+            // test
+            assert_eq!((12, 50), table.str_field_usage("name")?);
+            match table.str_field_usage("id") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"id\": field type is not a string type", e.to_string())
+            }
+            match table.str_field_usage("abc") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"abc\" doesn't exists within the header", e.to_string())
+            }
             Ok(())
         });
     }
 
     #[test]
-    fn healthcheck_corrupted_headers() {
+    fn field_stats() {
         with_tmpdir_and_table(&|_, table| -> Result<()> {
-            let buf = [0u8; 5];
-            create_file_with_bytes(&table.path, &buf)?;
-            let expected = Status::Corrupted;
-            match table.healthcheck() {
-                Ok(status) => assert_eq!(expected , status),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.add("name", FieldType::Str(50))?;
+            header.add_nullable("score", FieldType::F64)?;
+            let rows = [
+                (Value::I32(7), "foo", Value::F64(1.5)),
+                (Value::I32(-3), "", Value::Null),
+                (Value::Default, "bar", Value::F64(-2.25)),
+                (Value::I32(12), "baz", Value::Null)
+            ];
+            let mut records = Vec::new();
+            for (id, name, score) in rows {
+                let mut record = header.new_record()?;
+                record.set("id", id)?;
+                record.set("name", Value::Str(name.to_string()))?;
+                record.set("score", score)?;
+                records.push(Ok(record));
             }
+            Table::write_from_iter(&table.path, &header, records.into_iter())?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // test
+            let expected = serde_json::json!({
+                "id": {"count": 3, "min": -3, "max": 12},
+                "name": {"count": 3},
+                "score": {"count": 2, "min": -2.25, "max": 1.5}
+            });
+            assert_eq!(expected, table.field_stats()?);
             Ok(())
         });
     }
-    
+
     #[test]
-    fn healthcheck_corrupted() {
-        with_tmpdir_and_table(&|_, table| -> Result<()> {
-            let mut buf = [0u8; Header::BYTES+EMPTY_RECORD_HEADER_BYTES+5];
-            let mut writer = &mut buf as &mut [u8];
-            let mut header = Header::new("my_table")?;
-            header.record_count = 10;
-            header.write_to(&mut writer)?;
+    fn concat_into() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let other_path = dir.path().join("other.fmtable");
+            create_fake_table(&other_path, false)?;
+            let out = dir.path().join("merged.fmtable");
 
-            create_file_with_bytes(&table.path, &buf)?;
-            add_fields(&mut table.record_header)?;
-            assert_eq!(Status::Corrupted, table.healthcheck()?);
+            // test
+            assert_eq!(8, Table::concat_into(&out, &[&table.path, &other_path])?);
+            let merged = Table::from_file(out)?;
+            assert_eq!("merged", merged.header.get_name());
+            assert_eq!(8, merged.header.record_count);
+            assert!(table.record_header.structurally_eq(&merged.record_header));
+            for index in 0..8u64 {
+                assert_eq!(Some(&records[index as usize % 4]), merged.record(index)?.as_ref());
+            }
             Ok(())
         });
     }
-    
+
     #[test]
-    fn healthcheck_good() {
-        with_tmpdir_and_table(&|_, table| -> Result<()> {
-            create_fake_table(&table.path, false)?;
-            assert_eq!(Status::Good, table.healthcheck()?);
+    fn concat_into_compressed() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let compressed_path = dir.path().join("compressed.fmtable");
+            Table::write_compressed_from_iter(&compressed_path, &table.record_header, table.read_records()?)?;
+            let out = dir.path().join("merged.fmtable");
+
+            // test
+            assert_eq!(8, Table::concat_into(&out, &[&compressed_path, &table.path])?);
+            let merged = Table::from_file(out)?;
+            assert!(!merged.header.compressed);
+            for index in 0..8u64 {
+                assert_eq!(Some(&records[index as usize % 4]), merged.record(index)?.as_ref());
+            }
             Ok(())
         });
     }
-    
+
     #[test]
-    fn healthcheck_no_fields() {
-        with_tmpdir_and_table(&|_, table| -> Result<()> {
-            let mut writer = table.new_writer(true)?;
-            table.save_headers_into(&mut writer)?;
-            assert_eq!(Status::NoFields, table.healthcheck()?);
+    fn concat_into_with_endianness() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut header = table.record_header.clone();
+            header.set_endianness(Endianness::Little);
+            let little_path = dir.path().join("little.fmtable");
+            Table::write_from_iter(&little_path, &header, table.read_records()?)?;
+            let out = dir.path().join("merged.fmtable");
+
+            // records using another byte order are decoded
+            assert_eq!(8, Table::concat_into(&out, &[&table.path, &little_path])?);
+            let merged = Table::from_file(out)?;
+            assert!(table.record_header.structurally_eq(&merged.record_header));
+            for index in 0..8u64 {
+                assert_eq!(Some(&records[index as usize % 4]), merged.record(index)?.as_ref());
+            }
             Ok(())
         });
     }
 
     #[test]
-    fn save_headers_into() {
-        with_tmpdir_and_table(&|_, table| -> Result<()> {
-            // create table file and read table header data
+    fn concat_into_schema_mismatch() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
             create_fake_table(&table.path, false)?;
-            let mut reader = table.new_reader()?;
-            let size = Header::BYTES + 122;
-            let mut expected = vec![0u8; size];
-            reader.read_exact(&mut expected)?;
-            reader.rewind()?;
-            table.header.load_from(&mut reader)?;
-            table.record_header.load_from(&mut reader)?;
+            let mut header = RecordHeader::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(6))?;
+            let mut record = header.new_record()?;
+            record.set("bar", Value::Str("abc".to_string()))?;
+            let other_path = dir.path().join("other.fmtable");
+            Table::write_from_iter(&other_path, &header, vec![Ok(record)].into_iter())?;
+            let out = dir.path().join("merged.fmtable");
 
-            // test save table header
-            let mut buf = vec![0u8; size];
-            let wrt = &mut buf as &mut [u8];
-            let mut writer = Cursor::new(wrt);
-            if let Err(e) = table.save_headers_into(&mut writer) {
-                assert!(false, "expected success but got error: {:?}", e);
-            };
-            assert_eq!(expected, buf);
-            
+            // test
+            let expected = format!(
+                "table \"{}\" fields don't match table \"{}\" fields",
+                other_path.to_string_lossy(),
+                table.path.to_string_lossy()
+            );
+            match Table::concat_into(&out, &[&table.path, &other_path]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            assert!(!out.exists());
             Ok(())
         });
     }
 
     #[test]
-    fn save_headers() {
-        with_tmpdir_and_table(&|_, table| -> Result<()> {
-            // create table file and read table header data
+    fn concat_into_same_file() {
+        with_tmpdir_and_table(&|dir, table| -> Result<()> {
             create_fake_table(&table.path, false)?;
-            let mut reader = table.new_reader()?;
-            let size = Header::BYTES + 122;
-            let mut expected = vec![0u8; size];
-            reader.read_exact(&mut expected)?;
-            reader.rewind()?;
-            table.header.load_from(&mut reader)?;
-            table.record_header.load_from(&mut reader)?;
+            let expected = std::fs::read(&table.path)?;
 
-            // test save table header
-            assert_eq!(4, table.header.record_count);
-            table.header.record_count = 5;
-            if let Err(e) = table.save_headers() {
-                assert!(false, "expected success but got error: {:?}", e);
-            };
-            table.header.record_count = 4;
-            assert_eq!(4, table.header.record_count);
-            reader.rewind()?;
-            table.header.load_from(&mut reader)?;
-            assert_eq!(5, table.header.record_count);
-            
+            // test
+            let alias = dir.path().join(".").join("t.fmtable");
+            for out in [&table.path, &alias] {
+                match Table::concat_into(out, &[&table.path]) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!("source and target table files must be different", e.to_string())
+                }
+            }
+            assert_eq!(expected, std::fs::read(&table.path)?);
             Ok(())
         });
     }
-}
\ No newline at end of file
+}
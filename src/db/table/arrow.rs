@@ -0,0 +1,341 @@
+use anyhow::{bail, Result};
+use arrow::array::{
+    ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, DurationNanosecondArray, Float32Array, Float64Array,
+    Int8Array, Int16Array, Int32Array, Int64Array, StringArray, TimestampMillisecondArray,
+    UInt8Array, UInt16Array, UInt32Array, UInt64Array
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use std::io::Write;
+use std::sync::Arc;
+use super::Table;
+use super::record::{FieldType, Value};
+
+/// Max record count per Arrow record batch.
+const BATCH_SIZE: usize = 8192;
+
+/// Arrow decimal precision used to export decimals, the digits an i64 can hold.
+const DECIMAL_PRECISION: u8 = 19;
+
+/// Returns the Arrow data type used to export a field type.
+/// 
+/// # Arguments
+/// 
+/// * `field_type` - Field type to map.
+pub fn arrow_data_type(field_type: &FieldType) -> DataType {
+    match field_type {
+        FieldType::Bool => DataType::Boolean,
+        FieldType::I8 => DataType::Int8,
+        FieldType::I16 => DataType::Int16,
+        FieldType::I32 => DataType::Int32,
+        FieldType::I64 => DataType::Int64,
+        FieldType::U8 => DataType::UInt8,
+        FieldType::U16 => DataType::UInt16,
+        FieldType::U32 => DataType::UInt32,
+        FieldType::U64 => DataType::UInt64,
+        FieldType::F32 => DataType::Float32,
+        FieldType::F64 => DataType::Float64,
+        FieldType::Str(_)
+        | FieldType::StrVar
+        | FieldType::IpAddr
+        | FieldType::Char
+        | FieldType::Enum8(_)
+        | FieldType::Enum{..}
+        | FieldType::Uuid
+        | FieldType::Json(_)
+        | FieldType::Array{..} => DataType::Utf8,
+        FieldType::Duration => DataType::Duration(TimeUnit::Nanosecond),
+        FieldType::Date => DataType::Date32,
+        FieldType::Timestamp => DataType::Timestamp(TimeUnit::Millisecond, None),
+        FieldType::Bytes(_) => DataType::Binary,
+        FieldType::Decimal{scale} => DataType::Decimal128(DECIMAL_PRECISION, *scale as i8)
+    }
+}
+
+/// Collects a column values as optional values, [Value::Default] and
+/// [Value::Null] are collected as `None`.
+/// 
+/// # Arguments
+/// 
+/// * `field_type` - Column field type.
+/// * `values` - Column values.
+/// * `f` - Function to convert a value, returns `None` when the value doesn't match the field type.
+fn collect<T>(field_type: &FieldType, values: &[Value], f: impl Fn(&Value) -> Option<T>) -> Result<Vec<Option<T>>> {
+    let mut list = Vec::with_capacity(values.len());
+    for value in values {
+        if let Value::Default | Value::Null = value {
+            list.push(None);
+            continue;
+        }
+        match f(value) {
+            Some(v) => list.push(Some(v)),
+            None => bail!("value {:?} doesn't match the field type {:?}", value, field_type)
+        }
+    }
+    Ok(list)
+}
+
+/// Builds an Arrow array from a column values.
+/// 
+/// # Arguments
+/// 
+/// * `field_type` - Column field type.
+/// * `values` - Column values.
+fn build_array(field_type: &FieldType, values: &[Value]) -> Result<ArrayRef> {
+    let array: ArrayRef = match field_type {
+        FieldType::Bool => Arc::new(BooleanArray::from(collect(field_type, values, |v| match v {
+            Value::Bool(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::I8 => Arc::new(Int8Array::from(collect(field_type, values, |v| match v {
+            Value::I8(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::I16 => Arc::new(Int16Array::from(collect(field_type, values, |v| match v {
+            Value::I16(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::I32 => Arc::new(Int32Array::from(collect(field_type, values, |v| match v {
+            Value::I32(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::I64 => Arc::new(Int64Array::from(collect(field_type, values, |v| match v {
+            Value::I64(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::U8 => Arc::new(UInt8Array::from(collect(field_type, values, |v| match v {
+            Value::U8(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::U16 => Arc::new(UInt16Array::from(collect(field_type, values, |v| match v {
+            Value::U16(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::U32 => Arc::new(UInt32Array::from(collect(field_type, values, |v| match v {
+            Value::U32(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::U64 => Arc::new(UInt64Array::from(collect(field_type, values, |v| match v {
+            Value::U64(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::F32 => Arc::new(Float32Array::from(collect(field_type, values, |v| match v {
+            Value::F32(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::F64 => Arc::new(Float64Array::from(collect(field_type, values, |v| match v {
+            Value::F64(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::Str(_) | FieldType::StrVar | FieldType::Enum8(_) => Arc::new(StringArray::from(collect(field_type, values, |v| match v {
+            Value::Str(v) => Some(v.clone()),
+            _ => None
+        })?)),
+        FieldType::IpAddr => Arc::new(StringArray::from(collect(field_type, values, |v| match v {
+            Value::IpAddr(v) => Some(v.to_string()),
+            _ => None
+        })?)),
+        FieldType::Char => Arc::new(StringArray::from(collect(field_type, values, |v| match v {
+            Value::Char(v) => Some(v.to_string()),
+            _ => None
+        })?)),
+        FieldType::Uuid => Arc::new(StringArray::from(collect(field_type, values, |v| match v {
+            Value::Uuid(_) => Some(v.uuid_to_hyphenated()),
+            _ => None
+        })?)),
+        FieldType::Json(_) => Arc::new(StringArray::from(collect(field_type, values, |v| match v {
+            Value::Json(v) => Some(v.to_string()),
+            _ => None
+        })?)),
+        FieldType::Array{..} => Arc::new(StringArray::from(collect(field_type, values, |v| match v {
+            Value::Array(_) => Some(v.to_string()),
+            _ => None
+        })?)),
+        FieldType::Duration => Arc::new(DurationNanosecondArray::from(collect(field_type, values, |v| match v {
+            Value::Duration(v) => i64::try_from(v.as_nanos()).ok(),
+            _ => None
+        })?)),
+        FieldType::Date => Arc::new(Date32Array::from(collect(field_type, values, |v| match v {
+            Value::Date(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::Timestamp => Arc::new(TimestampMillisecondArray::from(collect(field_type, values, |v| match v {
+            Value::Timestamp(v) => Some(*v),
+            _ => None
+        })?)),
+        FieldType::Bytes(_) => Arc::new(collect(field_type, values, |v| match v {
+            Value::Bytes(v) => Some(v.clone()),
+            _ => None
+        })?.into_iter().collect::<BinaryArray>()),
+        FieldType::Decimal{scale} => Arc::new(Decimal128Array::from(collect(field_type, values, |v| match v {
+            Value::Decimal(v, _) => Some(*v as i128),
+            _ => None
+        })?).with_precision_and_scale(DECIMAL_PRECISION, *scale as i8)?),
+        FieldType::Enum{..} => Arc::new(StringArray::from(collect(field_type, values, |v| {
+            field_type.enum_label(v).map(|v| v.to_string())
+        })?))
+    };
+    Ok(array)
+}
+
+impl Table {
+    /// Builds the Arrow schema for the table fields, every field is
+    /// nullable since [Value::Default] and [Value::Null] are exported as null.
+    pub fn arrow_schema(&self) -> SchemaRef {
+        let fields: Vec<Field> = self.record_header.iter().map(|field| {
+            Field::new(field.get_name(), arrow_data_type(field.get_type()), true)
+        }).collect();
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Writes a record batch from the buffered columns and clears them.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Arrow stream writer.
+    /// * `schema` - Table Arrow schema.
+    /// * `columns` - Buffered column values.
+    fn write_arrow_batch(&self, writer: &mut StreamWriter<&mut impl Write>, schema: &SchemaRef, columns: &mut [Vec<Value>]) -> Result<()> {
+        let mut arrays = Vec::with_capacity(columns.len());
+        for (field, values) in self.record_header.iter().zip(columns.iter_mut()) {
+            match build_array(field.get_type(), values) {
+                Ok(v) => arrays.push(v),
+                Err(e) => bail!("field '{}': {}", field.get_name(), e)
+            }
+            values.clear();
+        }
+        let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+        writer.write(&batch)?;
+        Ok(())
+    }
+
+    /// Exports the table records as an Arrow IPC stream.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    pub fn export_arrow(&self, writer: &mut impl Write) -> Result<()> {
+        let schema = self.arrow_schema();
+        let mut stream = StreamWriter::try_new(writer, &schema)?;
+        let mut columns: Vec<Vec<Value>> = vec![Vec::new(); self.record_header.len()];
+        let mut pending = 0;
+        for record in self.read_records()? {
+            let record = record?;
+            for (column, (_, value)) in columns.iter_mut().zip(record.iter()) {
+                column.push(value.clone());
+            }
+            pending += 1;
+            if pending >= BATCH_SIZE {
+                self.write_arrow_batch(&mut stream, &schema, &mut columns)?;
+                pending = 0;
+            }
+        }
+        if pending > 0 {
+            self.write_arrow_batch(&mut stream, &schema, &mut columns)?;
+        }
+        stream.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Array;
+    use arrow::ipc::reader::StreamReader;
+    use std::io::Cursor;
+    use std::time::Duration;
+    use crate::db::table::test_helper::*;
+    use crate::db::table::record::Header as RecordHeader;
+
+    #[test]
+    fn arrow_data_type() {
+        assert_eq!(DataType::Boolean, super::arrow_data_type(&FieldType::Bool));
+        assert_eq!(DataType::Int32, super::arrow_data_type(&FieldType::I32));
+        assert_eq!(DataType::UInt64, super::arrow_data_type(&FieldType::U64));
+        assert_eq!(DataType::Float64, super::arrow_data_type(&FieldType::F64));
+        assert_eq!(DataType::Utf8, super::arrow_data_type(&FieldType::Str(10)));
+        assert_eq!(DataType::Utf8, super::arrow_data_type(&FieldType::IpAddr));
+        assert_eq!(DataType::Utf8, super::arrow_data_type(&FieldType::Char));
+        assert_eq!(DataType::Duration(TimeUnit::Nanosecond), super::arrow_data_type(&FieldType::Duration));
+        assert_eq!(DataType::Date32, super::arrow_data_type(&FieldType::Date));
+        assert_eq!(DataType::Timestamp(TimeUnit::Millisecond, None), super::arrow_data_type(&FieldType::Timestamp));
+        assert_eq!(DataType::Binary, super::arrow_data_type(&FieldType::Bytes(10)));
+    }
+
+    #[test]
+    fn build_array_with_defaults() -> Result<()> {
+        let values = [Value::I64(5), Value::Default, Value::I64(-2)];
+        let array = build_array(&FieldType::I64, &values)?;
+        let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(vec![Some(5), None, Some(-2)], array.iter().collect::<Vec<_>>());
+        assert_eq!(1, array.null_count());
+
+        let values = [Value::Default, Value::Str("foo".to_string())];
+        let array = build_array(&FieldType::Str(5), &values)?;
+        let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(vec![None, Some("foo")], array.iter().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn build_array_with_invalid_value() {
+        let values = [Value::I64(5), Value::Bool(true)];
+        match build_array(&FieldType::I64, &values) {
+            Ok(v) => panic!("expected error but got {:?}", v),
+            Err(e) => assert_eq!("value Bool(true) doesn't match the field type I64", e.to_string())
+        }
+    }
+
+    #[test]
+    fn export_arrow() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let mut header = RecordHeader::new();
+            header.add("id", FieldType::I32)?;
+            header.add("name", FieldType::Str(10))?;
+            header.add("active", FieldType::Bool)?;
+            header.add("elapsed", FieldType::Duration)?;
+            let mut records = Vec::new();
+            for (id, name, active) in [(1, "foo", true), (2, "bar", false)] {
+                let mut record = header.new_record()?;
+                record.set("id", Value::I32(id))?;
+                record.set("name", Value::Str(name.to_string()))?;
+                record.set("active", Value::Bool(active))?;
+                record.set("elapsed", Value::Duration(Duration::from_millis(1500)))?;
+                records.push(record);
+            }
+            let mut record = header.new_record()?;
+            record.set("id", Value::I32(3))?;
+            records.push(record);
+            Table::write_from_iter(&table.path, &header, records.into_iter().map(Ok))?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // test
+            let mut buf = Vec::new();
+            table.export_arrow(&mut buf)?;
+            let reader = StreamReader::try_new(Cursor::new(buf), None)?;
+            let expected = Schema::new(vec![
+                Field::new("id", DataType::Int32, true),
+                Field::new("name", DataType::Utf8, true),
+                Field::new("active", DataType::Boolean, true),
+                Field::new("elapsed", DataType::Duration(TimeUnit::Nanosecond), true)
+            ]);
+            assert_eq!(&expected, reader.schema().as_ref());
+            let batches = reader.collect::<std::result::Result<Vec<_>, _>>()?;
+            assert_eq!(1, batches.len());
+            let batch = &batches[0];
+            assert_eq!(3, batch.num_rows());
+
+            let ids = batch.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+            assert_eq!(vec![Some(1), Some(2), Some(3)], ids.iter().collect::<Vec<_>>());
+            let names = batch.column(1).as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!(vec![Some("foo"), Some("bar"), Some("")], names.iter().collect::<Vec<_>>());
+            let active = batch.column(2).as_any().downcast_ref::<BooleanArray>().unwrap();
+            assert_eq!(vec![Some(true), Some(false), Some(false)], active.iter().collect::<Vec<_>>());
+            let elapsed = batch.column(3).as_any().downcast_ref::<DurationNanosecondArray>().unwrap();
+            assert_eq!(vec![Some(1_500_000_000), Some(1_500_000_000), Some(0)], elapsed.iter().collect::<Vec<_>>());
+            Ok(())
+        });
+    }
+}
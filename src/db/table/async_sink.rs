@@ -0,0 +1,113 @@
+use anyhow::{anyhow, Result};
+use std::future::Future;
+use tokio::sync::mpsc;
+use tokio::task;
+use super::Table;
+use super::record::Record;
+
+/// Max records read ahead while the sink is awaited.
+const READ_AHEAD: usize = 64;
+
+impl Table {
+    /// Streams every table record into an async sink and returns the
+    /// record count. Records are read on a blocking thread and the sink is
+    /// awaited once per record, stopping on the first read or sink error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `f` - Async sink called with each record.
+    pub async fn for_each_async<F, Fut>(&self, mut f: F) -> Result<u64>
+    where
+        F: FnMut(Record) -> Fut,
+        Fut: Future<Output = Result<()>>
+    {
+        let (sender, mut receiver) = mpsc::channel::<Result<Record>>(READ_AHEAD);
+        let table = self.clone();
+        let handle = task::spawn_blocking(move || {
+            let records = match table.read_records() {
+                Ok(v) => v,
+                Err(e) => {
+                    let _ = sender.blocking_send(Err(e));
+                    return;
+                }
+            };
+            for record in records {
+                let failed = record.is_err();
+
+                // stop reading when the receiver is gone
+                if sender.blocking_send(record).is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        let mut count = 0u64;
+        while let Some(record) = receiver.recv().await {
+            f(record?).await?;
+            count += 1;
+        }
+        if let Err(e) = handle.await {
+            return Err(anyhow!("record reader task failed: {}", e));
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::runtime::Builder;
+    use tokio::sync::Mutex;
+    use crate::db::table::test_helper::*;
+
+    #[test]
+    fn for_each_async() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let expected = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // test
+            let runtime = Builder::new_current_thread().build()?;
+            let records = Arc::new(Mutex::new(Vec::new()));
+            let count = runtime.block_on(table.for_each_async(|record| {
+                let records = records.clone();
+                async move {
+                    records.lock().await.push(record);
+                    Ok(())
+                }
+            }))?;
+            assert_eq!(4, count);
+            assert_eq!(expected, *records.blocking_lock());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn for_each_async_with_sink_error() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // test
+            let runtime = Builder::new_current_thread().build()?;
+            let mut calls = 0;
+            let result = runtime.block_on(table.for_each_async(|_| {
+                calls += 1;
+                let fail = calls > 2;
+                async move {
+                    if fail {
+                        return Err(anyhow!("sink is closed"));
+                    }
+                    Ok(())
+                }
+            }));
+            match result {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("sink is closed", e.to_string())
+            }
+            assert_eq!(3, calls);
+            Ok(())
+        });
+    }
+}
@@ -2,6 +2,7 @@ use std::io::{Read, Write};
 use std::convert::TryFrom;
 use anyhow::{bail, Result};
 use crate::traits::{ByteSized, FromByteSlice, WriteAsBytes, ReadFrom, WriteTo, LoadFrom};
+use crate::db::indexer::header::{Header as IndexHeader, HASH_SIZE};
 use super::VERSION;
 use super::record::header::FieldType;
 use super::record::value::Value;
@@ -18,6 +19,16 @@ pub const TABLE_NAME_MAX_SIZE: u32 = 50;
 /// Table name field.
 pub const TABLE_NAME_FIELD: FieldType = FieldType::Str(TABLE_NAME_MAX_SIZE);
 
+/// Table version previous to the record data checksum, still readable.
+pub const LEGACY_VERSION: u32 = 1;
+
+/// Version bit flag used to mark a table file as compressed.
+pub const COMPRESSED_FLAG: u32 = 0x8000_0000;
+
+/// Version bit flag used to mark a table file records as prefixed by an
+/// user flags byte.
+pub const USER_FLAGS_FLAG: u32 = 0x4000_0000;
+
 //// Describes an Indexer file header.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Header {
@@ -25,7 +36,19 @@ pub struct Header {
     pub record_count: u64,
 
     /// Table name.
-    _name: String
+    _name: String,
+
+    /// Records are stored as a gzip stream when true.
+    pub compressed: bool,
+
+    /// Records are prefixed by an user defined flags byte when true.
+    pub user_flags: bool,
+
+    /// Record data region checksum, only set by writers that track it.
+    pub checksum: Option<[u8; HASH_SIZE]>,
+
+    /// Table file layout version.
+    _version: u32
 }
 
 impl Header {
@@ -40,7 +63,11 @@ impl Header {
         }
         Ok(Self{
             record_count: 0,
-            _name: name.to_string()
+            _name: name.to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         })
     }
 
@@ -49,6 +76,27 @@ impl Header {
         &self._name
     }
 
+    /// Gets the table file layout version.
+    pub fn get_version(&self) -> u32 {
+        self._version
+    }
+
+    /// Upgrades the header to the current layout version. Only safe when
+    /// the whole table file is rewritten, since a different header size
+    /// moves every record.
+    pub fn upgrade_version(&mut self) {
+        self._version = VERSION;
+    }
+
+    /// Gets the header size in bytes based on its layout version, version
+    /// 1 headers have no checksum.
+    pub fn size_as_bytes(&self) -> u64 {
+        if self._version == LEGACY_VERSION {
+            return Self::LEGACY_BYTES as u64;
+        }
+        Self::BYTES as u64
+    }
+
     /// Serialize the instance to a fixed byte slice.
     pub fn as_bytes(&self) -> [u8; Self::BYTES] {
         let mut buf = [0u8; Self::BYTES];
@@ -59,8 +107,15 @@ impl Header {
         magic_buf.copy_from_slice(&MAGIC_NUMBER_BYTES);
         carry += MAGIC_NUMBER_SIZE;
 
-        // save version
-        VERSION.write_as_bytes(&mut buf[carry..carry+u32::BYTES]).unwrap();
+        // save version along the layout flags
+        let mut version = self._version;
+        if self.compressed {
+            version |= COMPRESSED_FLAG;
+        }
+        if self.user_flags {
+            version |= USER_FLAGS_FLAG;
+        }
+        version.write_as_bytes(&mut buf[carry..carry+u32::BYTES]).unwrap();
         carry += u32::BYTES;
 
         // save record count
@@ -71,6 +126,17 @@ impl Header {
         let name_value = Value::Str(self._name.clone());
         let mut name_writer = &mut buf[carry..carry+TABLE_NAME_FIELD.value_byte_size()] as &mut [u8];
         TABLE_NAME_FIELD.write_value(&mut name_writer, &name_value).unwrap();
+        carry += TABLE_NAME_FIELD.value_byte_size();
+
+        // save checksum flag and value, version 1 headers have no room for it
+        if self._version == LEGACY_VERSION {
+            return buf;
+        }
+        if let Some(checksum) = self.checksum {
+            buf[carry] = 1;
+            carry += 1;
+            buf[carry..carry+HASH_SIZE].copy_from_slice(&checksum);
+        }
 
         buf
     }
@@ -80,8 +146,17 @@ impl ByteSized for Header {
     /// Table header size in bytes.
     /// 
     /// Byte Format
+    /// `<magic_number:11><version:4><record_count:8><name_size:4><name_value:50><checksum_valid:1><checksum:32>`.
+    const BYTES: usize = 99 + MAGIC_NUMBER_SIZE;
+}
+
+impl Header {
+    /// Version 1 table header size in bytes, same as [Header::BYTES]
+    /// without the checksum.
+    /// 
+    /// Byte Format
     /// `<magic_number:11><version:4><record_count:8><name_size:4><name_value:50>`.
-    const BYTES: usize = 66 + MAGIC_NUMBER_SIZE;
+    pub const LEGACY_BYTES: usize = 66 + MAGIC_NUMBER_SIZE;
 }
 
 impl LoadFrom for Header {
@@ -89,7 +164,7 @@ impl LoadFrom for Header {
         // read data
         let mut carry = 0;
         let mut buf = [0u8; Self::BYTES];
-        reader.read_exact(&mut buf)?;
+        reader.read_exact(&mut buf[..Self::LEGACY_BYTES])?;
 
         // read and validate magic number
         if buf[carry..carry+MAGIC_NUMBER_SIZE] != MAGIC_NUMBER_BYTES {
@@ -99,7 +174,10 @@ impl LoadFrom for Header {
 
         // read and validate table version
         let version = u32::from_byte_slice(&buf[carry..carry+u32::BYTES])?;
-        if version != VERSION {
+        let compressed = version & COMPRESSED_FLAG != 0;
+        let user_flags = version & USER_FLAGS_FLAG != 0;
+        let version = version & !(COMPRESSED_FLAG | USER_FLAGS_FLAG);
+        if version != VERSION && version != LEGACY_VERSION {
             bail!("table version mismatch, expected {} buf found {}", VERSION, version);
         }
         carry += u32::BYTES;
@@ -111,9 +189,27 @@ impl LoadFrom for Header {
         // read table name
         let mut name_reader = &buf[carry..carry+TABLE_NAME_FIELD.value_byte_size()] as &[u8];
         let name_value = TABLE_NAME_FIELD.read_value(&mut name_reader)?;
+        carry += TABLE_NAME_FIELD.value_byte_size();
+
+        // read checksum, version 1 headers don't have it
+        let checksum = if version == LEGACY_VERSION {
+            None
+        } else {
+            reader.read_exact(&mut buf[Self::LEGACY_BYTES..])?;
+            if buf[carry] > 0 {
+                carry += 1;
+                Some(IndexHeader::clone_hash(&buf[carry..carry+HASH_SIZE])?)
+            } else {
+                None
+            }
+        };
 
         // save values
         self.record_count = record_count;
+        self.compressed = compressed;
+        self.user_flags = user_flags;
+        self.checksum = checksum;
+        self._version = version;
         self._name = match name_value {
             Value::Str(s) => s,
             _ => bail!("name value should be a string")
@@ -153,7 +249,7 @@ impl TryFrom<&[u8]> for Header {
 
 impl WriteTo for Header {
     fn write_to(&self, writer: &mut impl Write) -> Result<()> {
-        writer.write_all(&self.as_bytes())?;
+        writer.write_all(&self.as_bytes()[..self.size_as_bytes() as usize])?;
         Ok(())
     }
 }
@@ -172,7 +268,11 @@ pub mod test_helper {
     pub fn build_header_bytes(name: &str, record_count: u64) -> [u8; Header::BYTES] {
         Header{
             record_count,
-            _name: name.to_string()
+            _name: name.to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         }.as_bytes()
     }
 }
@@ -187,6 +287,16 @@ mod tests {
         assert_eq!(50, TABLE_NAME_MAX_SIZE);
     }
 
+    #[test]
+    fn compressed_flag() {
+        assert_eq!(0x8000_0000, COMPRESSED_FLAG);
+    }
+
+    #[test]
+    fn user_flags_flag() {
+        assert_eq!(0x4000_0000, USER_FLAGS_FLAG);
+    }
+
     #[test]
     fn table_name_field() {
         let expected = 50;
@@ -201,7 +311,11 @@ mod tests {
     fn new() {
         let expected = Header{
             record_count: 0,
-            _name: "hello".to_string()
+            _name: "hello".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         match Header::new("hello") {
             Ok(v) => assert_eq!(expected, v),
@@ -227,7 +341,7 @@ mod tests {
             // magic number
             100, 97, 116, 97, 104, 101, 110, 95, 116, 98, 108,
             // version
-            0, 0, 0, 1,
+            0, 0, 0, 2,
             // record count = 2311457452320998632
             32, 19, 242, 78, 103, 5, 196, 232,
             // name size
@@ -235,13 +349,21 @@ mod tests {
             // name value: "my_table"
             109, 121, 95, 116, 97, 98, 108, 101, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
             0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0
+            0, 0, 0, 0, 0,
+            // checksum flag and value
+            0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
         ];
 
         // test header as_bytes function
         let header = Header{
             record_count: 2311457452320998632,
-            _name: "my_table".to_string()
+            _name: "my_table".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         assert_eq!(expected, header.as_bytes());
 
@@ -250,7 +372,7 @@ mod tests {
             // magic number
             100, 97, 116, 97, 104, 101, 110, 95, 116, 98, 108,
             // version
-            0, 0, 0, 1,
+            0, 0, 0, 2,
             // record count = 4525325654675485867
             62, 205, 47, 180, 235, 228, 244, 171,
             // name size
@@ -258,20 +380,28 @@ mod tests {
             // name value: "hellotbl"
             104, 101, 108, 108, 111, 95, 116, 98, 108, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
             0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0
+            0, 0, 0, 0, 0,
+            // checksum flag and value
+            0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
         ];
 
         // test header as_bytes function
         let header = Header{
             record_count: 4525325654675485867,
-            _name: "hello_tbl".to_string()
+            _name: "hello_tbl".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         assert_eq!(expected, header.as_bytes());
     }
 
     #[test]
     fn byte_sized() {
-        assert_eq!(77, Header::BYTES);
+        assert_eq!(110, Header::BYTES);
     }
 
     #[test]
@@ -279,11 +409,19 @@ mod tests {
         // first random try
         let mut header = Header{
             record_count: 0,
-            _name: "".to_string()
+            _name: "".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let expected = Header{
             record_count: 4535435,
-            _name: "my_table".to_string()
+            _name: "my_table".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("my_table", 4535435);
         let mut reader = &buf as &[u8];
@@ -296,11 +434,19 @@ mod tests {
         // second random try
         let mut header = Header{
             record_count: 0,
-            _name: "".to_string()
+            _name: "".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let expected = Header{
             record_count: 6572646535124,
-            _name: "hello_tbl".to_string()
+            _name: "hello_tbl".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("hello_tbl", 6572646535124);
         let mut reader = &buf as &[u8];
@@ -316,7 +462,11 @@ mod tests {
         // first random try
         let expected = Header{
             record_count: 2341234,
-            _name: "my_table".to_string()
+            _name: "my_table".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("my_table", 2341234);
         let value = match Header::from_byte_slice(&buf) {
@@ -331,7 +481,11 @@ mod tests {
         // second random try
         let expected = Header{
             record_count: 9879873495743,
-            _name: "hello_tbl".to_string()
+            _name: "hello_tbl".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("hello_tbl", 9879873495743);
         let value = match Header::from_byte_slice(&buf) {
@@ -349,7 +503,11 @@ mod tests {
         // first random try
         let expected = Header{
             record_count: 974734838473874,
-            _name: "my_table".to_string()
+            _name: "my_table".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("my_table", 974734838473874);
         let mut reader = &buf as &[u8];
@@ -365,7 +523,11 @@ mod tests {
         // second random try
         let expected = Header{
             record_count: 3434232315645344,
-            _name: "hello_tbl".to_string()
+            _name: "hello_tbl".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("hello_tbl", 3434232315645344);
         let mut reader = &buf as &[u8];
@@ -384,7 +546,11 @@ mod tests {
         // first random try
         let expected = Header{
             record_count: 32412342134234,
-            _name: "my_table".to_string()
+            _name: "my_table".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("my_table", 32412342134234);
         let value = match Header::try_from(&buf[..]) {
@@ -399,7 +565,11 @@ mod tests {
         // second random try
         let expected = Header{
             record_count: 56535423143214,
-            _name: "hello_tbl".to_string()
+            _name: "hello_tbl".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let buf = build_header_bytes("hello_tbl", 56535423143214);
         let value = match Header::try_from(&buf[..]) {
@@ -418,7 +588,11 @@ mod tests {
         let expected = build_header_bytes("my_table", 788477630402843);
         let header = Header{
             record_count: 788477630402843,
-            _name: "my_table".to_string()
+            _name: "my_table".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let mut buf = [0u8; Header::BYTES];
         let mut writer = &mut buf as &mut [u8];
@@ -432,7 +606,11 @@ mod tests {
         let expected = build_header_bytes("hello_tbl", 63439320337562938);
         let header = Header{
             record_count: 63439320337562938,
-            _name: "hello_tbl".to_string()
+            _name: "hello_tbl".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
         };
         let mut buf = [0u8; Header::BYTES];
         let mut writer = &mut buf as &mut [u8];
@@ -442,4 +620,93 @@ mod tests {
         };
         assert_eq!(expected, buf);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn load_from_compressed() -> Result<()> {
+        let header = Header{
+            record_count: 3,
+            _name: "my_table".to_string(),
+            compressed: true,
+            user_flags: false,
+            checksum: None,
+            _version: VERSION
+        };
+        let buf = header.as_bytes();
+        let version = u32::from_byte_slice(&buf[MAGIC_NUMBER_SIZE..MAGIC_NUMBER_SIZE+u32::BYTES])?;
+        assert_eq!(VERSION | COMPRESSED_FLAG, version);
+
+        let mut loaded = Header::new("")?;
+        loaded.load_from(&mut &buf[..])?;
+        assert_eq!(header, loaded);
+        Ok(())
+    }
+
+    #[test]
+    fn load_from_with_checksum() -> Result<()> {
+        let mut checksum = [0u8; HASH_SIZE];
+        for (i, v) in checksum.iter_mut().enumerate() {
+            *v = i as u8 + 1;
+        }
+        let header = Header{
+            record_count: 5,
+            _name: "my_table".to_string(),
+            compressed: false,
+            user_flags: false,
+            checksum: Some(checksum),
+            _version: VERSION
+        };
+        let buf = header.as_bytes();
+        assert_eq!(1, buf[Header::BYTES - HASH_SIZE - 1]);
+        assert_eq!(checksum, buf[Header::BYTES - HASH_SIZE..]);
+
+        let mut loaded = Header::new("")?;
+        loaded.load_from(&mut &buf[..])?;
+        assert_eq!(header, loaded);
+        Ok(())
+    }
+
+    #[test]
+    fn load_from_legacy_version() -> Result<()> {
+        let mut header = Header::new("my_table")?;
+        header.record_count = 5;
+        header._version = LEGACY_VERSION;
+        header.checksum = Some([1u8; HASH_SIZE]);
+        assert_eq!(77, Header::LEGACY_BYTES);
+        assert_eq!(77, header.size_as_bytes());
+
+        // test the checksum isn't written
+        let mut buf = Vec::new();
+        header.write_to(&mut buf)?;
+        assert_eq!(Header::LEGACY_BYTES, buf.len());
+        assert_eq!(
+            LEGACY_VERSION,
+            u32::from_byte_slice(&buf[MAGIC_NUMBER_SIZE..MAGIC_NUMBER_SIZE+u32::BYTES])?
+        );
+
+        // test load
+        let mut loaded = Header::new("")?;
+        loaded.load_from(&mut &buf[..])?;
+        header.checksum = None;
+        assert_eq!(header, loaded);
+
+        // test upgrade
+        loaded.upgrade_version();
+        assert_eq!(VERSION, loaded.get_version());
+        assert_eq!(Header::BYTES as u64, loaded.size_as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn load_from_with_user_flags() -> Result<()> {
+        let mut header = Header::new("my_table")?;
+        header.user_flags = true;
+        let buf = header.as_bytes();
+        let version = u32::from_byte_slice(&buf[MAGIC_NUMBER_SIZE..MAGIC_NUMBER_SIZE+u32::BYTES])?;
+        assert_eq!(VERSION | USER_FLAGS_FLAG, version);
+
+        let mut loaded = Header::new("")?;
+        loaded.load_from(&mut &buf[..])?;
+        assert_eq!(header, loaded);
+        Ok(())
+    }
+}
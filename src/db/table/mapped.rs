@@ -0,0 +1,115 @@
+use anyhow::{bail, Result};
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::PathBuf;
+use crate::error::TableError;
+use super::Table;
+use super::record::Record;
+
+/// Read only table backed by a memory mapped table file, records are
+/// decoded straight from the mapped bytes for cheap random access.
+#[derive(Debug)]
+pub struct MappedTable {
+    /// Table whose headers were loaded from the mapped file.
+    pub table: Table,
+
+    /// Memory mapped table file.
+    mmap: Mmap
+}
+
+impl MappedTable {
+    /// Loads a table headers and memory maps its file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - Table file path.
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let table = Table::open_lazy(path)?;
+        if table.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if table.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !table.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        let file = File::open(&table.path)?;
+
+        // SAFETY: the mapping is read only, the table file must not be
+        // truncated by another process while it is mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self{table, mmap})
+    }
+
+    /// Returns the complete record count available on the mapped file.
+    pub fn record_count(&self) -> u64 {
+        let data_size = (self.mmap.len() as u64).saturating_sub(self.table.calc_record_pos(0));
+        data_size / self.table.record_size()
+    }
+
+    /// Decodes a record from the mapped file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Record index.
+    pub fn record_at(&self, index: u64) -> Result<Record> {
+        let count = self.record_count();
+        if index >= count {
+            bail!("record index {} is out of bounds, the table file has {} records", index, count);
+        }
+        let start = (self.table.calc_record_pos(index) + self.table.record_prefix_size()) as usize;
+        let end = start + self.table.record_header.record_byte_size() as usize;
+        let mut buf = &self.mmap[start..end];
+        self.table.record_header.read_record(&mut buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::test_helper::*;
+    use std::fs::OpenOptions;
+
+    #[test]
+    fn record_at() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut expected = Vec::new();
+            for record in table.read_records()? {
+                expected.push(record?);
+            }
+            assert_eq!(4, expected.len());
+
+            // test
+            let mapped = MappedTable::open(table.path.clone())?;
+            assert_eq!(4, mapped.record_count());
+            for index in [2u64, 0, 3, 1] {
+                assert_eq!(expected[index as usize], mapped.record_at(index)?);
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn record_at_out_of_bounds() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+
+            // drop half of the last record
+            let size = std::fs::metadata(&table.path)?.len();
+            let file = OpenOptions::new().write(true).open(&table.path)?;
+            file.set_len(size - 5)?;
+
+            // test
+            let mapped = MappedTable::open(table.path.clone())?;
+            assert_eq!(3, mapped.record_count());
+            match mapped.record_at(3) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("record index 3 is out of bounds, the table file has 3 records", e.to_string())
+            }
+            Ok(())
+        });
+    }
+}
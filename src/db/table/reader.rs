@@ -0,0 +1,256 @@
+use anyhow::Result;
+use std::io::{ErrorKind, Read};
+use crate::error::ParseError;
+use super::record::Record;
+use super::record::header::Header as RecordHeader;
+
+/// Sequential record iterator over a record data region. It ends with
+/// `None` only when the data ends exactly at a record boundary, a trailing
+/// partial record is reported as [ParseError::SizeMismatch] unless the
+/// reader is lenient, see [RecordReader::set_lenient].
+pub struct RecordReader<R: Read> {
+    /// Byte reader positioned at a record boundary.
+    reader: R,
+
+    /// Record header used to decode the records.
+    record_header: RecordHeader,
+
+    /// Byte size of the prefix skipped before each record.
+    prefix_size: usize,
+
+    /// Record buffer.
+    buf: Vec<u8>,
+
+    /// `true` once the data end or an error has been reached.
+    done: bool,
+
+    /// Discards a trailing partial record instead of failing when `true`.
+    lenient: bool,
+
+    /// Fully read record count.
+    read_count: u64,
+
+    /// Byte size of the discarded trailing partial record.
+    partial_size: Option<usize>
+}
+
+impl<R: Read> RecordReader<R> {
+    /// Creates a new record reader.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader positioned at a record boundary.
+    /// * `record_header` - Record header used to decode the records.
+    pub fn new(reader: R, record_header: RecordHeader) -> Self {
+        Self::with_prefix(reader, record_header, 0)
+    }
+
+    /// Creates a new record reader that skips a prefix before each record.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader positioned at a record boundary.
+    /// * `record_header` - Record header used to decode the records.
+    /// * `prefix_size` - Byte size of the prefix skipped before each record.
+    pub fn with_prefix(reader: R, record_header: RecordHeader, prefix_size: usize) -> Self {
+        let buf = vec![0u8; prefix_size + record_header.record_byte_size() as usize];
+        Self{
+            reader,
+            record_header,
+            prefix_size,
+            buf,
+            done: false,
+            lenient: false,
+            read_count: 0,
+            partial_size: None
+        }
+    }
+
+    /// Sets whether a trailing partial record, like the one left by a
+    /// crash during an append, is discarded so the iteration ends cleanly
+    /// after the last complete record.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `lenient` - Discard a trailing partial record when `true`.
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Returns the count of records fully read so far.
+    pub fn read_count(&self) -> u64 {
+        self.read_count
+    }
+
+    /// Returns the byte size of the trailing partial record discarded by
+    /// a lenient reader, `None` when no record was discarded.
+    pub fn partial_record_size(&self) -> Option<usize> {
+        self.partial_size
+    }
+
+    /// Fills the record buffer and returns the read byte count, it is
+    /// smaller than the buffer size only when the data ends.
+    fn fill_buf(&mut self) -> Result<usize> {
+        let mut size = 0;
+        while size < self.buf.len() {
+            match self.reader.read(&mut self.buf[size..]) {
+                Ok(0) => break,
+                Ok(n) => size += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into())
+            }
+        }
+        Ok(size)
+    }
+
+    /// Reads the next record.
+    fn read_next(&mut self) -> Result<Option<Record>> {
+        let size = self.fill_buf()?;
+        if size < 1 {
+            return Ok(None);
+        }
+        if size < self.buf.len() {
+            if self.lenient {
+                self.partial_size = Some(size);
+                return Ok(None);
+            }
+            return Err(ParseError::SizeMismatch{
+                expected: self.buf.len(),
+                found: size
+            }.into());
+        }
+        let record = self.record_header.read_record(&mut &self.buf[self.prefix_size..])?;
+        self.read_count += 1;
+        Ok(Some(record))
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_next() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::test_helper::*;
+
+    #[test]
+    fn next_until_clean_end() -> Result<()> {
+        let mut header = RecordHeader::new();
+        add_fields(&mut header)?;
+        let records = fake_records()?;
+        let mut buf = Vec::new();
+        for record in records.iter() {
+            header.write_record(&mut buf, record)?;
+        }
+
+        // test
+        let mut reader = RecordReader::new(&buf[..], header);
+        for record in records.iter() {
+            assert_eq!(record, &reader.next().unwrap()?);
+        }
+        assert!(reader.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn next_with_trailing_partial_record() -> Result<()> {
+        let mut header = RecordHeader::new();
+        add_fields(&mut header)?;
+        let records = fake_records()?;
+        let mut buf = Vec::new();
+        header.write_record(&mut buf, &records[0])?;
+        let mut partial = Vec::new();
+        header.write_record(&mut partial, &records[1])?;
+        buf.extend_from_slice(&partial[..partial.len() / 2]);
+
+        // test
+        let mut reader = RecordReader::new(&buf[..], header);
+        assert_eq!(records[0], reader.next().unwrap()?);
+        match reader.next() {
+            Some(Err(e)) => match e.downcast::<ParseError>() {
+                Ok(ParseError::SizeMismatch{expected, found}) => {
+                    assert_eq!(partial.len(), expected);
+                    assert_eq!(partial.len() / 2, found);
+                },
+                Ok(ex) => panic!("expected ParseError::SizeMismatch but got {:?}", ex),
+                Err(ex) => panic!("expected ParseError::SizeMismatch but got {:?}", ex)
+            },
+            v => panic!("expected error but got {:?}", v)
+        }
+        assert!(reader.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn next_with_lenient_partial_record() -> Result<()> {
+        let mut header = RecordHeader::new();
+        add_fields(&mut header)?;
+        let records = fake_records()?;
+        let mut buf = Vec::new();
+        header.write_record(&mut buf, &records[0])?;
+        header.write_record(&mut buf, &records[1])?;
+        let mut partial = Vec::new();
+        header.write_record(&mut partial, &records[2])?;
+        buf.extend_from_slice(&partial[..5]);
+
+        // test
+        let mut reader = RecordReader::new(&buf[..], header);
+        reader.set_lenient(true);
+        let read: Vec<Record> = reader.by_ref().collect::<Result<_>>()?;
+        assert_eq!(records[..2], read[..]);
+        assert_eq!(2, reader.read_count());
+        assert_eq!(Some(5), reader.partial_record_size());
+        assert!(reader.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn next_with_lenient_clean_end() -> Result<()> {
+        let mut header = RecordHeader::new();
+        add_fields(&mut header)?;
+        let records = fake_records()?;
+        let mut buf = Vec::new();
+        for record in records.iter() {
+            header.write_record(&mut buf, record)?;
+        }
+
+        // test
+        let mut reader = RecordReader::new(&buf[..], header);
+        reader.set_lenient(true);
+        assert_eq!(3, reader.by_ref().count());
+        assert_eq!(3, reader.read_count());
+        assert_eq!(None, reader.partial_record_size());
+        Ok(())
+    }
+
+    #[test]
+    fn table_record_reader() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+
+            // test
+            let read: Vec<Record> = table.record_reader()?.collect::<Result<_>>()?;
+            assert_eq!(records, read);
+            Ok(())
+        });
+    }
+}
@@ -2,9 +2,12 @@ pub mod header;
 pub mod value;
 
 use serde::ser::{Serialize, Serializer, SerializeMap};
+use serde_json::{Map as JSMap, Value as JSValue};
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
 use anyhow::{bail, Result};
-pub use header::Header;
+pub use header::{Header, FieldType, TrimMode, decode_partial};
 pub use value::Value;
 
 /// Represents a data record.
@@ -64,8 +67,12 @@ impl Record {
     /// 
     /// * `index` - Field index.
     /// * `value` - New value.
-    pub fn set_by_index(&mut self, index: usize, value: Value) {
-        self._list[index].1 = value;
+    pub fn set_by_index(&mut self, index: usize, value: Value) -> Result<()> {
+        match self._list.get_mut(index) {
+            Some(item) => item.1 = value,
+            None => bail!("can't update: field index {} is out of range, the record has {} fields", index, self._list.len())
+        }
+        Ok(())
     }
 
     /// Get a value by name.
@@ -93,6 +100,98 @@ impl Record {
         None
     }
 
+    /// Get a value by name converted into a type, errors when the field
+    /// doesn't exists or the value variant doesn't match the type.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Field name.
+    pub fn get_as<T: TryFrom<Value, Error = anyhow::Error>>(&self, name: &str) -> Result<T> {
+        let value = match self.get(name) {
+            Some(v) => v.clone(),
+            None => bail!("unknown field \"{}\"", name)
+        };
+        match T::try_from(value) {
+            Ok(v) => Ok(v),
+            Err(e) => bail!("field \"{}\": {}", name, e)
+        }
+    }
+
+    /// Get a bool value by name, see [Self::get_as].
+    pub fn get_bool(&self, name: &str) -> Result<bool> {
+        self.get_as(name)
+    }
+
+    /// Get a i8 value by name, see [Self::get_as].
+    pub fn get_i8(&self, name: &str) -> Result<i8> {
+        self.get_as(name)
+    }
+
+    /// Get a i16 value by name, see [Self::get_as].
+    pub fn get_i16(&self, name: &str) -> Result<i16> {
+        self.get_as(name)
+    }
+
+    /// Get a i32 value by name, see [Self::get_as].
+    pub fn get_i32(&self, name: &str) -> Result<i32> {
+        self.get_as(name)
+    }
+
+    /// Get a i64 value by name, see [Self::get_as].
+    pub fn get_i64(&self, name: &str) -> Result<i64> {
+        self.get_as(name)
+    }
+
+    /// Get a u8 value by name, see [Self::get_as].
+    pub fn get_u8(&self, name: &str) -> Result<u8> {
+        self.get_as(name)
+    }
+
+    /// Get a u16 value by name, see [Self::get_as].
+    pub fn get_u16(&self, name: &str) -> Result<u16> {
+        self.get_as(name)
+    }
+
+    /// Get a u32 value by name, see [Self::get_as].
+    pub fn get_u32(&self, name: &str) -> Result<u32> {
+        self.get_as(name)
+    }
+
+    /// Get a u64 value by name, see [Self::get_as].
+    pub fn get_u64(&self, name: &str) -> Result<u64> {
+        self.get_as(name)
+    }
+
+    /// Get a f32 value by name, see [Self::get_as].
+    pub fn get_f32(&self, name: &str) -> Result<f32> {
+        self.get_as(name)
+    }
+
+    /// Get a f64 value by name, see [Self::get_as].
+    pub fn get_f64(&self, name: &str) -> Result<f64> {
+        self.get_as(name)
+    }
+
+    /// Get a String value by name, see [Self::get_as].
+    pub fn get_str(&self, name: &str) -> Result<String> {
+        self.get_as(name)
+    }
+
+    /// Get a IpAddr value by name, see [Self::get_as].
+    pub fn get_ip_addr(&self, name: &str) -> Result<IpAddr> {
+        self.get_as(name)
+    }
+
+    /// Get a char value by name, see [Self::get_as].
+    pub fn get_char(&self, name: &str) -> Result<char> {
+        self.get_as(name)
+    }
+
+    /// Get a Duration value by name, see [Self::get_as].
+    pub fn get_duration(&self, name: &str) -> Result<Duration> {
+        self.get_as(name)
+    }
+
     /// Returns the number of fields on the header.
     pub fn len(&self) -> usize {
         self._list.len()
@@ -102,6 +201,57 @@ impl Record {
     pub fn iter(&self) -> std::slice::Iter<(String, Value)> {
         self._list.iter()
     }
+
+    /// Converts the record into a JSON object keyed by field name,
+    /// [Value::Default] and [Value::Null] are converted into JSON null.
+    pub fn to_json(&self) -> JSValue {
+        let mut obj = JSMap::new();
+        for (name, value) in self._list.iter() {
+            obj.insert(name.to_string(), value.into());
+        }
+        JSValue::Object(obj)
+    }
+
+    /// Returns the first field whose value isn't valid for the header
+    /// field type along with the expected type and the offending value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `header` - Record header to validate against.
+    pub fn first_invalid(&self, header: &Header) -> Option<(String, FieldType, Value)> {
+        for (index, field) in header.iter().enumerate() {
+            if let Some(value) = self.get_by_index(index) {
+                if !field.is_valid(value) {
+                    return Some((field.get_name().to_string(), field.get_type().clone(), value.clone()));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the name, old value and new value of every field whose
+    /// value differs between both records, following this record's
+    /// field order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Record to compare against.
+    pub fn diff(&self, other: &Record) -> Result<Vec<(String, Value, Value)>> {
+        if self.len() != other.len() {
+            bail!("can't diff records with different fields");
+        }
+        let mut changes = Vec::new();
+        for (name, old) in self._list.iter() {
+            let new = match other.get(name) {
+                Some(v) => v,
+                None => bail!("can't diff records with different fields")
+            };
+            if old != new {
+                changes.push((name.to_string(), old.clone(), new.clone()));
+            }
+        }
+        Ok(changes)
+    }
 }
 
 impl Serialize for Record {
@@ -307,9 +457,9 @@ mod tests {
             assert_eq!(("bar".to_string(), Value::U64(34u64)), record._list[2]);
 
             // update values
-            record.set_by_index(0, Value::F32(657.54f32));
-            record.set_by_index(1, Value::I64(956i64));
-            record.set_by_index(2, Value::U64(45596u64));
+            record.set_by_index(0, Value::F32(657.54f32)).unwrap();
+            record.set_by_index(1, Value::I64(956i64)).unwrap();
+            record.set_by_index(2, Value::U64(45596u64)).unwrap();
 
             // check the new values
             assert_eq!(3, record._list.len());
@@ -319,6 +469,21 @@ mod tests {
             assert_eq!(("bar".to_string(), Value::U64(45596u64)), record._list[2]);
         }
 
+        #[test]
+        fn set_by_index_out_of_range() -> Result<()> {
+            let mut record = Record::new();
+            record.add("foo", Value::F32(23.12f32))?;
+            match record.set_by_index(1, Value::F32(1f32)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("can't update: field index 1 is out of range, the record has 1 fields", e.to_string())
+            }
+            match record.set("bar", Value::F32(1f32)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("can't update: unknown field \"bar\"", e.to_string())
+            }
+            Ok(())
+        }
+
         #[test]
         fn get_by_index_existing() {
             let mut record = Record::new();
@@ -513,5 +678,135 @@ mod tests {
             assert_eq!(expected_list, list);
             assert_eq!(expected_map, map);
         }
+    
+        #[test]
+        fn first_invalid() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(3))?;
+            header.add("abc", FieldType::Bool)?;
+            let mut record = header.new_record()?;
+            record.set("foo", 11i32.into())?;
+            record.set("bar", "abcd".into())?;
+            record.set("abc", 1i8.into())?;
+
+            // test
+            let expected = ("bar".to_string(), FieldType::Str(3), Value::Str("abcd".to_string()));
+            assert_eq!(Some(expected), record.first_invalid(&header));
+            Ok(())
+        }
+
+        #[test]
+        fn first_invalid_with_valid_record() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(3))?;
+            let mut record = header.new_record()?;
+            record.set("foo", 11i32.into())?;
+            record.set("bar", "abc".into())?;
+
+            // test
+            assert_eq!(None, record.first_invalid(&header));
+            Ok(())
+        }
+    
+        #[test]
+        fn typed_getters() -> Result<()> {
+            let mut record = Record::new();
+            record.add("id", Value::U32(7))?;
+            record.add("name", Value::Str("foo".to_string()))?;
+            record.add("active", Value::Bool(true))?;
+            record.add("price", Value::F64(9.5))?;
+
+            // test
+            assert_eq!(7, record.get_u32("id")?);
+            assert_eq!("foo", record.get_str("name")?);
+            assert!(record.get_bool("active")?);
+            assert_eq!(9.5, record.get_f64("price")?);
+            Ok(())
+        }
+
+        #[test]
+        fn typed_getters_with_errors() -> Result<()> {
+            let mut record = Record::new();
+            record.add("id", Value::U32(7))?;
+
+            // test wrong variant
+            match record.get_i64("id") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"id\": expected a I64 value but got U32(7)", e.to_string())
+            }
+
+            // test missing field
+            match record.get_u32("foo") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("unknown field \"foo\"", e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn to_json() {
+            let mut record = Record::new();
+            record.add("id", Value::I32(-7)).unwrap();
+            record.add("price", Value::F64(9.5)).unwrap();
+            record.add("active", Value::Bool(true)).unwrap();
+            record.add("name", Value::Str("foo".to_string())).unwrap();
+            record.add("empty", Value::Default).unwrap();
+            record.add("missing", Value::Null).unwrap();
+
+            // test
+            let expected = serde_json::json!({
+                "id": -7,
+                "price": 9.5,
+                "active": true,
+                "name": "foo",
+                "empty": null,
+                "missing": null
+            });
+            assert_eq!(expected, record.to_json());
+        }
+
+        #[test]
+        fn diff() -> Result<()> {
+            let mut old = Record::new();
+            old.add("id", Value::I32(1))?;
+            old.add("name", Value::Str("foo".to_string()))?;
+            old.add("active", Value::Bool(true))?;
+            let mut new = Record::new();
+            new.add("id", Value::I32(1))?;
+            new.add("name", Value::Str("bar".to_string()))?;
+            new.add("active", Value::Bool(false))?;
+
+            // test
+            let expected = vec![
+                ("name".to_string(), Value::Str("foo".to_string()), Value::Str("bar".to_string())),
+                ("active".to_string(), Value::Bool(true), Value::Bool(false))
+            ];
+            assert_eq!(expected, old.diff(&new)?);
+            assert!(old.diff(&old)?.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn diff_with_different_fields() -> Result<()> {
+            let mut old = Record::new();
+            old.add("id", Value::I32(1))?;
+            old.add("name", Value::Str("foo".to_string()))?;
+            let mut new = Record::new();
+            new.add("id", Value::I32(1))?;
+            new.add("title", Value::Str("foo".to_string()))?;
+            match old.diff(&new) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("can't diff records with different fields", e.to_string())
+            }
+            let mut short = Record::new();
+            short.add("id", Value::I32(1))?;
+            match old.diff(&short) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("can't diff records with different fields", e.to_string())
+            }
+            Ok(())
+        }
     }
-}
\ No newline at end of file
+}
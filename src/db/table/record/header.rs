@@ -1,14 +1,24 @@
 use serde::{Serialize, Deserialize};
+use serde_json::Value as JSValue;
 use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+use std::time::Duration;
 use anyhow::{bail, Result};
-use crate::error::ParseError;
-use crate::traits::{ByteSized, FromByteSlice, WriteAsBytes, ReadFrom, WriteTo, LoadFrom};
-use super::value::Value;
+use chrono::{DateTime, NaiveDate};
+use crate::error::{ParseError, TableError};
+use sha3::{Digest, Sha3_256};
+use crate::db::indexer::header::HASH_SIZE;
+use crate::traits::{
+    ByteSized, Endianness, FromByteSlice, WriteAsBytes, ReadFrom, ReadFromEndian, WriteTo,
+    WriteToEndian, LoadFrom
+};
+use super::value::{days_from_date, Value};
 use super::Record;
 
 /// Represents a field type.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub enum FieldType {
     /// Represents a bool type being `type_byte = 1`.
     Bool,
@@ -33,7 +43,69 @@ pub enum FieldType {
     /// Represents a f64 type being `type_byte = 11`.
     F64,
     /// Represents a string type being `type_byte = 12`.
-    Str(u32)
+    Str(u32),
+    /// Represents an IP address type being `type_byte = 13`.
+    IpAddr,
+    /// Represents a single unicode character type being `type_byte = 14`.
+    Char,
+    /// Represents a duration stored as i64 nanoseconds being `type_byte = 15`.
+    Duration,
+    /// Represents a closed set of string variants stored as their u8
+    /// index being `type_byte = 16`.
+    Enum8(Vec<String>),
+    /// Represents a date stored as i32 days since the Unix epoch being
+    /// `type_byte = 17`.
+    Date,
+    /// Represents a timestamp stored as i64 milliseconds since the Unix
+    /// epoch being `type_byte = 18`.
+    Timestamp,
+    /// Represents a raw bytes type with a max size being `type_byte = 19`.
+    Bytes(u32),
+    /// Represents a fixed-point decimal stored as an i64 scaled integer
+    /// along its scale being `type_byte = 20`, so scale 2 stores cents.
+    Decimal{scale: u8},
+    /// Represents a closed set of labels stored as a u16 discriminant
+    /// being `type_byte = 21`, values are [Value::Enum].
+    Enum{variants: Vec<String>},
+    /// Represents a UUID stored as its raw 16 bytes being `type_byte = 22`.
+    Uuid,
+    /// Represents JSON text with a max size being `type_byte = 23`, it is
+    /// stored like [Self::Str] and validated on write.
+    Json(u32),
+    /// Represents `len` values of the element type stored back to back
+    /// being `type_byte = 24`, values are [Value::Array].
+    Array{element: Box<FieldType>, len: u32},
+    /// Represents a string without a max size stored as
+    /// `<size:4><value:size>` being `type_byte = 25`, records holding it
+    /// aren't fixed length, see [Self::is_fixed_length].
+    StrVar
+}
+
+/// String trimming applied to string values before writing them.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Copy, Clone, Default)]
+pub enum TrimMode {
+    /// Keeps the string as is.
+    #[default]
+    None,
+    /// Removes the trailing whitespaces.
+    End,
+    /// Removes both leading and trailing whitespaces.
+    Both
+}
+
+impl TrimMode {
+    /// Trims a string based on the trim mode.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `s` - String to trim.
+    pub fn apply<'a>(&self, s: &'a str) -> &'a str {
+        match self {
+            Self::None => s,
+            Self::End => s.trim_end(),
+            Self::Both => s.trim()
+        }
+    }
 }
 
 impl FieldType {
@@ -41,9 +113,25 @@ impl FieldType {
     pub const MIN_TYPE_ID: u8 = 1u8;
 
     /// Max value the field type first byte can take.
-    pub const MAX_TYPE_ID: u8 = 12u8;
+    pub const MAX_TYPE_ID: u8 = 25u8;
+
+    /// Max scale allowed on [Self::Decimal], the digits an i64 can hold.
+    pub const MAX_DECIMAL_SCALE: u8 = 18;
+
+    /// Max variant count allowed on [Self::Enum8].
+    pub const MAX_ENUM8_VARIANTS: usize = 256;
 
-    /// Gets the byte size of the value described by the field type.
+    /// Max variant count allowed on [Self::Enum].
+    pub const MAX_ENUM_VARIANTS: usize = 65536;
+
+    /// Byte size of an IP address value: `<version:1><address:16>`.
+    const IP_ADDR_BYTES: usize = 17;
+
+    /// Byte size of a UUID value.
+    const UUID_BYTES: usize = 16;
+
+    /// Gets the byte size of the value described by the field type, it is
+    /// the min byte size on variable length types.
     pub fn value_byte_size(&self) -> usize {
         match self {
             Self::Bool => u8::BYTES,
@@ -57,10 +145,252 @@ impl FieldType {
             Self::U64 => u64::BYTES,
             Self::F32 => f32::BYTES,
             Self::F64 => f64::BYTES,
-            Self::Str(size) => u32::BYTES + *size as usize
+            Self::Str(size) => u32::BYTES + *size as usize,
+            Self::IpAddr => Self::IP_ADDR_BYTES,
+            Self::Char => char::BYTES,
+            Self::Duration => i64::BYTES,
+            Self::Enum8(_) => u8::BYTES,
+            Self::Date => i32::BYTES,
+            Self::Timestamp => i64::BYTES,
+            Self::Bytes(size) => u32::BYTES + *size as usize,
+            Self::Decimal{..} => i64::BYTES + u8::BYTES,
+            Self::Enum{..} => u16::BYTES,
+            Self::Uuid => Self::UUID_BYTES,
+            Self::Json(size) => u32::BYTES + *size as usize,
+            Self::Array{element, len} => element.value_byte_size() * *len as usize,
+            Self::StrVar => u32::BYTES
+        }
+    }
+
+    /// Gets the byte size of the data stored after the field type bytes,
+    /// [Self::Enum8] and [Self::Enum] store their variant names while
+    /// [Self::Array] stores its element type.
+    pub fn variants_byte_size(&self) -> usize {
+        match self {
+            Self::Enum8(variants) | Self::Enum{variants} => variants.iter().map(|v| u32::BYTES + v.len()).sum(),
+            Self::Array{element, ..} => Self::BYTES + element.variants_byte_size(),
+            _ => 0
+        }
+    }
+
+    /// Validates the field type definition, [Self::Enum8] and
+    /// [Self::Enum] must have between 1 and [Self::MAX_ENUM8_VARIANTS] or
+    /// [Self::MAX_ENUM_VARIANTS] unique variants no longer than
+    /// [Field::MAX_NAME_SIZE] bytes, [Self::Decimal] scale can't be
+    /// bigger than [Self::MAX_DECIMAL_SCALE] and [Self::Array] must have at
+    /// least one fixed length element of a type other than string, bytes,
+    /// JSON or array.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Decimal{scale} if *scale > Self::MAX_DECIMAL_SCALE => {
+                bail!("decimal scale must be <= {} but got {}", Self::MAX_DECIMAL_SCALE, scale);
+            },
+            Self::Enum8(variants) => Self::validate_variants("enum8", variants, Self::MAX_ENUM8_VARIANTS)?,
+            Self::Enum{variants} => Self::validate_variants("enum", variants, Self::MAX_ENUM_VARIANTS)?,
+            Self::Array{element, len} => {
+                if *len < 1 {
+                    bail!("array field type requires at least one element");
+                }
+                match element.as_ref() {
+                    Self::Str(_) | Self::Bytes(_) | Self::Json(_) | Self::Array{..} | Self::StrVar => {
+                        bail!("array element type can't be {}", element.type_name());
+                    },
+                    _ => element.validate()?
+                }
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Validates an enum field type variant list.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `kind` - Enum type name used on error messages.
+    /// * `variants` - Variant names.
+    /// * `max` - Max variant count.
+    fn validate_variants(kind: &str, variants: &[String], max: usize) -> Result<()> {
+        if variants.is_empty() {
+            bail!("{} field type requires at least one variant", kind);
+        }
+        if variants.len() > max {
+            bail!("{} field type can't have more than {} variants but got {}", kind, max, variants.len());
+        }
+        for (index, variant) in variants.iter().enumerate() {
+            if variant.len() > Field::MAX_NAME_SIZE {
+                bail!("{} variant size must be <= {} bytes length", kind, Field::MAX_NAME_SIZE);
+            }
+            if variants[..index].contains(variant) {
+                bail!("duplicated {} variant \"{}\"", kind, variant);
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the label of a [Value::Enum] value, returns `None` when
+    /// the field type isn't [Self::Enum] or the discriminant is out of range.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Value to resolve.
+    pub fn enum_label(&self, value: &Value) -> Option<&str> {
+        match (self, value) {
+            (Self::Enum{variants}, Value::Enum(v)) => variants.get(*v as usize).map(|v| v.as_str()),
+            _ => None
+        }
+    }
+
+    /// Reads the variant names stored after an enum field type bytes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    /// * `count` - Variant count.
+    /// * `max` - Max variant count.
+    fn read_variants(reader: &mut impl Read, count: usize, max: usize) -> Result<Vec<String>> {
+        if count > max {
+            bail!(ParseError::InvalidValue)
+        }
+        let mut variants = Vec::with_capacity(count);
+        for _ in 0..count {
+            let size = u32::read_from(reader)? as usize;
+            if size > Field::MAX_NAME_SIZE {
+                bail!(ParseError::InvalidValue)
+            }
+            let mut name_buf = vec![0u8; size];
+            reader.read_exact(&mut name_buf)?;
+            variants.push(String::from_utf8(name_buf)?);
+        }
+        Ok(variants)
+    }
+
+    /// Reads the element type stored after an array field type bytes.
+    /// Nested arrays aren't allowed, so these are rejected before reading
+    /// the element type to avoid deep recursion.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    fn read_array_element(reader: &mut dyn Read) -> Result<Self> {
+        let mut buf = [0u8; Self::BYTES];
+        reader.read_exact(&mut buf)?;
+        if buf[0] == 24 {
+            bail!(ParseError::InvalidValue)
+        }
+        Self::read_from(&mut (&buf[..]).chain(reader))
+    }
+
+    /// Returns true when every value of the field type takes the same
+    /// amount of bytes, which is required to calculate record positions.
+    pub fn is_fixed_length(&self) -> bool {
+        match self {
+            Self::Bool
+            | Self::I8
+            | Self::I16
+            | Self::I32
+            | Self::I64
+            | Self::U8
+            | Self::U16
+            | Self::U32
+            | Self::U64
+            | Self::F32
+            | Self::F64
+            | Self::Str(_)
+            | Self::IpAddr
+            | Self::Char
+            | Self::Duration
+            | Self::Enum8(_)
+            | Self::Date
+            | Self::Timestamp
+            | Self::Bytes(_)
+            | Self::Decimal{..}
+            | Self::Enum{..}
+            | Self::Uuid
+            | Self::Json(_)
+            | Self::Array{..} => true,
+            Self::StrVar => false
+        }
+    }
+
+    /// Gets the field type name used by schema descriptors.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::Str(_) => "str",
+            Self::IpAddr => "ip_addr",
+            Self::Char => "char",
+            Self::Duration => "duration",
+            Self::Enum8(_) => "enum8",
+            Self::Date => "date",
+            Self::Timestamp => "timestamp",
+            Self::Bytes(_) => "bytes",
+            Self::Decimal{..} => "decimal",
+            Self::Enum{..} => "enum",
+            Self::Uuid => "uuid",
+            Self::Json(_) => "json",
+            Self::Array{..} => "array",
+            Self::StrVar => "strvar"
         }
     }
 
+    /// Builds a field type from its type name.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Field type name.
+    /// * `size` - String max size, required by `str` types.
+    pub fn from_type_name(name: &str, size: Option<u32>) -> Result<Self> {
+        let field_type = match name {
+            "bool" => Self::Bool,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            "str" => match size {
+                Some(v) => Self::Str(v),
+                None => bail!("str field type requires a size")
+            },
+            "ip_addr" => Self::IpAddr,
+            "char" => Self::Char,
+            "duration" => Self::Duration,
+            "enum8" => bail!("enum8 field type requires variants"),
+            "date" => Self::Date,
+            "timestamp" => Self::Timestamp,
+            "bytes" => match size {
+                Some(v) => Self::Bytes(v),
+                None => bail!("bytes field type requires a size")
+            },
+            "decimal" => bail!("decimal field type requires a scale"),
+            "enum" => bail!("enum field type requires variants"),
+            "uuid" => Self::Uuid,
+            "json" => match size {
+                Some(v) => Self::Json(v),
+                None => bail!("json field type requires a size")
+            },
+            "array" => bail!("array field type requires an element type and length"),
+            "strvar" => Self::StrVar,
+            _ => bail!("unknown field type \"{}\"", name)
+        };
+        Ok(field_type)
+    }
+
     /// Gets the string max size when [Self::Str].
     pub fn str_size(&self) -> Result<u32> {
         match self {
@@ -69,6 +399,60 @@ impl FieldType {
         }
     }
 
+    /// Gets the value max size when [Self::Str], [Self::Bytes] or [Self::Json].
+    pub fn max_size(&self) -> Option<u32> {
+        match self {
+            Self::Str(size) | Self::Bytes(size) | Self::Json(size) => Some(*size),
+            _ => None
+        }
+    }
+
+    /// Gets the variant names when [Self::Enum8] or [Self::Enum].
+    pub fn variants(&self) -> Option<&Vec<String>> {
+        match self {
+            Self::Enum8(variants) | Self::Enum{variants} => Some(variants),
+            _ => None
+        }
+    }
+
+    /// Gets the scale when [Self::Decimal].
+    pub fn scale(&self) -> Option<u8> {
+        match self {
+            Self::Decimal{scale} => Some(*scale),
+            _ => None
+        }
+    }
+
+    /// Builds a field type from its type name along the values required
+    /// by some types, see [FieldDescriptor].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Field type name.
+    /// * `size` - Value max size, required by `str`, `bytes` and `json` types.
+    /// * `variants` - Variant names, required by `enum8` and `enum` types.
+    /// * `scale` - Decimal scale, required by `decimal` types.
+    pub fn from_description(name: &str, size: Option<u32>, variants: Option<&Vec<String>>, scale: Option<u8>) -> Result<Self> {
+        match (name, variants, scale) {
+            ("enum8", Some(variants), _) => Ok(Self::Enum8(variants.clone())),
+            ("enum", Some(variants), _) => Ok(Self::Enum{variants: variants.clone()}),
+            ("decimal", _, Some(scale)) => Ok(Self::Decimal{scale}),
+            _ => Self::from_type_name(name, size)
+        }
+    }
+
+    /// Validates the field type definition, flagging zero capacity string,
+    /// bytes and JSON types since they can't hold any value. Already
+    /// serialized zero capacity fields can still be read and written.
+    pub fn validate_definition(&self) -> Result<()> {
+        match self {
+            Self::Str(0) => bail!("string field capacity can't be zero"),
+            Self::Bytes(0) => bail!("bytes field capacity can't be zero"),
+            Self::Json(0) => bail!("json field capacity can't be zero"),
+            _ => Ok(())
+        }
+    }
+
     /// Validate a value against a field type.
     /// 
     /// # Arguments
@@ -120,34 +504,141 @@ impl FieldType {
                     return false;
                 }
                 return true;
+            },
+            FieldType::IpAddr => if let Value::IpAddr(_) = value {
+                return true;
+            },
+            FieldType::Char => if let Value::Char(_) = value {
+                return true;
+            },
+            FieldType::Duration => if let Value::Duration(v) = value {
+                return v.as_nanos() <= i64::MAX as u128;
+            },
+            FieldType::Enum8(variants) => if let Value::Str(s) = value {
+                return variants.contains(s);
+            },
+            FieldType::Date => if let Value::Date(_) = value {
+                return true;
+            },
+            FieldType::Timestamp => if let Value::Timestamp(_) = value {
+                return true;
+            },
+            FieldType::Bytes(size) => if let Value::Bytes(v) = value {
+                return v.len() <= (*size) as usize;
+            },
+            FieldType::Decimal{scale} => if let Value::Decimal(_, value_scale) = value {
+                return value_scale == scale;
+            },
+            FieldType::Enum{variants} => if let Value::Enum(v) = value {
+                return (*v as usize) < variants.len();
+            },
+            FieldType::Uuid => if let Value::Uuid(_) = value {
+                return true;
+            },
+            FieldType::Json(size) => match value {
+                Value::Json(v) => return v.to_string().len() <= *size as usize,
+                Value::Str(s) => return s.len() <= *size as usize && serde_json::from_str::<JSValue>(s).is_ok(),
+                _ => {}
+            },
+            FieldType::Array{element, len} => if let Value::Array(values) = value {
+                return values.len() == *len as usize && values.iter().all(|v| element.is_valid(v));
+            },
+            FieldType::StrVar => if let Value::Str(s) = value {
+                return s.len() <= u32::MAX as usize;
             }
         }
         return false;
     }
 
+    /// Validate a value against a field type and describe why it is
+    /// invalid on error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Value to validate.
+    pub fn validate_value(&self, value: &Value) -> Result<()> {
+        self.validate_value_with(value, TrimMode::None)
+    }
+
+    /// Validate a value against a field type as it would be written using
+    /// a trim mode.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Value to validate.
+    /// * `trim` - Trim mode applied to string values.
+    pub fn validate_value_with(&self, value: &Value, trim: TrimMode) -> Result<()> {
+        if let (Self::Str(size), Value::Str(s)) = (self, value) {
+            let s = trim.apply(s);
+            if s.len() > *size as usize {
+                bail!("string value ({} bytes) exceeds field size ({} bytes)", s.len(), size);
+            }
+            return Ok(());
+        }
+        if let (Self::Bytes(size), Value::Bytes(v)) = (self, value) {
+            if v.len() > *size as usize {
+                bail!("bytes value ({} bytes) exceeds field size ({} bytes)", v.len(), size);
+            }
+            return Ok(());
+        }
+        if let (Self::Json(size), Value::Json(v)) = (self, value) {
+            let value_size = v.to_string().len();
+            if value_size > *size as usize {
+                bail!("JSON value size ({} bytes) is bigger than field size ({} bytes)", value_size, size);
+            }
+            return Ok(());
+        }
+        if let (Self::Array{element, len}, Value::Array(values)) = (self, value) {
+            if values.len() != *len as usize {
+                bail!("array value has {} elements but the field type requires {}", values.len(), len);
+            }
+            for (index, v) in values.iter().enumerate() {
+                if let Err(e) = element.validate_value_with(v, trim) {
+                    bail!("array element {}: {}", index, e);
+                }
+            }
+            return Ok(());
+        }
+        if !self.is_valid(value) {
+            bail!("value {:?} doesn't match the field type {:?}", value, self);
+        }
+        Ok(())
+    }
+
     /// Reads a value from a reader based on the field type.
     /// 
     /// # Arguments
     /// 
     /// * `reader` - Byte reader.
     pub fn read_value(&self, reader: &mut impl Read) -> Result<Value> {
+        self.read_value_endian(reader, Endianness::Big)
+    }
+
+    /// Reads a value from a reader based on the field type, numeric
+    /// values are decoded using the provided byte order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    /// * `endianness` - Byte order of numeric values.
+    pub fn read_value_endian(&self, reader: &mut impl Read, endianness: Endianness) -> Result<Value> {
         let value: Value = match self {
-            Self::Bool => bool::read_from(reader)?.into(),
-            Self::I8 => i8::read_from(reader)?.into(),
-            Self::I16 => i16::read_from(reader)?.into(),
-            Self::I32 => i32::read_from(reader)?.into(),
-            Self::I64 => i64::read_from(reader)?.into(),
-            Self::U8 => u8::read_from(reader)?.into(),
-            Self::U16 => u16::read_from(reader)?.into(),
-            Self::U32 => u32::read_from(reader)?.into(),
-            Self::U64 => u64::read_from(reader)?.into(),
-            Self::F32 => f32::read_from(reader)?.into(),
-            Self::F64 => f64::read_from(reader)?.into(),
+            Self::Bool => bool::read_from_endian(reader, endianness)?.into(),
+            Self::I8 => i8::read_from_endian(reader, endianness)?.into(),
+            Self::I16 => i16::read_from_endian(reader, endianness)?.into(),
+            Self::I32 => i32::read_from_endian(reader, endianness)?.into(),
+            Self::I64 => i64::read_from_endian(reader, endianness)?.into(),
+            Self::U8 => u8::read_from_endian(reader, endianness)?.into(),
+            Self::U16 => u16::read_from_endian(reader, endianness)?.into(),
+            Self::U32 => u32::read_from_endian(reader, endianness)?.into(),
+            Self::U64 => u64::read_from_endian(reader, endianness)?.into(),
+            Self::F32 => f32::read_from_endian(reader, endianness)?.into(),
+            Self::F64 => f64::read_from_endian(reader, endianness)?.into(),
             Self::Str(size) => {
                 let size = (*size) as usize;
 
                 // read the real string size
-                let value_size = u32::read_from(reader)? as usize;
+                let value_size = u32::read_from_endian(reader, endianness)? as usize;
                 if value_size > size {
                     bail!("string value size can't be bigger than the field size");
                 }
@@ -156,10 +647,121 @@ impl FieldType {
                 if size > 0 {
                     let mut buf = vec![0u8; size as usize];
                     reader.read_exact(&mut buf)?;
-                    Value::Str(String::from_utf8(buf[..value_size].to_vec())?)
+                    match String::from_utf8(buf[..value_size].to_vec()) {
+                        Ok(v) => Value::Str(v),
+                        Err(e) => bail!(ParseError::InvalidUtf8(e))
+                    }
                 } else {
                     Value::Str("".to_string())
                 }
+            },
+            Self::IpAddr => {
+                let mut buf = [0u8; Self::IP_ADDR_BYTES];
+                reader.read_exact(&mut buf)?;
+                match buf[0] {
+                    4 => {
+                        let mut octets = [0u8; 4];
+                        octets.copy_from_slice(&buf[1..5]);
+                        Value::IpAddr(IpAddr::V4(Ipv4Addr::from(octets)))
+                    },
+                    6 => {
+                        let mut octets = [0u8; 16];
+                        octets.copy_from_slice(&buf[1..]);
+                        Value::IpAddr(IpAddr::V6(Ipv6Addr::from(octets)))
+                    },
+                    _ => bail!(ParseError::InvalidValue)
+                }
+            },
+            Self::Char => char::read_from_endian(reader, endianness)?.into(),
+            Self::Duration => {
+                let nanos = i64::read_from_endian(reader, endianness)?;
+                if nanos < 0 {
+                    bail!(ParseError::InvalidValue)
+                }
+                Duration::from_nanos(nanos as u64).into()
+            },
+            Self::Enum8(variants) => {
+                let index = u8::read_from_endian(reader, endianness)? as usize;
+                match variants.get(index) {
+                    Some(v) => Value::Str(v.clone()),
+                    None => bail!("enum8 value index {} is out of range", index)
+                }
+            },
+            Self::Date => Value::Date(i32::read_from_endian(reader, endianness)?),
+            Self::Timestamp => Value::Timestamp(i64::read_from_endian(reader, endianness)?),
+            Self::Bytes(size) => {
+                let size = (*size) as usize;
+
+                // read the real value size
+                let value_size = u32::read_from_endian(reader, endianness)? as usize;
+                if value_size > size {
+                    bail!("bytes value size can't be bigger than the field size");
+                }
+
+                // read the value and drop the padding
+                let mut buf = vec![0u8; size];
+                reader.read_exact(&mut buf)?;
+                buf.truncate(value_size);
+                Value::Bytes(buf)
+            },
+            Self::Decimal{scale} => {
+                let value = i64::read_from_endian(reader, endianness)?;
+                let value_scale = u8::read_from(reader)?;
+                if value_scale != *scale {
+                    bail!("decimal value scale {} doesn't match the field scale {}", value_scale, scale);
+                }
+                Value::Decimal(value, value_scale)
+            },
+            Self::Enum{variants} => {
+                let index = u16::read_from_endian(reader, endianness)?;
+                if index as usize >= variants.len() {
+                    bail!("enum value index {} is out of range", index);
+                }
+                Value::Enum(index)
+            },
+            Self::Uuid => {
+                let mut buf = [0u8; Self::UUID_BYTES];
+                reader.read_exact(&mut buf)?;
+                Value::Uuid(buf)
+            },
+            Self::Json(size) => {
+                let size = (*size) as usize;
+
+                // read the real value size
+                let value_size = u32::read_from_endian(reader, endianness)? as usize;
+                if value_size > size {
+                    bail!("JSON value size can't be bigger than the field size");
+                }
+
+                // read and parse the value, empty values are default values
+                let mut buf = vec![0u8; size];
+                reader.read_exact(&mut buf)?;
+                if value_size < 1 {
+                    Value::Json(JSValue::Null)
+                } else {
+                    match serde_json::from_slice(&buf[..value_size]) {
+                        Ok(v) => Value::Json(v),
+                        Err(e) => bail!("invalid JSON value: {}", e)
+                    }
+                }
+            },
+            Self::Array{element, len} => {
+                let mut values = Vec::with_capacity(*len as usize);
+                for _ in 0..*len {
+                    values.push(element.read_value_endian(reader, endianness)?);
+                }
+                Value::Array(values)
+            },
+            Self::StrVar => {
+                let value_size = u32::read_from_endian(reader, endianness)? as u64;
+                let buf = read_sized(reader, value_size)?;
+                if (buf.len() as u64) < value_size {
+                    bail!(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+                }
+                match String::from_utf8(buf) {
+                    Ok(v) => Value::Str(v),
+                    Err(e) => bail!(ParseError::InvalidUtf8(e))
+                }
             }
         };
         Ok(value)
@@ -171,65 +773,97 @@ impl FieldType {
     /// 
     /// * `writer` - Byte writer.
     pub fn write_value(&self, writer: &mut impl Write, value: &Value) -> Result<()> {
+        self.write_value_with(writer, value, TrimMode::None)
+    }
+
+    /// Write a value into a writer based on the field type, string values
+    /// are trimmed before checking their size.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `value` - Value to write.
+    /// * `trim` - Trim mode applied to string values.
+    pub fn write_value_with(&self, writer: &mut impl Write, value: &Value, trim: TrimMode) -> Result<()> {
+        self.write_value_endian(writer, value, trim, Endianness::Big)
+    }
+
+    /// Write a value into a writer based on the field type, numeric values
+    /// are encoded using the provided byte order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `value` - Value to write.
+    /// * `trim` - Trim mode applied to string values.
+    /// * `endianness` - Byte order of numeric values.
+    pub fn write_value_endian(
+        &self,
+        writer: &mut impl Write,
+        value: &Value,
+        trim: TrimMode,
+        endianness: Endianness
+    ) -> Result<()> {
         match self {
             Self::Bool => match value {
-                Value::Bool(v) => (*v).write_to(writer)?,
-                Value::Default => false.write_to(writer)?,
+                Value::Bool(v) => (*v).write_to_endian(writer, endianness)?,
+                Value::Default => false.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::Bool")
             },
             Self::I8 => match value {
-                Value::I8(v) => v.write_to(writer)?,
-                Value::Default => 0i8.write_to(writer)?,
+                Value::I8(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0i8.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::I8")
             },
             Self::I16 => match value {
-                Value::I16(v) => v.write_to(writer)?,
-                Value::Default => 0i16.write_to(writer)?,
+                Value::I16(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0i16.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::I16")
             },
             Self::I32 => match value {
-                Value::I32(v) => v.write_to(writer)?,
-                Value::Default => 0i32.write_to(writer)?,
+                Value::I32(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0i32.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::I32")
             },
             Self::I64 => match value {
-                Value::I64(v) => v.write_to(writer)?,
-                Value::Default => 0i64.write_to(writer)?,
+                Value::I64(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0i64.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::I64")
             },
             Self::U8 => match value {
-                Value::U8(v) => v.write_to(writer)?,
-                Value::Default => 0u8.write_to(writer)?,
+                Value::U8(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0u8.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::U8")
             },
             Self::U16 => match value {
-                Value::U16(v) => v.write_to(writer)?,
-                Value::Default => 0u16.write_to(writer)?,
+                Value::U16(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0u16.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::U16")
             },
             Self::U32 => match value {
-                Value::U32(v) => v.write_to(writer)?,
-                Value::Default => 0u32.write_to(writer)?,
+                Value::U32(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0u32.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::U32")
             },
             Self::U64 => match value {
-                Value::U64(v) => v.write_to(writer)?,
-                Value::Default => 0u64.write_to(writer)?,
+                Value::U64(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0u64.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::U64")
             },
             Self::F32 => match value {
-                Value::F32(v) => v.write_to(writer)?,
-                Value::Default => 0f32.write_to(writer)?,
+                Value::F32(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0f32.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::F32")
             },
             Self::F64 => match value {
-                Value::F64(v) => v.write_to(writer)?,
-                Value::Default => 0f64.write_to(writer)?,
+                Value::F64(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0f64.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::F64")
             },
             Self::Str(size) => match value {
                 Value::Str(v) => {
                     // validate string value
+                    let v = trim.apply(v);
                     let size = *size;
                     let value_buf = v.as_bytes();
                     let value_size = value_buf.len() as u32;
@@ -242,7 +876,7 @@ impl FieldType {
                     }
 
                     // write value
-                    value_size.write_to(writer)?;
+                    value_size.write_to_endian(writer, endianness)?;
                     writer.write_all(&value_buf)?;
                     if value_size < size {
                         // fill with zeros
@@ -251,24 +885,461 @@ impl FieldType {
                 },
                 Value::Default => {
                     // write default value size and string value
-                    0u32.write_to(writer)?;
+                    0u32.write_to_endian(writer, endianness)?;
+                    writer.write_all(&vec![0u8; (*size) as usize])?;
+                },
+                _ => bail!("value must be a Value::Str")
+            },
+            Self::IpAddr => {
+                let ip = match value {
+                    Value::IpAddr(v) => *v,
+                    Value::Default => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                    _ => bail!("value must be a Value::IpAddr")
+                };
+
+                // write version and address padded to 16 bytes
+                let mut buf = [0u8; Self::IP_ADDR_BYTES];
+                match ip {
+                    IpAddr::V4(v) => {
+                        buf[0] = 4;
+                        buf[1..5].copy_from_slice(&v.octets());
+                    },
+                    IpAddr::V6(v) => {
+                        buf[0] = 6;
+                        buf[1..].copy_from_slice(&v.octets());
+                    }
+                }
+                writer.write_all(&buf)?;
+            },
+            Self::Char => match value {
+                Value::Char(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => '\0'.write_to_endian(writer, endianness)?,
+                _ => bail!("value must be a Value::Char")
+            },
+            Self::Duration => match value {
+                Value::Duration(v) => {
+                    let nanos = v.as_nanos();
+                    if nanos > i64::MAX as u128 {
+                        bail!("duration {:?} is too big to be stored as i64 nanoseconds", v);
+                    }
+                    (nanos as i64).write_to_endian(writer, endianness)?
+                },
+                Value::Default => 0i64.write_to_endian(writer, endianness)?,
+                _ => bail!("value must be a Value::Duration")
+            },
+            Self::Enum8(variants) => match value {
+                Value::Str(v) => {
+                    let v = trim.apply(v);
+                    match variants.iter().position(|variant| variant == v) {
+                        Some(index) => (index as u8).write_to_endian(writer, endianness)?,
+                        None => bail!("\"{}\" is not a valid enum8 variant", v)
+                    }
+                },
+                Value::Default => 0u8.write_to_endian(writer, endianness)?,
+                _ => bail!("value must be a Value::Str")
+            },
+            Self::Date => match value {
+                Value::Date(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0i32.write_to_endian(writer, endianness)?,
+                _ => bail!("value must be a Value::Date")
+            },
+            Self::Timestamp => match value {
+                Value::Timestamp(v) => v.write_to_endian(writer, endianness)?,
+                Value::Default => 0i64.write_to_endian(writer, endianness)?,
+                _ => bail!("value must be a Value::Timestamp")
+            },
+            Self::Bytes(size) => match value {
+                Value::Bytes(v) => {
+                    // validate bytes value
+                    let size = *size;
+                    let value_size = v.len() as u64;
+                    if value_size > size as u64 {
+                        bail!(
+                            "bytes value size ({} bytes) is bigger than field size ({} bytes)",
+                            value_size,
+                            size
+                        );
+                    }
+
+                    // write value
+                    (value_size as u32).write_to_endian(writer, endianness)?;
+                    writer.write_all(v)?;
+                    if (value_size as u32) < size {
+                        // fill with zeros
+                        writer.write_all(&vec![0u8; (size - value_size as u32) as usize])?;
+                    }
+                },
+                Value::Default => {
+                    // write default value size and bytes value
+                    0u32.write_to_endian(writer, endianness)?;
                     writer.write_all(&vec![0u8; (*size) as usize])?;
                 },
+                _ => bail!("value must be a Value::Bytes")
+            },
+            Self::Decimal{scale} => match value {
+                Value::Decimal(v, value_scale) => {
+                    if value_scale != scale {
+                        bail!("decimal value scale {} doesn't match the field scale {}", value_scale, scale);
+                    }
+                    v.write_to_endian(writer, endianness)?;
+                    scale.write_to(writer)?;
+                },
+                Value::Default => {
+                    0i64.write_to_endian(writer, endianness)?;
+                    scale.write_to(writer)?;
+                },
+                _ => bail!("value must be a Value::Decimal")
+            },
+            Self::Enum{variants} => match value {
+                Value::Enum(v) => {
+                    if *v as usize >= variants.len() {
+                        bail!("enum value index {} is out of range", v);
+                    }
+                    v.write_to_endian(writer, endianness)?
+                },
+                Value::Default => 0u16.write_to_endian(writer, endianness)?,
+                _ => bail!("value must be a Value::Enum")
+            },
+            Self::Uuid => match value {
+                Value::Uuid(v) => writer.write_all(v)?,
+                Value::Default => writer.write_all(&[0u8; Self::UUID_BYTES])?,
+                _ => bail!("value must be a Value::Uuid")
+            },
+            Self::Json(size) => {
+                // validate JSON value, string values must be JSON text
+                let text = match value {
+                    Value::Json(v) => v.to_string(),
+                    Value::Str(v) => {
+                        if let Err(e) = serde_json::from_str::<JSValue>(v) {
+                            bail!("invalid JSON value: {}", e);
+                        }
+                        v.to_string()
+                    },
+                    Value::Default => "".to_string(),
+                    _ => bail!("value must be a Value::Json")
+                };
+                let size = *size;
+                let value_size = text.len() as u32;
+                if value_size > size {
+                    bail!(
+                        "JSON value size ({} bytes) is bigger than field size ({} bytes)",
+                        value_size,
+                        size
+                    );
+                }
+
+                // write value and fill with zeros
+                value_size.write_to_endian(writer, endianness)?;
+                writer.write_all(text.as_bytes())?;
+                writer.write_all(&vec![0u8; (size - value_size) as usize])?;
+            },
+            Self::Array{element, len} => match value {
+                Value::Array(values) => {
+                    if values.len() != *len as usize {
+                        bail!("array value has {} elements but the field type requires {}", values.len(), len);
+                    }
+                    for v in values {
+                        element.write_value_endian(writer, v, trim, endianness)?;
+                    }
+                },
+                Value::Default => for _ in 0..*len {
+                    element.write_value_endian(writer, &Value::Default, trim, endianness)?;
+                },
+                _ => bail!("value must be a Value::Array")
+            },
+            Self::StrVar => match value {
+                Value::Str(v) => {
+                    let value_buf = trim.apply(v).as_bytes();
+                    let value_size = match u32::try_from(value_buf.len()) {
+                        Ok(v) => v,
+                        Err(_) => bail!("string value size ({} bytes) is too big", value_buf.len())
+                    };
+                    value_size.write_to_endian(writer, endianness)?;
+                    writer.write_all(value_buf)?;
+                },
+                Value::Default => 0u32.write_to_endian(writer, endianness)?,
                 _ => bail!("value must be a Value::Str")
             }
         }
         Ok(())
     }
-}
-
-impl ByteSized for FieldType {
-    /// Byte representation: `<type:1><value:4>`.
-    const BYTES: usize = 5;
-}
 
-impl ReadFrom for FieldType {
-    fn read_from(reader: &mut impl Read) -> Result<Self> {
-        // read data
+    /// Parses a string into a value based on the field type.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `s` - String to parse.
+    pub fn parse_value(&self, s: &str) -> Result<Value> {
+        let value: Value = match self {
+            Self::Bool => s.parse::<bool>()?.into(),
+            Self::I8 => s.parse::<i8>()?.into(),
+            Self::I16 => s.parse::<i16>()?.into(),
+            Self::I32 => s.parse::<i32>()?.into(),
+            Self::I64 => s.parse::<i64>()?.into(),
+            Self::U8 => s.parse::<u8>()?.into(),
+            Self::U16 => s.parse::<u16>()?.into(),
+            Self::U32 => s.parse::<u32>()?.into(),
+            Self::U64 => s.parse::<u64>()?.into(),
+            Self::F32 => s.parse::<f32>()?.into(),
+            Self::F64 => s.parse::<f64>()?.into(),
+            Self::Str(size) => {
+                let value_size = s.len();
+                if value_size > *size as usize {
+                    bail!(
+                        "string value size ({} bytes) is bigger than field size ({} bytes)",
+                        value_size,
+                        size
+                    );
+                }
+                s.into()
+            },
+            Self::IpAddr => match s.parse::<IpAddr>() {
+                Ok(v) => Value::IpAddr(v),
+                Err(e) => bail!("invalid IP address \"{}\": {}", s, e)
+            },
+            Self::Char => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(v), None) => v.into(),
+                    _ => bail!("char value must be a single character but got \"{}\"", s)
+                }
+            },
+            Self::Duration => parse_duration(s)?.into(),
+            Self::Enum8(variants) => {
+                if !variants.iter().any(|v| v == s) {
+                    bail!("\"{}\" is not a valid enum8 variant", s);
+                }
+                s.into()
+            },
+            Self::Date => Value::Date(parse_date(s)?),
+            Self::Timestamp => Value::Timestamp(parse_timestamp(s)?),
+            Self::Bytes(size) => {
+                let buf = parse_hex(s)?;
+                if buf.len() > *size as usize {
+                    bail!(
+                        "bytes value size ({} bytes) is bigger than field size ({} bytes)",
+                        buf.len(),
+                        size
+                    );
+                }
+                Value::Bytes(buf)
+            },
+            Self::Decimal{scale} => Value::Decimal(parse_decimal(s, *scale)?, *scale),
+            Self::Enum{variants} => match variants.iter().position(|v| v == s) {
+                Some(index) => Value::Enum(index as u16),
+                None => bail!("\"{}\" is not a valid enum variant", s)
+            },
+            Self::Uuid => Value::parse_uuid(s)?,
+            Self::Json(size) => {
+                let value: JSValue = match serde_json::from_str(s) {
+                    Ok(v) => v,
+                    Err(e) => bail!("invalid JSON value: {}", e)
+                };
+                let value_size = value.to_string().len();
+                if value_size > *size as usize {
+                    bail!(
+                        "JSON value size ({} bytes) is bigger than field size ({} bytes)",
+                        value_size,
+                        size
+                    );
+                }
+                Value::Json(value)
+            },
+            Self::Array{..} => {
+                let value: JSValue = match serde_json::from_str(s) {
+                    Ok(v) => v,
+                    Err(e) => bail!("invalid JSON value: {}", e)
+                };
+                Value::from_json_typed(value, self)?
+            },
+            Self::StrVar => s.into()
+        };
+        Ok(value)
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date string into days since the Unix epoch.
+/// 
+/// # Arguments
+/// 
+/// * `s` - String to parse.
+fn parse_date(s: &str) -> Result<i32> {
+    match NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        Ok(v) => Ok(days_from_date(&v)),
+        Err(e) => bail!("invalid date \"{}\": {}", s, e)
+    }
+}
+
+/// Parses either an RFC 3339 timestamp string or an integer amount of
+/// milliseconds into milliseconds since the Unix epoch.
+/// 
+/// # Arguments
+/// 
+/// * `s` - String to parse.
+fn parse_timestamp(s: &str) -> Result<i64> {
+    if let Ok(v) = s.parse::<i64>() {
+        return Ok(v);
+    }
+    match DateTime::parse_from_rfc3339(s) {
+        Ok(v) => Ok(v.timestamp_millis()),
+        Err(e) => bail!("invalid timestamp \"{}\": {}", s, e)
+    }
+}
+
+/// Parses a hexadecimal string into bytes, both lower and upper case
+/// digits are accepted.
+/// 
+/// # Arguments
+/// 
+/// * `s` - String to parse.
+fn parse_hex(s: &str) -> Result<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        bail!("invalid hex value \"{}\": odd number of digits", s);
+    }
+    let mut buf = Vec::with_capacity(s.len() / 2);
+    for i in (0..s.len()).step_by(2) {
+        match s.get(i..i + 2).and_then(|v| u8::from_str_radix(v, 16).ok()) {
+            Some(v) => buf.push(v),
+            None => bail!("invalid hex value \"{}\": invalid digit", s)
+        }
+    }
+    Ok(buf)
+}
+
+/// Parses a decimal string like `"-123.45"` into an integer scaled by
+/// `10^scale`, values with more fractional digits than the scale are
+/// rejected instead of rounded.
+/// 
+/// # Arguments
+/// 
+/// * `s` - String to parse.
+/// * `scale` - Decimal scale.
+fn parse_decimal(s: &str, scale: u8) -> Result<i64> {
+    let (negative, text) = match s.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, s)
+    };
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (text, "")
+    };
+    let is_digits = |v: &str| v.bytes().all(|c| c.is_ascii_digit());
+    if (integer.is_empty() && fraction.is_empty()) || !is_digits(integer) || !is_digits(fraction) {
+        bail!("invalid decimal \"{}\"", s);
+    }
+    if fraction.len() > scale as usize {
+        bail!("invalid decimal \"{}\": more than {} fractional digits", s, scale);
+    }
+
+    // build the scaled integer padding the fractional digits
+    let digits = format!("{}{}{}", integer, fraction, "0".repeat(scale as usize - fraction.len()));
+    let value = match digits.trim_start_matches('0') {
+        "" => 0i128,
+        v if v.len() > 19 => bail!("invalid decimal \"{}\": value out of range", s),
+        v => v.parse::<i128>()?
+    };
+    let value = if negative { -value } else { value };
+    match i64::try_from(value) {
+        Ok(v) => Ok(v),
+        Err(_) => bail!("invalid decimal \"{}\": value out of range", s)
+    }
+}
+
+/// Parses a duration string made of an amount and an optional unit
+/// (`ns`, `us`, `µs`, `ms`, `s`, `m` or `h`) like `"1500ms"` or `"1.5s"`.
+/// Amounts without unit are treated as nanoseconds.
+/// 
+/// # Arguments
+/// 
+/// * `s` - String to parse.
+fn parse_duration(s: &str) -> Result<Duration> {
+    let text = s.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (amount, unit) = text.split_at(split);
+    let unit_nanos: u64 = match unit.trim() {
+        "" | "ns" => 1,
+        "us" | "µs" => 1_000,
+        "ms" => 1_000_000,
+        "s" => 1_000_000_000,
+        "m" => 60_000_000_000,
+        "h" => 3_600_000_000_000,
+        _ => bail!("invalid duration \"{}\": unknown unit \"{}\"", s, unit.trim())
+    };
+
+    // integers are converted without float precision loss
+    let nanos = if amount.contains('.') {
+        let value = match amount.parse::<f64>() {
+            Ok(v) => (v * unit_nanos as f64).round(),
+            Err(e) => bail!("invalid duration \"{}\": {}", s, e)
+        };
+        if value > i64::MAX as f64 {
+            bail!("invalid duration \"{}\": value out of range", s);
+        }
+        value as u128
+    } else {
+        match amount.parse::<u128>() {
+            Ok(v) => v.saturating_mul(unit_nanos as u128),
+            Err(e) => bail!("invalid duration \"{}\": {}", s, e)
+        }
+    };
+    if nanos > i64::MAX as u128 {
+        bail!("invalid duration \"{}\": value out of range", s);
+    }
+    Ok(Duration::from_nanos(nanos as u64))
+}
+
+/// Reads up to `size` bytes without allocating them upfront, so a
+/// corrupted size read from a file can't trigger a huge allocation. The
+/// returned buffer is shorter than `size` when the reader ends early.
+/// 
+/// # Arguments
+/// 
+/// * `reader` - Byte reader.
+/// * `size` - Byte count to read.
+fn read_sized(reader: &mut impl Read, size: u64) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader.by_ref().take(size).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Decodes a subset of values from a raw record buffer without a
+/// header, skipping every byte region not listed.
+/// 
+/// # Arguments
+/// 
+/// * `bytes` - Raw record bytes.
+/// * `types` - List of `(byte_offset, field_type)` to decode, values are returned in the same order.
+/// * `endianness` - Byte order of numeric values, see [Header::endianness].
+pub fn decode_partial(bytes: &[u8], types: &[(usize, FieldType)], endianness: Endianness) -> Result<Vec<Value>> {
+    let mut values = Vec::with_capacity(types.len());
+    for (offset, field_type) in types {
+        let end = match offset.checked_add(field_type.value_byte_size()) {
+            Some(v) => v,
+            None => bail!(ParseError::OutOfRange(format!("field offset {}", offset)))
+        };
+        if end > bytes.len() {
+            bail!(ParseError::SizeMismatch{
+                expected: end,
+                found: bytes.len()
+            });
+        }
+        let mut reader = &bytes[*offset..end];
+        values.push(field_type.read_value_endian(&mut reader, endianness)?);
+    }
+    Ok(values)
+}
+
+impl ByteSized for FieldType {
+    /// Byte representation: `<type:1><value:4>`. [FieldType::Enum8] and
+    /// [FieldType::Enum] store the variant count as value followed by
+    /// every variant name as
+    /// `<name_size:4><name:name_size>`, see [FieldType::variants_byte_size].
+    const BYTES: usize = 5;
+}
+
+impl ReadFrom for FieldType {
+    fn read_from(reader: &mut impl Read) -> Result<Self> {
+        // read data
         let mut buf = [0u8; Self::BYTES];
         reader.read_exact(&mut buf)?;
         
@@ -288,8 +1359,38 @@ impl ReadFrom for FieldType {
             12 => {
                 Self::Str(u32::from_byte_slice(&buf[1..])?)
             },
+            13 => Self::IpAddr,
+            14 => Self::Char,
+            15 => Self::Duration,
+            16 => {
+                let count = u32::from_byte_slice(&buf[1..])? as usize;
+                Self::Enum8(Self::read_variants(reader, count, Self::MAX_ENUM8_VARIANTS)?)
+            },
+            17 => Self::Date,
+            18 => Self::Timestamp,
+            19 => {
+                Self::Bytes(u32::from_byte_slice(&buf[1..])?)
+            },
+            20 => match u8::try_from(u32::from_byte_slice(&buf[1..])?) {
+                Ok(scale) => Self::Decimal{scale},
+                Err(_) => bail!(ParseError::InvalidValue)
+            },
+            21 => {
+                let count = u32::from_byte_slice(&buf[1..])? as usize;
+                Self::Enum{variants: Self::read_variants(reader, count, Self::MAX_ENUM_VARIANTS)?}
+            },
+            22 => Self::Uuid,
+            23 => {
+                Self::Json(u32::from_byte_slice(&buf[1..])?)
+            },
+            24 => {
+                let len = u32::from_byte_slice(&buf[1..])?;
+                Self::Array{element: Box::new(Self::read_array_element(reader)?), len}
+            },
+            25 => Self::StrVar,
             _ => bail!(ParseError::InvalidValue)
         };
+        field_type.validate()?;
         Ok(field_type)
     }
 }
@@ -312,9 +1413,56 @@ impl WriteTo for FieldType {
             Self::Str(size) => {
                 buf[0] = 12;
                 size.write_as_bytes(&mut buf[1..])?;
-            }
+            },
+            Self::IpAddr => buf[0] = 13,
+            Self::Char => buf[0] = 14,
+            Self::Duration => buf[0] = 15,
+            Self::Enum8(variants) => {
+                buf[0] = 16;
+                (variants.len() as u32).write_as_bytes(&mut buf[1..])?;
+            },
+            Self::Date => buf[0] = 17,
+            Self::Timestamp => buf[0] = 18,
+            Self::Bytes(size) => {
+                buf[0] = 19;
+                size.write_as_bytes(&mut buf[1..])?;
+            },
+            Self::Decimal{scale} => {
+                buf[0] = 20;
+                (*scale as u32).write_as_bytes(&mut buf[1..])?;
+            },
+            Self::Enum{variants} => {
+                buf[0] = 21;
+                (variants.len() as u32).write_as_bytes(&mut buf[1..])?;
+            },
+            Self::Uuid => buf[0] = 22,
+            Self::Json(size) => {
+                buf[0] = 23;
+                size.write_as_bytes(&mut buf[1..])?;
+            },
+            Self::Array{len, ..} => {
+                buf[0] = 24;
+                len.write_as_bytes(&mut buf[1..])?;
+            },
+            Self::StrVar => buf[0] = 25
         };
         writer.write_all(&buf)?;
+
+        // write array element type
+        if let Self::Array{element, ..} = self {
+            element.write_to(writer)?;
+        }
+
+        // write enum variant names
+        if let Self::Enum8(variants) | Self::Enum{variants} = self {
+            for variant in variants {
+                if variant.len() > Field::MAX_NAME_SIZE {
+                    bail!("{} variant size must be <= {} bytes length", self.type_name(), Field::MAX_NAME_SIZE);
+                }
+                (variant.len() as u32).write_to(writer)?;
+                writer.write_all(variant.as_bytes())?;
+            }
+        }
         Ok(())
     }
 }
@@ -323,13 +1471,21 @@ impl WriteTo for FieldType {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Field {
     _name: String,
-    _value_type: FieldType
+    _value_type: FieldType,
+    #[serde(default)]
+    _nullable: bool
 }
 
 impl Field {
     /// Name string max allowed length.
     const MAX_NAME_SIZE: usize = 50;
 
+    /// Field type first byte bit flag used to mark a field as nullable.
+    pub const NULLABLE_FLAG: u8 = 0x80;
+
+    /// Byte size of the presence byte prefixed to nullable field values.
+    const PRESENCE_BYTES: usize = 1;
+
     /// Create a new field.
     /// 
     /// # Arguments
@@ -340,12 +1496,28 @@ impl Field {
         if name.as_bytes().len() > Self::MAX_NAME_SIZE {
             bail!("field name size must be <= {} bytes length", Self::MAX_NAME_SIZE);
         }
+        value_type.validate()?;
         Ok(Self{
             _name: name.to_string(),
-            _value_type: value_type
+            _value_type: value_type,
+            _nullable: false
         })
     }
 
+    /// Create a new nullable field. Nullable field values are prefixed
+    /// by a presence byte so [Value::Null] can be told apart from a zero
+    /// value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Field name. The name string must be <= [MAX_NAME_SIZE] bytes length.
+    /// * `value_type` - Value field type.
+    pub fn new_nullable(name: &str, value_type: FieldType) -> Result<Self> {
+        let mut field = Self::new(name, value_type)?;
+        field._nullable = true;
+        Ok(field)
+    }
+
     /// Returns the field name.
     pub fn get_name(&self) -> &str {
         &self._name
@@ -355,10 +1527,144 @@ impl Field {
     pub fn get_type(&self) -> &FieldType {
         &self._value_type
     }
+
+    /// Returns `true` when the field accepts [Value::Null] values.
+    pub fn is_nullable(&self) -> bool {
+        self._nullable
+    }
+
+    /// Gets the byte size of the field value within a record, including
+    /// the presence byte on nullable fields.
+    pub fn value_byte_size(&self) -> usize {
+        if self._nullable {
+            return Self::PRESENCE_BYTES + self._value_type.value_byte_size();
+        }
+        self._value_type.value_byte_size()
+    }
+
+    /// Gets the byte size of the field definition within the record header.
+    pub fn size_as_bytes(&self) -> u64 {
+        (Self::BYTES + self._value_type.variants_byte_size()) as u64
+    }
+
+    /// Validate a value against the field.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Value to validate.
+    pub fn is_valid(&self, value: &Value) -> bool {
+        if let Value::Null = value {
+            return self._nullable;
+        }
+        self._value_type.is_valid(value)
+    }
+
+    /// Validate a value against the field as it would be written using a
+    /// trim mode and describe why it is invalid on error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Value to validate.
+    /// * `trim` - Trim mode applied to string values.
+    pub fn validate_value_with(&self, value: &Value, trim: TrimMode) -> Result<()> {
+        if let Value::Null = value {
+            if !self._nullable {
+                bail!("null value on a non-nullable field");
+            }
+            return Ok(());
+        }
+        self._value_type.validate_value_with(value, trim)
+    }
+
+    /// Reads a field value from a reader.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    pub fn read_value(&self, reader: &mut impl Read) -> Result<Value> {
+        self.read_value_endian(reader, Endianness::Big)
+    }
+
+    /// Reads a field value from a reader, numeric values are decoded
+    /// using the provided byte order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    /// * `endianness` - Byte order of numeric values.
+    pub fn read_value_endian(&self, reader: &mut impl Read, endianness: Endianness) -> Result<Value> {
+        if !self._nullable {
+            return self._value_type.read_value_endian(reader, endianness);
+        }
+
+        // read the presence byte and skip the value when null
+        let value = match u8::read_from(reader)? {
+            0 => {
+                let mut buf = vec![0u8; self._value_type.value_byte_size()];
+                reader.read_exact(&mut buf)?;
+                Value::Null
+            },
+            1 => self._value_type.read_value_endian(reader, endianness)?,
+            _ => bail!(ParseError::InvalidValue)
+        };
+        Ok(value)
+    }
+
+    /// Writes a field value into a writer, string values are trimmed
+    /// before checking their size.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `value` - Value to write.
+    /// * `trim` - Trim mode applied to string values.
+    pub fn write_value_with(&self, writer: &mut impl Write, value: &Value, trim: TrimMode) -> Result<()> {
+        self.write_value_endian(writer, value, trim, Endianness::Big)
+    }
+
+    /// Writes a field value into a writer, numeric values are encoded
+    /// using the provided byte order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `value` - Value to write.
+    /// * `trim` - Trim mode applied to string values.
+    /// * `endianness` - Byte order of numeric values.
+    pub fn write_value_endian(
+        &self,
+        writer: &mut impl Write,
+        value: &Value,
+        trim: TrimMode,
+        endianness: Endianness
+    ) -> Result<()> {
+        if !self._nullable {
+            if let Value::Null = value {
+                bail!("null value on a non-nullable field");
+            }
+            return self._value_type.write_value_endian(writer, value, trim, endianness);
+        }
+
+        // write the presence byte followed by the value
+        if let Value::Null = value {
+            0u8.write_to(writer)?;
+            writer.write_all(&vec![0u8; self._value_type.value_byte_size()])?;
+            return Ok(());
+        }
+        let mut buf = Vec::with_capacity(self._value_type.value_byte_size());
+        self._value_type.write_value_endian(&mut buf, value, trim, endianness)?;
+        1u8.write_to(writer)?;
+        writer.write_all(&buf)?;
+        Ok(())
+    }
 }
 
 impl ByteSized for Field {
     /// Byte representation: `<name_value_size:4><name_value:50><field_type:5>`.
+    /// The field type first byte includes [Field::NULLABLE_FLAG] on
+    /// nullable fields. [FieldType::Enum8] and [FieldType::Enum] variant
+    /// names follow, see
+    /// [Field::size_as_bytes].
     const BYTES: usize = 59;
 }
 
@@ -376,11 +1682,16 @@ impl ReadFrom for Field {
         let name_buf = &buf[..size];
         let name = String::from_utf8(name_buf.to_vec())?;
 
-        // read field value type
-        let value_type = FieldType::read_from(reader)?;
+        // read field value type along the nullable flag
+        let mut buf = [0u8; FieldType::BYTES];
+        reader.read_exact(&mut buf)?;
+        let nullable = buf[0] & Self::NULLABLE_FLAG != 0;
+        buf[0] &= !Self::NULLABLE_FLAG;
+        let value_type = FieldType::read_from(&mut (&buf[..]).chain(reader))?;
 
         // build field and provide read byte count
-        let field = Field::new(&name, value_type)?;
+        let mut field = Field::new(&name, value_type)?;
+        field._nullable = nullable;
         Ok(field)
     }
 }
@@ -404,27 +1715,147 @@ impl WriteTo for Field {
         buf_writer.write_all(name_bytes)?;
         writer.write_all(&buf)?;
 
-        // write field value type
-        self._value_type.write_to(writer)?;
+        // write field value type along the nullable flag
+        let mut buf = Vec::with_capacity(FieldType::BYTES + self._value_type.variants_byte_size());
+        self._value_type.write_to(&mut buf)?;
+        if self._nullable {
+            buf[0] |= Self::NULLABLE_FLAG;
+        }
+        writer.write_all(&buf)?;
         Ok(())
     }
 }
 
-/// Represent the record header. Byte format: `<field_count:1><fields:?>`
-#[derive(Debug, PartialEq, Clone)]
+/// Serializable field description used by [SchemaDescriptor].
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct FieldDescriptor {
+    /// Field name.
+    pub name: String,
+
+    /// Field type name as returned by [FieldType::type_name].
+    #[serde(rename = "type")]
+    pub type_name: String,
+
+    /// Value max size, only on `str`, `bytes` and `json` fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+
+    /// `true` when the field accepts null values.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub nullable: bool,
+
+    /// Variant names, only on `enum8` and `enum` fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<String>>,
+
+    /// Decimal scale, only on `decimal` fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<u8>,
+
+    /// Element type, only on `array` fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<ElementDescriptor>,
+
+    /// Element count, only on `array` fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub len: Option<u32>,
+
+    /// Value byte size within the record, ignored when building a header.
+    #[serde(default)]
+    pub byte_size: usize
+}
+
+/// Serializable array element type description used by [FieldDescriptor].
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct ElementDescriptor {
+    /// Element type name as returned by [FieldType::type_name].
+    #[serde(rename = "type")]
+    pub type_name: String,
+
+    /// Variant names, only on `enum8` and `enum` elements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<String>>,
+
+    /// Decimal scale, only on `decimal` elements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<u8>
+}
+
+impl ElementDescriptor {
+    /// Describes an array element type.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `field_type` - Element type.
+    pub fn new(field_type: &FieldType) -> Self {
+        Self{
+            type_name: field_type.type_name().to_string(),
+            variants: field_type.variants().cloned(),
+            scale: field_type.scale()
+        }
+    }
+
+    /// Builds the element type from its description.
+    pub fn to_field_type(&self) -> Result<FieldType> {
+        FieldType::from_description(&self.type_name, None, self.variants.as_ref(), self.scale)
+    }
+}
+
+/// Serializable schema description independent from the binary layout.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct SchemaDescriptor {
+    /// Fields in record order.
+    pub fields: Vec<FieldDescriptor>
+}
+
+/// Field differences between two headers, see [Header::diff].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct HeaderDiff {
+    /// Field names only found on the other header.
+    pub added: Vec<String>,
+
+    /// Field names only found on the header.
+    pub removed: Vec<String>,
+
+    /// Field names found on both headers with different types or
+    /// nullability.
+    pub type_changed: Vec<String>
+}
+
+impl HeaderDiff {
+    /// Returns true when no field was added, removed nor changed. Field
+    /// order isn't compared, see [Header::is_read_compatible].
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.type_changed.is_empty()
+    }
+}
+
+/// Represent the record header. Byte format: `<field_count:4><fields:?>`,
+/// the field count includes [Header::LITTLE_ENDIAN_FLAG] when the record
+/// numeric values are little-endian. The trim mode is a write option that
+/// isn't stored, so it's ignored on equality.
+#[derive(Debug, Clone)]
 pub struct Header {
     _list: Vec<Field>,
     _map: HashMap<String, usize>,
-    _record_byte_size: u64
+    _record_byte_size: u64,
+    _trim_mode: TrimMode,
+    _endianness: Endianness
 }
 
 impl Header {
+    /// Field count bit flag used to mark the record numeric values as
+    /// encoded in little-endian byte order.
+    pub const LITTLE_ENDIAN_FLAG: u32 = 0x8000_0000;
+
     /// Create a new instance.
     pub fn new() -> Self {
         Self{
             _list: Vec::new(),
             _map: HashMap::new(),
-            _record_byte_size: 0
+            _record_byte_size: 0,
+            _trim_mode: TrimMode::None,
+            _endianness: Endianness::Big
         }
     }
 
@@ -435,7 +1866,47 @@ impl Header {
     /// * `name` - Field name.
     /// * `value_type` - Field value type.
     pub fn add(&mut self, name: &str, value_type: FieldType) -> Result<&Self> {
-        let field = Field::new(name, value_type)?;
+        self.add_field(Field::new(name, value_type)?)
+    }
+
+    /// Add a new nullable field.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Field name.
+    /// * `value_type` - Field value type.
+    pub fn add_nullable(&mut self, name: &str, value_type: FieldType) -> Result<&Self> {
+        self.add_field(Field::new_nullable(name, value_type)?)
+    }
+
+    /// Inserts a new field at an index position shifting the fields after
+    /// it. This changes the byte layout of every record written afterwards,
+    /// so records written before won't be readable with this header.
+    /// This is currently very inefficient as the map is rebuilt.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Index position to insert the field at.
+    /// * `name` - Field name.
+    /// * `value_type` - Field value type.
+    pub fn insert_at(&mut self, index: usize, name: &str, value_type: FieldType) -> Result<()> {
+        if index > self._list.len() {
+            bail!("field index {} is out of range, the header has {} fields", index, self._list.len());
+        }
+        if self._map.contains_key(name) {
+            bail!("field \"{}\" already exists within the header", name);
+        }
+        self._list.insert(index, Field::new(name, value_type)?);
+        self.rebuild_hashmap();
+        Ok(())
+    }
+
+    /// Add a field.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `field` - Field to add.
+    fn add_field(&mut self, field: Field) -> Result<&Self> {
 
         // avoid duplicated fields
         if let Some(_) = self._map.get(&field._name) {
@@ -443,9 +1914,9 @@ impl Header {
         }
 
         // add field
-        self._record_byte_size += field._value_type.value_byte_size() as u64;
+        self._record_byte_size += field.value_byte_size() as u64;
+        self._map.insert(field._name.clone(), self._list.len());
         self._list.push(field);
-        self._map.insert(name.to_string(), self._list.len()-1);
         
         Ok(self)
     }
@@ -456,7 +1927,7 @@ impl Header {
         let mut record_size = 0u64;
         for (index, field) in self._list.iter().enumerate() {
             field_map.insert(field._name.clone(), index);
-            record_size += field._value_type.value_byte_size() as u64;
+            record_size += field.value_byte_size() as u64;
         }
         self._map = field_map;
         self._record_byte_size = record_size;
@@ -491,6 +1962,62 @@ impl Header {
         Some(self.remove(index))
     }
 
+    /// Renames a field keeping its position and type, so the record
+    /// layout doesn't change.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `old` - Current field name.
+    /// * `new` - New field name. The name string must be <= [Field::MAX_NAME_SIZE] bytes length.
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<()> {
+        let index = match self._map.get(old) {
+            Some(v) => *v,
+            None => bail!("field \"{}\" doesn't exists within the header", old)
+        };
+        if old == new {
+            return Ok(());
+        }
+        if new.len() > Field::MAX_NAME_SIZE {
+            bail!("field name size must be <= {} bytes length", Field::MAX_NAME_SIZE);
+        }
+        if self._map.contains_key(new) {
+            bail!("field \"{}\" already exists within the header", new);
+        }
+
+        // update the field name and its map key
+        self._map.remove(old);
+        self._map.insert(new.to_string(), index);
+        self._list[index]._name = new.to_string();
+        Ok(())
+    }
+
+    /// Reorders the fields so the field at `order[i]` is moved to the
+    /// index position `i`. This changes the byte layout of every record
+    /// written afterwards, so records written before won't be readable
+    /// with this header.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `order` - Permutation of the current field indexes.
+    pub fn reorder(&mut self, order: &[usize]) -> Result<()> {
+        if order.len() != self._list.len() {
+            bail!("field order has {} indexes but the header has {} fields", order.len(), self._list.len());
+        }
+        let mut seen = vec![false; order.len()];
+        for index in order {
+            if *index >= seen.len() {
+                bail!("field index {} is out of range, the header has {} fields", index, self._list.len());
+            }
+            if seen[*index] {
+                bail!("field index {} is repeated within the field order", index);
+            }
+            seen[*index] = true;
+        }
+        self._list = order.iter().map(|index| self._list[*index].clone()).collect();
+        self.rebuild_hashmap();
+        Ok(())
+    }
+
     /// Get a field by name.
     /// 
     /// # Arguments
@@ -549,10 +2076,17 @@ impl Header {
     /// Return the previously calculated byte count to be writed when
     /// the header is converted into bytes.
     pub fn size_as_bytes(&self) -> u64 {
-        u32::BYTES as u64 + (Field::BYTES as u64 * self._list.len() as u64)
+        u32::BYTES as u64 + self._list.iter().map(|v| v.size_as_bytes()).sum::<u64>()
+    }
+
+    /// Returns true when every field is a fixed length type, so records
+    /// can be randomly accessed by index.
+    pub fn is_fixed_length(&self) -> bool {
+        self._list.iter().all(|field| field.get_type().is_fixed_length())
     }
 
-    /// Returns the record size in bytes.
+    /// Returns the record size in bytes, only the min field value sizes are
+    /// counted when the header isn't fixed length, see [Self::is_fixed_length].
     pub fn record_byte_size(&self) -> u64 {
         return self._record_byte_size;
     }
@@ -574,53 +2108,496 @@ impl Header {
         Ok(record)
     }
 
-    /// Reads a record from the reader.
+    /// Reads a record from the reader. A reader ending before the record
+    /// is complete returns [ParseError::UnexpectedEof] naming the field
+    /// being read. Variable length records are prefixed by their byte size
+    /// as `<size:4><values:size>`.
     /// 
     /// # Arguments
     /// 
     /// * `reader` - Byte reader.
     pub fn read_record(&self, reader: &mut impl Read) -> Result<Record> {
-        let mut record = Record::new();
-
-        for field in self._list.iter() {
-            let value = field._value_type.read_value(reader)?;
-            record.add(&field._name, value)?;
-        }
-        Ok(record)
+        self.read_record_with_max(reader, None)
     }
 
-    /// Writes a record into the writer.
+    /// Same as [Self::read_record] but a variable length record declaring
+    /// a byte size bigger than `max` fails with [ParseError::RecordTooBig]
+    /// before being read. Fixed length records aren't checked.
     /// 
     /// # Arguments
     /// 
-    /// * `writer` - Byte writer.
-    pub fn write_record(&self, writer: &mut impl Write, record: &Record) -> Result<()> {
-        if self._list.len() != record.len() {
-            bail!("header field count mismatch the record value count");
+    /// * `reader` - Byte reader.
+    /// * `max` - Max variable length record byte size, no limit when `None`.
+    pub fn read_record_with_max(&self, reader: &mut impl Read, max: Option<u64>) -> Result<Record> {
+        if self.is_fixed_length() {
+            return self.read_values(reader);
         }
-        for (index, field) in self._list.iter().enumerate() {
-            let value = match record.get_by_index(index) {
-                Some(v) => v,
-                None => bail!("invalid value index! this should never happen, please check \
-                    the record \"len()\" function")
-            };
-            if let Err(e) = field._value_type.write_value(writer, value) {
-                bail!("error saving field \"{}\": {}", &field._name, e);
+
+        // read and check the declared record size before reading the record
+        let size = u32::read_from_endian(reader, self._endianness)? as u64;
+        if let Some(max) = max {
+            if size > max {
+                bail!(ParseError::RecordTooBig{size, max})
             }
         }
-        Ok(())
+        let buf = read_sized(reader, size)?;
+        if (buf.len() as u64) < size {
+            bail!(ParseError::SizeMismatch{
+                expected: size as usize,
+                found: buf.len()
+            });
+        }
+        let mut data = &buf[..];
+        let record = self.read_values(&mut data)?;
+        if !data.is_empty() {
+            bail!(ParseError::SizeMismatch{
+                expected: buf.len() - data.len(),
+                found: buf.len()
+            });
+        }
+        Ok(record)
+    }
+
+    /// Reads every field value from the reader into a record.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    fn read_values(&self, reader: &mut impl Read) -> Result<Record> {
+        let mut record = Record::new();
+
+        for field in self._list.iter() {
+            let value = match field.read_value_endian(reader, self._endianness) {
+                Ok(v) => v,
+                Err(e) => match e.downcast_ref::<std::io::Error>() {
+                    Some(ex) if ex.kind() == std::io::ErrorKind::UnexpectedEof => {
+                        bail!(ParseError::UnexpectedEof(field._name.clone()))
+                    },
+                    _ => return Err(e)
+                }
+            };
+            record.add(&field._name, value)?;
+        }
+        Ok(record)
+    }
+
+    /// Writes a record into the writer, variable length records are
+    /// prefixed by their byte size, see [Self::read_record].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    pub fn write_record(&self, writer: &mut impl Write, record: &Record) -> Result<()> {
+        if self._list.len() != record.len() {
+            bail!(ParseError::FieldCountMismatch{
+                expected: self._list.len(),
+                got: record.len()
+            });
+        }
+        if self.is_fixed_length() {
+            return self.write_values(writer, record);
+        }
+
+        // encode the record first so its size is known
+        let mut buf = Vec::with_capacity(self._record_byte_size as usize);
+        self.write_values(&mut buf, record)?;
+        let size = match u32::try_from(buf.len()) {
+            Ok(v) => v,
+            Err(_) => bail!("record size ({} bytes) is too big", buf.len())
+        };
+        size.write_to_endian(writer, self._endianness)?;
+        writer.write_all(&buf)?;
+        Ok(())
+    }
+
+    /// Writes every record value into the writer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `record` - Record to write.
+    fn write_values(&self, writer: &mut impl Write, record: &Record) -> Result<()> {
+        for (index, field) in self._list.iter().enumerate() {
+            let value = match record.get_by_index(index) {
+                Some(v) => v,
+                None => bail!("invalid value index! this should never happen, please check \
+                    the record \"len()\" function")
+            };
+            if let Err(e) = field.write_value_endian(writer, value, self._trim_mode, self._endianness) {
+                bail!("error saving field \"{}\": {}", &field._name, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes `count` records made of [Value::Default] values into the
+    /// writer and returns the total bytes written. Useful to preallocate
+    /// a table file along with [crate::fill_file].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `count` - Records to write.
+    pub fn write_default_records(&self, writer: &mut impl Write, count: u64) -> Result<u64> {
+        let mut buf = Vec::with_capacity(self._record_byte_size as usize);
+        self.write_record(&mut buf, &self.new_record()?)?;
+        for _ in 0..count {
+            writer.write_all(&buf)?;
+        }
+        Ok(count * buf.len() as u64)
+    }
+
+    /// Builds a record from a JSON object keyed by field name. Missing
+    /// fields and JSON null are converted into [Value::Default], or
+    /// [Value::Null] on nullable fields, and every value is validated
+    /// against its field type.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `json` - JSON object to build the record from.
+    pub fn record_from_json(&self, json: &JSValue) -> Result<Record> {
+        let obj = match json {
+            JSValue::Object(v) => v,
+            _ => bail!("can't build a record from a JSON value other than object")
+        };
+        for key in obj.keys() {
+            if !self._map.contains_key(key) {
+                bail!("field \"{}\" doesn't exists within the header", key);
+            }
+        }
+
+        let mut record = Record::new();
+        for field in self._list.iter() {
+            let value = match obj.get(&field._name) {
+                None | Some(JSValue::Null) if field._nullable => Value::Null,
+                None => Value::Default,
+                Some(v) => match Value::from_json_typed(v.clone(), &field._value_type) {
+                    Ok(v) => v,
+                    Err(e) => bail!("invalid value for field \"{}\": {}", field._name, e)
+                }
+            };
+            if !field.is_valid(&value) {
+                bail!("invalid value for field \"{}\": value {:?} doesn't match the field type {:?}",
+                    field._name, value, field._value_type);
+            }
+            record.add(&field._name, value)?;
+        }
+        Ok(record)
+    }
+
+    /// Sets a record field value after validating it against the header
+    /// field, so invalid values are rejected before writing the record.
+    /// [Value::Default] is always accepted.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `record` - Record to update.
+    /// * `name` - Field name.
+    /// * `value` - New value.
+    pub fn set_checked(&self, record: &mut Record, name: &str, value: Value) -> Result<()> {
+        let field = match self.get(name) {
+            Some(v) => v,
+            None => bail!("field \"{}\" doesn't exists within the header", name)
+        };
+        if value != Value::Default && !field.is_valid(&value) {
+            bail!("invalid value for field \"{}\": value {:?} doesn't match the field type {:?}",
+                name, value, field._value_type);
+        }
+        record.set(name, value)
+    }
+
+    /// Gets the trim mode applied to string values on write.
+    pub fn trim_mode(&self) -> TrimMode {
+        self._trim_mode
+    }
+
+    /// Sets the trim mode applied to string values on write. This is a
+    /// write option and it isn't stored within the table file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mode` - Trim mode.
+    pub fn set_trim_mode(&mut self, mode: TrimMode) {
+        self._trim_mode = mode;
+    }
+
+    /// Gets the byte order used to encode numeric values.
+    pub fn endianness(&self) -> Endianness {
+        self._endianness
+    }
+
+    /// Sets the byte order used to encode numeric values. It is stored
+    /// along the fields so records are read back with the same order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `endianness` - Byte order.
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self._endianness = endianness;
     }
 
     /// Returns an iterator over the header fields.
     pub fn iter(&self) -> std::slice::Iter<Field> {
         self._list.iter()
     }
+
+    /// Returns the byte offset of a field within a record, the sum of the
+    /// byte size of the preceding fields. Offsets are only valid when every
+    /// field is fixed length, see [Self::is_fixed_length].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Field name.
+    pub fn field_offset(&self, name: &str) -> Result<u64> {
+        let index = match self._map.get(name) {
+            Some(v) => *v,
+            None => bail!("field \"{}\" doesn't exists within the header", name)
+        };
+        if !self.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        let mut offset = 0u64;
+        for field in self._list[..index].iter() {
+            offset += field.value_byte_size() as u64;
+        }
+        Ok(offset)
+    }
+
+    /// Returns the byte span of a field within a record as `(offset, size)`,
+    /// useful to seek and read a single field value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Field name.
+    pub fn field_span(&self, name: &str) -> Result<(u64, u64)> {
+        let offset = self.field_offset(name)?;
+        let size = self._list[self._map[name]].value_byte_size() as u64;
+        Ok((offset, size))
+    }
+
+    /// Compares the fields (names and types in order) and the byte order
+    /// against another header, ignoring any cached value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Header to compare with.
+    pub fn structurally_eq(&self, other: &Header) -> bool {
+        self._list == other._list && self._endianness == other._endianness
+    }
+
+    /// Lists the fields added, removed or with a changed type on another
+    /// header compared to this one, each list follows the field order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Header to compare with.
+    pub fn diff(&self, other: &Header) -> HeaderDiff {
+        let mut diff = HeaderDiff::default();
+        for field in self._list.iter() {
+            match other.get(&field._name) {
+                None => diff.removed.push(field._name.clone()),
+                Some(v) if v._value_type != field._value_type || v._nullable != field._nullable => {
+                    diff.type_changed.push(field._name.clone());
+                },
+                Some(_) => {}
+            }
+        }
+        for field in other._list.iter() {
+            if self.get(&field._name).is_none() {
+                diff.added.push(field._name.clone());
+            }
+        }
+        diff
+    }
+
+    /// Returns true when records written with another header can be read
+    /// with this header layout, so both must have the same fields in the
+    /// same order with the same types and the same byte order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Header to compare with.
+    pub fn is_read_compatible(&self, other: &Header) -> bool {
+        self.structurally_eq(other)
+    }
+
+    /// Builds a header from a CSV file by using its header row as field
+    /// names and inferring each field type from a sample of data rows.
+    /// Columns with only `true` or `false` values are inferred as
+    /// [FieldType::Bool], integers as [FieldType::I64], decimals as
+    /// [FieldType::F64] and anything else as [FieldType::Str] sized to the
+    /// longest sampled value, or 1 byte when every sampled value is empty
+    /// so the field passes [FieldType::validate_definition]. Empty values
+    /// don't affect the inferred type.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - CSV file path.
+    /// * `sample_rows` - Max data row count to sample.
+    pub fn infer_from_csv(path: &PathBuf, sample_rows: usize) -> Result<Header> {
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .flexible(true)
+            .from_path(path)?;
+        let names: Vec<String> = csv_reader.headers()?.iter().map(|v| v.to_string()).collect();
+
+        // (is bool, is integer, is decimal, has values, max length) per column
+        let mut columns = vec![(true, true, true, false, 1usize); names.len()];
+        for item in csv_reader.records().take(sample_rows) {
+            let item = item?;
+            for (column, value) in columns.iter_mut().zip(item.iter()) {
+                column.4 = column.4.max(value.len());
+                if value.is_empty() {
+                    continue;
+                }
+                column.0 = column.0 && (value == "true" || value == "false");
+                column.1 = column.1 && value.parse::<i64>().is_ok();
+                column.2 = column.2 && value.parse::<f64>().is_ok();
+                column.3 = true;
+            }
+        }
+
+        // build header
+        let mut header = Self::new();
+        for (name, column) in names.iter().zip(columns) {
+            let value_type = match column {
+                (_, _, _, false, size) => FieldType::Str(size as u32),
+                (true, _, _, _, _) => FieldType::Bool,
+                (_, true, _, _, _) => FieldType::I64,
+                (_, _, true, _, _) => FieldType::F64,
+                (_, _, _, _, size) => FieldType::Str(size as u32)
+            };
+            header.add(name, value_type)?;
+        }
+        Ok(header)
+    }
+
+    /// Describes the header fields as a serializable schema descriptor.
+    pub fn to_descriptor(&self) -> SchemaDescriptor {
+        let fields = self._list.iter().map(|field| FieldDescriptor{
+            name: field._name.clone(),
+            type_name: field._value_type.type_name().to_string(),
+            size: field._value_type.max_size(),
+            nullable: field._nullable,
+            variants: field._value_type.variants().cloned(),
+            scale: field._value_type.scale(),
+            element: match &field._value_type {
+                FieldType::Array{element, ..} => Some(ElementDescriptor::new(element)),
+                _ => None
+            },
+            len: match &field._value_type {
+                FieldType::Array{len, ..} => Some(*len),
+                _ => None
+            },
+            byte_size: field.value_byte_size()
+        }).collect();
+        SchemaDescriptor{fields}
+    }
+
+    /// Builds a header from a schema descriptor. Field byte sizes are
+    /// ignored since these are calculated from the field types.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `descriptor` - Schema descriptor.
+    pub fn from_descriptor(descriptor: &SchemaDescriptor) -> Result<Self> {
+        let mut header = Self::new();
+        for field in descriptor.fields.iter() {
+            let value_type = match (field.type_name.as_str(), &field.element, field.len) {
+                ("array", Some(element), Some(len)) => element.to_field_type().map(|v| FieldType::Array{
+                    element: Box::new(v),
+                    len
+                }),
+                _ => FieldType::from_description(&field.type_name, field.size, field.variants.as_ref(), field.scale)
+            };
+            let value_type = match value_type {
+                Ok(v) => v,
+                Err(e) => bail!("field \"{}\": {}", field.name, e)
+            };
+            if field.nullable {
+                header.add_nullable(&field.name, value_type)?;
+            } else {
+                header.add(&field.name, value_type)?;
+            }
+        }
+        Ok(header)
+    }
+
+    /// Describes the header fields as a JSON schema, an array of
+    /// `{name, type, size?, nullable?, variants?, scale?, element?, len?}`
+    /// objects in record order.
+    pub fn to_schema_json(&self) -> JSValue {
+        let fields = self.to_descriptor().fields.into_iter().map(|field| {
+            let mut value = serde_json::to_value(field).unwrap_or(JSValue::Null);
+            if let JSValue::Object(map) = &mut value {
+                map.remove("byte_size");
+            }
+            value
+        }).collect();
+        JSValue::Array(fields)
+    }
+
+    /// Builds a header from a JSON schema as produced by
+    /// [Self::to_schema_json].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - JSON schema.
+    pub fn from_schema_json(value: &JSValue) -> Result<Self> {
+        if !value.is_array() {
+            bail!("schema JSON must be an array of fields");
+        }
+        let fields: Vec<FieldDescriptor> = match serde_json::from_value(value.clone()) {
+            Ok(v) => v,
+            Err(e) => bail!("invalid schema JSON: {}", e)
+        };
+        Self::from_descriptor(&SchemaDescriptor{fields})
+    }
+
+    /// Generates a stable hash from the header fields (names and types in
+    /// order) to detect schema drift.
+    pub fn schema_hash(&self) -> Result<[u8; HASH_SIZE]> {
+        let mut buf = Vec::with_capacity(self.size_as_bytes() as usize);
+        self.write_to(&mut buf)?;
+        let hash = Sha3_256::digest(&buf);
+        Ok(hash.into())
+    }
+
+    /// Generates a hash from the record values as serialized by
+    /// [Self::write_record], useful as a cheap equality key to dedup
+    /// records. The hash is layout dependent, so values are hashed in the
+    /// header field order and the same values under a different header
+    /// will produce a different hash.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `record` - Record to hash.
+    pub fn hash_record(&self, record: &Record) -> Result<[u8; HASH_SIZE]> {
+        let mut buf = Vec::with_capacity(self.record_byte_size() as usize);
+        self.write_record(&mut buf, record)?;
+        Ok(crate::generate_hash(&mut &buf[..])?)
+    }
+}
+
+impl PartialEq for Header {
+    fn eq(&self, other: &Self) -> bool {
+        self._list == other._list
+            && self._map == other._map
+            && self._record_byte_size == other._record_byte_size
+            && self._endianness == other._endianness
+    }
 }
 
 impl LoadFrom for Header {
     fn load_from(&mut self, reader: &mut impl Read) -> Result<()> {
-        // read field count
+        // read field count along the byte order flag
         let field_count = u32::read_from(reader)?;
+        let endianness = if field_count & Self::LITTLE_ENDIAN_FLAG != 0 {
+            Endianness::Little
+        } else {
+            Endianness::Big
+        };
+        let field_count = field_count & !Self::LITTLE_ENDIAN_FLAG;
 
         // read fields
         let mut record_size = 0u64;
@@ -632,7 +2609,7 @@ impl LoadFrom for Header {
             if let Some(_) = map.insert(field._name.clone(), i as usize) {
                 bail!("duplicated field \"{}\"", &field._name);
             }
-            record_size += field._value_type.value_byte_size() as u64;
+            record_size += field.value_byte_size() as u64;
             list.push(field);
         }
 
@@ -640,6 +2617,7 @@ impl LoadFrom for Header {
         self._list = list;
         self._map = map;
         self._record_byte_size = record_size;
+        self._endianness = endianness;
         Ok(())
     }
 }
@@ -654,8 +2632,11 @@ impl ReadFrom for Header {
 
 impl WriteTo for Header {
     fn write_to(&self, writer: &mut impl Write) -> Result<()> {
-        // write field count
-        let field_count = self._list.len() as u32;
+        // write field count along the byte order flag
+        let mut field_count = self._list.len() as u32;
+        if self._endianness == Endianness::Little {
+            field_count |= Self::LITTLE_ENDIAN_FLAG;
+        }
         field_count.write_to(writer)?;
 
         // write fields data
@@ -680,7 +2661,7 @@ mod tests {
 
         #[test]
         fn max_type_id() {
-            assert_eq!(12u8, FieldType::MAX_TYPE_ID);
+            assert_eq!(25u8, FieldType::MAX_TYPE_ID);
         }
 
         #[test]
@@ -697,6 +2678,27 @@ mod tests {
             assert_eq!(f32::BYTES, FieldType::F32.value_byte_size());
             assert_eq!(f64::BYTES, FieldType::F64.value_byte_size());
             assert_eq!(29usize, FieldType::Str(25u32).value_byte_size());
+            assert_eq!(17usize, FieldType::IpAddr.value_byte_size());
+            assert_eq!(4usize, FieldType::Char.value_byte_size());
+            assert_eq!(8usize, FieldType::Duration.value_byte_size());
+            assert_eq!(4usize, FieldType::Date.value_byte_size());
+            assert_eq!(8usize, FieldType::Timestamp.value_byte_size());
+            assert_eq!(14usize, FieldType::Bytes(10u32).value_byte_size());
+        }
+
+        #[test]
+        fn validate_definition() {
+            assert!(FieldType::Str(5).validate_definition().is_ok());
+            assert!(FieldType::Bytes(5).validate_definition().is_ok());
+            assert!(FieldType::I32.validate_definition().is_ok());
+            match FieldType::Str(0).validate_definition() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("string field capacity can't be zero", e.to_string())
+            }
+            match FieldType::Bytes(0).validate_definition() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("bytes field capacity can't be zero", e.to_string())
+            }
         }
 
         #[test]
@@ -931,6 +2933,22 @@ mod tests {
             assert_eq!(false, field_type.is_valid(&Value::Str("abcdef".to_string())));
         }
 
+        #[test]
+        fn validate_value() -> Result<()> {
+            FieldType::Str(5).validate_value(&Value::Str("abcde".to_string()))?;
+            FieldType::Str(5).validate_value(&Value::Default)?;
+            FieldType::I32.validate_value(&Value::I32(3))?;
+            match FieldType::Str(5).validate_value(&Value::Str("abcdef".to_string())) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("string value (6 bytes) exceeds field size (5 bytes)", e.to_string())
+            }
+            match FieldType::I32.validate_value(&Value::Bool(true)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value Bool(true) doesn't match the field type I32", e.to_string())
+            }
+            Ok(())
+        }
+
         #[test]
         fn bool_read_value() {
             let expected = Value::Bool(false);
@@ -1091,6 +3109,28 @@ mod tests {
             }
         }
 
+        #[test]
+        fn str_read_value_invalid_utf8() {
+            let mut reader = &[
+                // value size as 3u32
+                0u8, 0u8, 0u8, 3u8,
+                // "\u{e9}" lead byte followed by an invalid continuation byte
+                97u8, 195u8, 40u8, 0u8
+            ] as &[u8];
+            match FieldType::Str(4).read_value(&mut reader) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => match e.downcast::<ParseError>() {
+                    Ok(ParseError::InvalidUtf8(ex)) => assert_eq!(1, ex.utf8_error().valid_up_to()),
+                    Ok(ex) => panic!("expected ParseError::InvalidUtf8 but got {:?}", ex),
+                    Err(ex) => panic!("expected ParseError::InvalidUtf8 but got {:?}", ex)
+                }
+            }
+
+            // trailing padding bytes aren't validated
+            let mut reader = &[0u8, 0u8, 0u8, 1u8, 97u8, 195u8, 40u8, 255u8] as &[u8];
+            assert_eq!(Value::Str("a".to_string()), FieldType::Str(4).read_value(&mut reader).unwrap());
+        }
+
         #[test]
         fn str_read_value_exact() {
             let expected = Value::Str("abcdefgh".to_string());
@@ -1493,6 +3533,35 @@ mod tests {
             };
         }
 
+        #[test]
+        fn i32_write_value_endian() -> Result<()> {
+            let field_type = FieldType::I32;
+            let value = Value::I32(2064390957i32);
+
+            let mut buf = Vec::new();
+            field_type.write_value_endian(&mut buf, &value, TrimMode::None, Endianness::Big)?;
+            assert_eq!(vec![123u8, 12u8, 27u8, 45u8], buf);
+
+            let mut buf = Vec::new();
+            field_type.write_value_endian(&mut buf, &value, TrimMode::None, Endianness::Little)?;
+            assert_eq!(vec![45u8, 27u8, 12u8, 123u8], buf);
+            assert_eq!(value, field_type.read_value_endian(&mut (&buf as &[u8]), Endianness::Little)?);
+            Ok(())
+        }
+
+        #[test]
+        fn str_write_value_endian() -> Result<()> {
+            let field_type = FieldType::Str(4);
+            let value = Value::Str("ab".to_string());
+
+            // only the size prefix byte order changes
+            let mut buf = Vec::new();
+            field_type.write_value_endian(&mut buf, &value, TrimMode::None, Endianness::Little)?;
+            assert_eq!(vec![2u8, 0u8, 0u8, 0u8, 97u8, 98u8, 0u8, 0u8], buf);
+            assert_eq!(value, field_type.read_value_endian(&mut (&buf as &[u8]), Endianness::Little)?);
+            Ok(())
+        }
+
         #[test]
         fn i64_write_value() {
             let field_type = FieldType::I64;
@@ -2052,6 +4121,37 @@ mod tests {
             };
         }
 
+        #[test]
+        fn str_write_value_with_trim() -> Result<()> {
+            let field_type = FieldType::Str(6);
+            let value = Value::Str(" abc  ".to_string());
+
+            // test trim modes
+            let cases: [(TrimMode, &[u8]); 3] = [
+                (TrimMode::None, &[0, 0, 0, 6, 32, 97, 98, 99, 32, 32]),
+                (TrimMode::End, &[0, 0, 0, 4, 32, 97, 98, 99, 0, 0]),
+                (TrimMode::Both, &[0, 0, 0, 3, 97, 98, 99, 0, 0, 0])
+            ];
+            for (trim, expected) in cases {
+                let mut buf = [0u8; 10];
+                field_type.write_value_with(&mut (&mut buf as &mut [u8]), &value, trim)?;
+                assert_eq!(expected, buf);
+            }
+
+            // trimming happens before the size check
+            let field_type = FieldType::Str(3);
+            let value = Value::Str("abc   ".to_string());
+            let mut buf = [0u8; 7];
+            field_type.validate_value_with(&value, TrimMode::End)?;
+            field_type.write_value_with(&mut (&mut buf as &mut [u8]), &value, TrimMode::End)?;
+            assert_eq!([0u8, 0, 0, 3, 97, 98, 99], buf);
+            match field_type.write_value(&mut (&mut buf as &mut [u8]), &value) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("string value size (6 bytes) is bigger than field size (3 bytes)", e.to_string())
+            }
+            Ok(())
+        }
+
         #[test]
         fn str_write_value_with_other_types() {
             let field_type = FieldType::Str(1);
@@ -2106,52 +4206,281 @@ mod tests {
         }
 
         #[test]
-        fn byte_sized() {
-            assert_eq!(5, FieldType::BYTES);
+        fn ip_addr_write_value_ipv4() -> Result<()> {
+            let field_type = FieldType::IpAddr;
+            let value = Value::IpAddr("192.168.1.20".parse()?);
+            let expected = [
+                4u8, 192u8, 168u8, 1u8, 20u8,
+                0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8
+            ];
+            let mut buf = [0u8; 17];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &value)?;
+            assert_eq!(expected, buf);
+
+            // read it back
+            assert_eq!(value, field_type.read_value(&mut (&buf as &[u8]))?);
+            Ok(())
         }
 
         #[test]
-        fn bool_read_from() {
-            let mut reader = &[1u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::Bool;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn ip_addr_write_value_ipv6() -> Result<()> {
+            let field_type = FieldType::IpAddr;
+            let value = Value::IpAddr("2001:db8::ff00:42:8329".parse()?);
+            let expected = [
+                6u8, 32u8, 1u8, 13u8, 184u8, 0u8, 0u8, 0u8, 0u8,
+                0u8, 0u8, 255u8, 0u8, 0u8, 66u8, 131u8, 41u8
+            ];
+            let mut buf = [0u8; 17];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &value)?;
+            assert_eq!(expected, buf);
+
+            // read it back
+            assert_eq!(value, field_type.read_value(&mut (&buf as &[u8]))?);
+            Ok(())
         }
 
         #[test]
-        fn i8_read_from() {
-            let mut reader = &[2u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::I8;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+        fn ip_addr_write_value_default() -> Result<()> {
+            let mut expected = [0u8; 17];
+            expected[0] = 4u8;
+            let mut buf = [0u8; 17];
+            FieldType::IpAddr.write_value(&mut (&mut buf as &mut [u8]), &Value::Default)?;
+            assert_eq!(expected, buf);
+            Ok(())
+        }
+
+        #[test]
+        fn ip_addr_write_value_with_other_types() {
+            let mut buf = [0u8; 17];
+            match FieldType::IpAddr.write_value(&mut (&mut buf as &mut [u8]), &Value::Str("10.0.0.1".to_string())) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::IpAddr", e.to_string())
             };
         }
 
         #[test]
-        fn i16_read_from() {
-            let mut reader = &[3u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::I16;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+        fn ip_addr_read_value_with_invalid_version() {
+            let buf = [5u8; 17];
+            match FieldType::IpAddr.read_value(&mut (&buf as &[u8])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => match e.downcast::<ParseError>() {
+                    Ok(ParseError::InvalidValue) => {},
+                    Ok(ex) => panic!("expected ParseError::InvalidValue but got {:?}", ex),
+                    Err(ex) => panic!("expected ParseError::InvalidValue but got {:?}", ex)
+                }
             };
         }
 
         #[test]
-        fn i32_read_from() {
-            let mut reader = &[4u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::I32;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+        fn parse_value() -> Result<()> {
+            assert_eq!(Value::Bool(true), FieldType::Bool.parse_value("true")?);
+            assert_eq!(Value::I8(-12), FieldType::I8.parse_value("-12")?);
+            assert_eq!(Value::I32(1234), FieldType::I32.parse_value("1234")?);
+            assert_eq!(Value::U64(98765), FieldType::U64.parse_value("98765")?);
+            assert_eq!(Value::F64(2.5), FieldType::F64.parse_value("2.5")?);
+            assert_eq!(Value::Str("abc".to_string()), FieldType::Str(5).parse_value("abc")?);
+            Ok(())
+        }
+
+        #[test]
+        fn parse_value_with_invalid_str_size() {
+            let expected = "string value size (6 bytes) is bigger than field size (5 bytes)";
+            match FieldType::Str(5).parse_value("abcdef") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
             };
         }
 
         #[test]
-        fn i64_read_from() {
+        fn ip_addr_parse_value() -> Result<()> {
+            let expected = Value::IpAddr(IpAddr::V4(Ipv4Addr::new(10, 0, 12, 254)));
+            assert_eq!(expected, FieldType::IpAddr.parse_value("10.0.12.254")?);
+            let expected = Value::IpAddr(IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)));
+            assert_eq!(expected, FieldType::IpAddr.parse_value("fe80::1")?);
+            Ok(())
+        }
+
+        #[test]
+        fn ip_addr_parse_value_malformed() {
+            let expected = "invalid IP address \"300.1.2.3\": invalid IP address syntax";
+            match FieldType::IpAddr.parse_value("300.1.2.3") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            };
+            assert!(FieldType::IpAddr.parse_value("10.0.0").is_err());
+        }
+
+        #[test]
+        fn char_write_value() -> Result<()> {
+            let field_type = FieldType::Char;
+            for (value, expected) in [
+                ('a', [0u8, 0u8, 0u8, 97u8]),
+                ('\u{e9}', [0u8, 0u8, 0u8, 233u8]),
+                ('\u{1f980}', [0u8, 1u8, 249u8, 128u8])
+            ] {
+                let mut buf = [0u8; 4];
+                field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Char(value))?;
+                assert_eq!(expected, buf);
+
+                // read it back
+                assert_eq!(Value::Char(value), field_type.read_value(&mut (&buf as &[u8]))?);
+            }
+
+            // test default
+            let mut buf = [1u8; 4];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Default)?;
+            assert_eq!([0u8; 4], buf);
+            Ok(())
+        }
+
+        #[test]
+        fn char_write_value_with_other_types() {
+            let mut buf = [0u8; 4];
+            match FieldType::Char.write_value(&mut (&mut buf as &mut [u8]), &Value::Str("a".to_string())) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Char", e.to_string())
+            };
+        }
+
+        #[test]
+        fn char_parse_value() -> Result<()> {
+            assert_eq!(Value::Char('\u{e9}'), FieldType::Char.parse_value("\u{e9}")?);
+            assert_eq!(Value::Char('\u{1f980}'), FieldType::Char.parse_value("\u{1f980}")?);
+            Ok(())
+        }
+
+        #[test]
+        fn char_parse_value_with_invalid_length() {
+            match FieldType::Char.parse_value("ab") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("char value must be a single character but got \"ab\"", e.to_string())
+            };
+            assert!(FieldType::Char.parse_value("").is_err());
+        }
+
+        #[test]
+        fn duration_write_value() -> Result<()> {
+            let field_type = FieldType::Duration;
+            for (value, expected) in [
+                (Duration::from_nanos(1), [0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 1u8]),
+                (Duration::from_millis(1500), [0u8, 0u8, 0u8, 0u8, 89u8, 104u8, 47u8, 0u8]),
+                (Duration::from_nanos(i64::MAX as u64), [127u8, 255u8, 255u8, 255u8, 255u8, 255u8, 255u8, 255u8])
+            ] {
+                let mut buf = [0u8; 8];
+                field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Duration(value))?;
+                assert_eq!(expected, buf);
+
+                // read it back
+                assert_eq!(Value::Duration(value), field_type.read_value(&mut (&buf as &[u8]))?);
+            }
+
+            // test default
+            let mut buf = [1u8; 8];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Default)?;
+            assert_eq!([0u8; 8], buf);
+            Ok(())
+        }
+
+        #[test]
+        fn duration_write_value_overflow() {
+            let mut buf = [0u8; 8];
+            let duration = Duration::from_nanos(i64::MAX as u64 + 1);
+            let value = Value::Duration(duration);
+            assert!(!FieldType::Duration.is_valid(&value));
+            let expected = format!("duration {:?} is too big to be stored as i64 nanoseconds", duration);
+            match FieldType::Duration.write_value(&mut (&mut buf as &mut [u8]), &value) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+        }
+
+        #[test]
+        fn duration_read_value_negative() {
+            let buf = (-1i64).to_be_bytes();
+            match FieldType::Duration.read_value(&mut (&buf as &[u8])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("invalid byte slice value", e.to_string())
+            }
+        }
+
+        #[test]
+        fn duration_parse_value() -> Result<()> {
+            for (s, expected) in [
+                ("1500ms", Duration::from_millis(1500)),
+                ("1.5s", Duration::from_millis(1500)),
+                ("250", Duration::from_nanos(250)),
+                ("250ns", Duration::from_nanos(250)),
+                ("3us", Duration::from_micros(3)),
+                ("3\u{b5}s", Duration::from_micros(3)),
+                ("2m", Duration::from_secs(120)),
+                (" 1h ", Duration::from_secs(3600))
+            ] {
+                assert_eq!(Value::Duration(expected), FieldType::Duration.parse_value(s)?);
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn duration_parse_value_invalid() {
+            match FieldType::Duration.parse_value("10days") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("invalid duration \"10days\": unknown unit \"days\"", e.to_string())
+            };
+            match FieldType::Duration.parse_value("9999999h") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("invalid duration \"9999999h\": value out of range", e.to_string())
+            };
+            assert!(FieldType::Duration.parse_value("ms").is_err());
+            assert!(FieldType::Duration.parse_value("-5s").is_err());
+        }
+
+        #[test]
+        fn byte_sized() {
+            assert_eq!(5, FieldType::BYTES);
+        }
+
+        #[test]
+        fn bool_read_from() {
+            let mut reader = &[1u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::Bool;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i8_read_from() {
+            let mut reader = &[2u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::I8;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i16_read_from() {
+            let mut reader = &[3u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::I16;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i32_read_from() {
+            let mut reader = &[4u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::I32;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i64_read_from() {
             let mut reader = &[5u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
             let expected = FieldType::I64;
             match FieldType::read_from(&mut reader) {
@@ -2161,239 +4490,1106 @@ mod tests {
         }
 
         #[test]
-        fn u8_read_from() {
-            let mut reader = &[6u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::U8;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn u8_read_from() {
+            let mut reader = &[6u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::U8;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn u16_read_from() {
+            let mut reader = &[7u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::U16;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn u32_read_from() {
+            let mut reader = &[8u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::U32;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn u64_read_from() {
+            let mut reader = &[9u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::U64;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn f32_read_from() {
+            let mut reader = &[10u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::F32;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn f64_read_from() {
+            let mut reader = &[11u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::F64;
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn str_read_from_with_size() {
+            let mut reader = &[12u8, 43u8, 23u8, 65u8, 86u8] as &[u8];
+            let expected = FieldType::Str(722944342);
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn str_read_from_with_zero_size() {
+            let mut reader = &[12u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            let expected = FieldType::Str(0);
+            match FieldType::read_from(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn bool_write_to() {
+            let field_type = FieldType::Bool;
+            let expected = [1u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i8_write_to() {
+            let field_type = FieldType::I8;
+            let expected = [2u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i16_write_to() {
+            let field_type = FieldType::I16;
+            let expected = [3u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i32_write_to() {
+            let field_type = FieldType::I32;
+            let expected = [4u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn i64_write_to() {
+            let field_type = FieldType::I64;
+            let expected = [5u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn u8_write_to() {
+            let field_type = FieldType::U8;
+            let expected = [6u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn u16_write_to() {
+            let field_type = FieldType::U16;
+            let expected = [7u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn u32_write_to() {
+            let field_type = FieldType::U32;
+            let expected = [8u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn u64_write_to() {
+            let field_type = FieldType::U64;
+            let expected = [9u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn f32_write_to() {
+            let field_type = FieldType::F32;
+            let expected = [10u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn f64_write_to() {
+            let field_type = FieldType::F64;
+            let expected = [11u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn str_write_to_with_size() {
+            let field_type = FieldType::Str(234655354);
+            let expected = [12u8, 13u8, 252u8, 142u8, 122u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        #[test]
+        fn ip_addr_read_from() -> Result<()> {
+            let mut reader = &[13u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            assert_eq!(FieldType::IpAddr, FieldType::read_from(&mut reader)?);
+            Ok(())
+        }
+
+        #[test]
+        fn ip_addr_write_to() -> Result<()> {
+            let expected = [13u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            FieldType::IpAddr.write_to(&mut (&mut buf as &mut [u8]))?;
+            assert_eq!(expected, buf);
+            Ok(())
+        }
+
+        #[test]
+        fn char_read_from() -> Result<()> {
+            let mut reader = &[14u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            assert_eq!(FieldType::Char, FieldType::read_from(&mut reader)?);
+            Ok(())
+        }
+
+        #[test]
+        fn duration_read_from() -> Result<()> {
+            let mut reader = &[15u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
+            assert_eq!(FieldType::Duration, FieldType::read_from(&mut reader)?);
+            Ok(())
+        }
+
+        #[test]
+        fn duration_write_to() -> Result<()> {
+            let expected = [15u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            FieldType::Duration.write_to(&mut (&mut buf as &mut [u8]))?;
+            assert_eq!(expected, buf);
+            Ok(())
+        }
+
+        #[test]
+        fn char_write_to() -> Result<()> {
+            let expected = [14u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            FieldType::Char.write_to(&mut (&mut buf as &mut [u8]))?;
+            assert_eq!(expected, buf);
+            Ok(())
+        }
+
+        #[test]
+        fn str_write_to_with_zero_size() {
+            let field_type = FieldType::Str(0);
+            let expected = [12u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = [0u8; 5];
+            let mut writer = &mut buf as &mut [u8];
+            match field_type.write_to(&mut writer) {
+                Ok(()) => assert_eq!(expected, buf),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            };
+        }
+
+        /// Builds an enum8 field type from variant names.
+        /// 
+        /// # Arguments
+        /// 
+        /// * `variants` - Variant names.
+        fn enum8(variants: &[&str]) -> FieldType {
+            FieldType::Enum8(variants.iter().map(|v| v.to_string()).collect())
+        }
+
+        #[test]
+        fn enum8_read_write_value() -> Result<()> {
+            let field_type = enum8(&["new", "active", "closed"]);
+            assert_eq!(1, field_type.value_byte_size());
+
+            // test encode
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &Value::Str("closed".to_string()))?;
+            field_type.write_value(&mut buf, &Value::Default)?;
+            field_type.write_value(&mut buf, &Value::Str("active".to_string()))?;
+            assert_eq!(vec![2u8, 0, 1], buf);
+
+            // test decode
+            let mut reader = &buf[..];
+            assert_eq!(Value::Str("closed".to_string()), field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Str("new".to_string()), field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Str("active".to_string()), field_type.read_value(&mut reader)?);
+
+            // test out of range index
+            match field_type.read_value(&mut &[3u8][..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("enum8 value index 3 is out of range", e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn enum8_with_invalid_values() {
+            let field_type = enum8(&["new", "active"]);
+            assert!(field_type.is_valid(&Value::Str("new".to_string())));
+            assert!(!field_type.is_valid(&Value::Str("old".to_string())));
+            assert!(!field_type.is_valid(&Value::U8(0)));
+
+            let mut buf = Vec::new();
+            match field_type.write_value(&mut buf, &Value::Str("old".to_string())) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("\"old\" is not a valid enum8 variant", e.to_string())
+            }
+            match field_type.write_value(&mut buf, &Value::U8(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Str", e.to_string())
+            }
+            match field_type.parse_value("old") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("\"old\" is not a valid enum8 variant", e.to_string())
+            }
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn enum8_variant_limit() -> Result<()> {
+            let names: Vec<String> = (0..257).map(|v| format!("v{}", v)).collect();
+            let refs: Vec<&str> = names.iter().map(|v| v.as_str()).collect();
+            enum8(&refs[..256]).validate()?;
+            match Field::new("status", enum8(&refs)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("enum8 field type can't have more than 256 variants but got 257", e.to_string())
+            }
+            match Field::new("status", enum8(&["a", "b", "a"])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("duplicated enum8 variant \"a\"", e.to_string())
+            }
+            match Field::new("status", enum8(&[])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("enum8 field type requires at least one variant", e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn enum8_write_read_header() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U32)?;
+            header.add_nullable("status", enum8(&["new", "active"]))?;
+            header.add("name", FieldType::Str(5))?;
+
+            // test header bytes
+            let mut buf = Vec::new();
+            header.write_to(&mut buf)?;
+            assert_eq!(4 + 59 * 3 + (4 + 3) + (4 + 6), buf.len() as u64);
+            assert_eq!(buf.len() as u64, header.size_as_bytes());
+            assert_eq!(header, Header::read_from(&mut &buf[..])?);
+
+            // test descriptor
+            let descriptor = header.to_descriptor();
+            assert_eq!(Some(vec!["new".to_string(), "active".to_string()]), descriptor.fields[1].variants);
+            assert_eq!(header, Header::from_descriptor(&descriptor)?);
+
+            // test record
+            let mut record = header.new_record()?;
+            record.set("status", Value::Str("active".to_string()))?;
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+            assert_eq!(header.record_byte_size(), buf.len() as u64);
+            let read = header.read_record(&mut &buf[..])?;
+            assert_eq!(Some(&Value::Str("active".to_string())), read.get("status"));
+            Ok(())
+        }
+
+        #[test]
+        fn date_write_value() -> Result<()> {
+            let field_type = FieldType::Date;
+            for (value, expected) in [
+                (19782i32, [0u8, 0u8, 77u8, 70u8]),
+                (-1i32, [255u8, 255u8, 255u8, 255u8]),
+                (i32::MAX, [127u8, 255u8, 255u8, 255u8])
+            ] {
+                let mut buf = [0u8; 4];
+                field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Date(value))?;
+                assert_eq!(expected, buf);
+
+                // read it back
+                assert_eq!(Value::Date(value), field_type.read_value(&mut (&buf as &[u8]))?);
+            }
+
+            // test default
+            let mut buf = [1u8; 4];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Default)?;
+            assert_eq!([0u8; 4], buf);
+            Ok(())
+        }
+
+        #[test]
+        fn timestamp_write_value() -> Result<()> {
+            let field_type = FieldType::Timestamp;
+            for (value, expected) in [
+                (1709214330250i64, [0u8, 0u8, 1u8, 141u8, 245u8, 28u8, 237u8, 138u8]),
+                (-1i64, [255u8; 8]),
+                (i64::MIN, [128u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8])
+            ] {
+                let mut buf = [0u8; 8];
+                field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Timestamp(value))?;
+                assert_eq!(expected, buf);
+
+                // read it back
+                assert_eq!(Value::Timestamp(value), field_type.read_value(&mut (&buf as &[u8]))?);
+            }
+
+            // test default
+            let mut buf = [1u8; 8];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Default)?;
+            assert_eq!([0u8; 8], buf);
+            Ok(())
+        }
+
+        #[test]
+        fn date_timestamp_is_valid() {
+            let values = [
+                Value::Date(0),
+                Value::Timestamp(0),
+                Value::I32(0),
+                Value::I64(0),
+                Value::Duration(Duration::from_millis(0)),
+                Value::Str("2024-02-29".to_string())
+            ];
+            for (field_type, expected) in [
+                (FieldType::Date, [true, false, false, false, false, false]),
+                (FieldType::Timestamp, [false, true, false, false, false, false]),
+                (FieldType::I32, [false, false, true, false, false, false]),
+                (FieldType::I64, [false, false, false, true, false, false]),
+                (FieldType::Duration, [false, false, false, false, true, false])
+            ] {
+                for (value, expected) in values.iter().zip(expected) {
+                    assert_eq!(expected, field_type.is_valid(value), "{:?} with {:?}", field_type, value);
+                }
+            }
+        }
+
+        #[test]
+        fn date_timestamp_with_other_types() {
+            let mut buf = [0u8; 8];
+            match FieldType::Date.write_value(&mut (&mut buf as &mut [u8]), &Value::I32(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Date", e.to_string())
+            }
+            match FieldType::Timestamp.write_value(&mut (&mut buf as &mut [u8]), &Value::Date(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Timestamp", e.to_string())
+            }
+        }
+
+        #[test]
+        fn date_parse_value() -> Result<()> {
+            assert_eq!(Value::Date(19782), FieldType::Date.parse_value("2024-02-29")?);
+            assert_eq!(Value::Date(0), FieldType::Date.parse_value("1970-01-01")?);
+            assert_eq!(Value::Date(-1), FieldType::Date.parse_value("1969-12-31")?);
+            match FieldType::Date.parse_value("2023-02-29") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("invalid date \"2023-02-29\": input is out of range", e.to_string())
+            }
+            assert!(FieldType::Date.parse_value("29/02/2024").is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn timestamp_parse_value() -> Result<()> {
+            let expected = Value::Timestamp(1709214330250);
+            assert_eq!(expected, FieldType::Timestamp.parse_value("2024-02-29T13:45:30.250Z")?);
+            assert_eq!(expected, FieldType::Timestamp.parse_value("2024-02-29T15:45:30.250+02:00")?);
+            assert_eq!(expected, FieldType::Timestamp.parse_value("1709214330250")?);
+            assert_eq!(Value::Timestamp(-1), FieldType::Timestamp.parse_value("-1")?);
+            match FieldType::Timestamp.parse_value("2024-02-29") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("invalid timestamp \"2024-02-29\": premature end of input", e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn date_timestamp_read_write_to() -> Result<()> {
+            for (field_type, type_byte) in [(FieldType::Date, 17u8), (FieldType::Timestamp, 18u8)] {
+                let expected = [type_byte, 0u8, 0u8, 0u8, 0u8];
+                let mut buf = [0u8; 5];
+                field_type.write_to(&mut (&mut buf as &mut [u8]))?;
+                assert_eq!(expected, buf);
+                assert_eq!(field_type, FieldType::read_from(&mut &buf[..])?);
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn date_timestamp_type_name() -> Result<()> {
+            for field_type in [FieldType::Date, FieldType::Timestamp] {
+                assert_eq!(field_type, FieldType::from_type_name(field_type.type_name(), None)?);
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn bytes_read_value_partial() -> Result<()> {
+            let mut reader = &[
+                // value size as 3u32
+                0u8, 0u8, 0u8, 3u8,
+                // bytes value, not valid UTF-8
+                255u8, 0u8, 192u8, 0u8, 0u8,
+                // extra bytes, this shouldn't be read
+                10u8, 20u8, 33u8
+            ] as &[u8];
+            assert_eq!(Value::Bytes(vec![255u8, 0u8, 192u8]), FieldType::Bytes(5).read_value(&mut reader)?);
+
+            // check final reader position
+            let mut buf = [0u8; 3];
+            reader.read_exact(&mut buf)?;
+            assert_eq!([10u8, 20u8, 33u8], buf);
+
+            // value size bigger than the field size
+            let mut reader = &[0u8, 0u8, 0u8, 6u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8] as &[u8];
+            match FieldType::Bytes(5).read_value(&mut reader) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("bytes value size can't be bigger than the field size", e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn bytes_write_value() -> Result<()> {
+            let field_type = FieldType::Bytes(5);
+            let mut buf = [1u8; 9];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Bytes(vec![255u8, 0u8, 192u8]))?;
+            assert_eq!([0u8, 0u8, 0u8, 3u8, 255u8, 0u8, 192u8, 0u8, 0u8], buf);
+
+            // test default
+            let mut buf = [1u8; 9];
+            field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Default)?;
+            assert_eq!([0u8; 9], buf);
+            Ok(())
+        }
+
+        #[test]
+        fn bytes_write_value_invalid_value_size() {
+            let field_type = FieldType::Bytes(2);
+            let expected = "bytes value size (3 bytes) is bigger than field size (2 bytes)";
+            let mut buf = [0u8; 6];
+            match field_type.write_value(&mut (&mut buf as &mut [u8]), &Value::Bytes(vec![1u8, 2u8, 3u8])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            };
+            match field_type.validate_value(&Value::Bytes(vec![1u8, 2u8, 3u8])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("bytes value (3 bytes) exceeds field size (2 bytes)", e.to_string())
+            };
+        }
+
+        #[test]
+        fn bytes_is_valid() {
+            let field_type = FieldType::Bytes(2);
+            assert!(field_type.is_valid(&Value::Bytes(vec![])));
+            assert!(field_type.is_valid(&Value::Bytes(vec![1u8, 2u8])));
+            assert!(!field_type.is_valid(&Value::Bytes(vec![1u8, 2u8, 3u8])));
+            assert!(!field_type.is_valid(&Value::Str("ab".to_string())));
+            assert!(!FieldType::Str(2).is_valid(&Value::Bytes(vec![1u8])));
+        }
+
+        #[test]
+        fn bytes_parse_value() -> Result<()> {
+            let field_type = FieldType::Bytes(3);
+            assert_eq!(Value::Bytes(vec![0u8, 171u8, 255u8]), field_type.parse_value("00abFF")?);
+            assert_eq!(Value::Bytes(vec![]), field_type.parse_value("")?);
+            for (s, expected) in [
+                ("abc", "invalid hex value \"abc\": odd number of digits"),
+                ("zz", "invalid hex value \"zz\": invalid digit"),
+                ("00112233", "bytes value size (4 bytes) is bigger than field size (3 bytes)")
+            ] {
+                match field_type.parse_value(s) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn bytes_read_write_to() -> Result<()> {
+            let field_type = FieldType::Bytes(300);
+            let expected = [19u8, 0u8, 0u8, 1u8, 44u8];
+            let mut buf = [0u8; 5];
+            field_type.write_to(&mut (&mut buf as &mut [u8]))?;
+            assert_eq!(expected, buf);
+            assert_eq!(field_type, FieldType::read_from(&mut &buf[..])?);
+
+            // test type name
+            assert_eq!(field_type, FieldType::from_type_name(field_type.type_name(), Some(300))?);
+            assert!(FieldType::from_type_name("bytes", None).is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn decimal_read_write_value() -> Result<()> {
+            let field_type = FieldType::Decimal{scale: 2};
+            assert_eq!(9, field_type.value_byte_size());
+            let value = Value::Decimal(12345, 2);
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &value)?;
+            assert_eq!(vec![0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 48u8, 57u8, 2u8], buf);
+            assert_eq!(value, field_type.read_value(&mut (&buf as &[u8]))?);
+
+            // test default
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &Value::Default)?;
+            assert_eq!(vec![0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 2u8], buf);
+
+            // scale mismatch
+            let expected = "decimal value scale 3 doesn't match the field scale 2";
+            match field_type.write_value(&mut Vec::new(), &Value::Decimal(12345, 3)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            let buf = [0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 48u8, 57u8, 3u8];
+            match field_type.read_value(&mut (&buf as &[u8])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            Ok(())
         }
 
         #[test]
-        fn u16_read_from() {
-            let mut reader = &[7u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::U16;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn decimal_is_valid() {
+            let field_type = FieldType::Decimal{scale: 2};
+            assert!(field_type.is_valid(&Value::Decimal(12345, 2)));
+            assert!(!field_type.is_valid(&Value::Decimal(12345, 3)));
+            assert!(!field_type.is_valid(&Value::I64(12345)));
+            assert!(!FieldType::I64.is_valid(&Value::Decimal(12345, 2)));
+            assert!(FieldType::Decimal{scale: 18}.validate().is_ok());
+            match (FieldType::Decimal{scale: 19}).validate() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("decimal scale must be <= 18 but got 19", e.to_string())
+            }
         }
 
         #[test]
-        fn u32_read_from() {
-            let mut reader = &[8u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::U32;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn decimal_parse_value() -> Result<()> {
+            let field_type = FieldType::Decimal{scale: 2};
+            assert_eq!(Value::Decimal(12345, 2), field_type.parse_value("123.45")?);
+            assert_eq!(Value::Decimal(12340, 2), field_type.parse_value("123.4")?);
+            assert_eq!(Value::Decimal(12300, 2), field_type.parse_value("123")?);
+            assert_eq!(Value::Decimal(-5, 2), field_type.parse_value("-0.05")?);
+            assert_eq!(Value::Decimal(50, 2), field_type.parse_value(".5")?);
+            for (s, expected) in [
+                ("1.234", "invalid decimal \"1.234\": more than 2 fractional digits"),
+                ("abc", "invalid decimal \"abc\""),
+                ("", "invalid decimal \"\""),
+                ("1.2.3", "invalid decimal \"1.2.3\""),
+                ("99999999999999999999", "invalid decimal \"99999999999999999999\": value out of range")
+            ] {
+                match field_type.parse_value(s) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+            Ok(())
         }
 
         #[test]
-        fn u64_read_from() {
-            let mut reader = &[9u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::U64;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn decimal_read_write_to() -> Result<()> {
+            let field_type = FieldType::Decimal{scale: 2};
+            let expected = [20u8, 0u8, 0u8, 0u8, 2u8];
+            let mut buf = [0u8; 5];
+            field_type.write_to(&mut (&mut buf as &mut [u8]))?;
+            assert_eq!(expected, buf);
+            assert_eq!(field_type, FieldType::read_from(&mut &buf[..])?);
+            assert!(FieldType::read_from(&mut &[20u8, 0u8, 0u8, 1u8, 0u8][..]).is_err());
+
+            // test type name
+            assert_eq!("decimal", field_type.type_name());
+            assert!(FieldType::from_type_name("decimal", None).is_err());
+            Ok(())
+        }
+
+        /// Builds an enum field type.
+        /// 
+        /// # Arguments
+        /// 
+        /// * `variants` - Variant names.
+        fn enum_type(variants: &[&str]) -> FieldType {
+            FieldType::Enum{variants: variants.iter().map(|v| v.to_string()).collect()}
         }
 
         #[test]
-        fn f32_read_from() {
-            let mut reader = &[10u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::F32;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn enum_label() {
+            let field_type = enum_type(&["active", "paused", "closed"]);
+            assert_eq!(Some("active"), field_type.enum_label(&Value::Enum(0)));
+            assert_eq!(Some("closed"), field_type.enum_label(&Value::Enum(2)));
+            assert_eq!(None, field_type.enum_label(&Value::Enum(3)));
+            assert_eq!(None, field_type.enum_label(&Value::U16(0)));
+            assert_eq!(None, enum8(&["active"]).enum_label(&Value::Enum(0)));
         }
 
         #[test]
-        fn f64_read_from() {
-            let mut reader = &[11u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::F64;
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn enum_read_write_value() -> Result<()> {
+            let field_type = enum_type(&["active", "paused", "closed"]);
+            assert_eq!(2, field_type.value_byte_size());
+
+            // test encode
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &Value::Enum(2))?;
+            field_type.write_value(&mut buf, &Value::Default)?;
+            field_type.write_value(&mut buf, &Value::Enum(1))?;
+            assert_eq!(vec![0u8, 2, 0, 0, 0, 1], buf);
+
+            // test decode
+            let mut reader = &buf[..];
+            assert_eq!(Value::Enum(2), field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Enum(0), field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Enum(1), field_type.read_value(&mut reader)?);
+
+            // test parse
+            assert_eq!(Value::Enum(1), field_type.parse_value("paused")?);
+            match field_type.parse_value("open") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("\"open\" is not a valid enum variant", e.to_string())
+            }
+            Ok(())
         }
 
         #[test]
-        fn str_read_from_with_size() {
-            let mut reader = &[12u8, 43u8, 23u8, 65u8, 86u8] as &[u8];
-            let expected = FieldType::Str(722944342);
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn enum_with_out_of_range_values() {
+            let field_type = enum_type(&["active", "paused"]);
+            assert!(field_type.is_valid(&Value::Enum(1)));
+            assert!(!field_type.is_valid(&Value::Enum(2)));
+            assert!(!field_type.is_valid(&Value::U16(0)));
+            assert!(!field_type.is_valid(&Value::Str("active".to_string())));
+
+            let mut buf = Vec::new();
+            match field_type.write_value(&mut buf, &Value::Enum(2)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("enum value index 2 is out of range", e.to_string())
+            }
+            match field_type.write_value(&mut buf, &Value::U16(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Enum", e.to_string())
+            }
+            assert!(buf.is_empty());
+            match field_type.read_value(&mut &[0u8, 2][..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("enum value index 2 is out of range", e.to_string())
+            }
         }
 
         #[test]
-        fn str_read_from_with_zero_size() {
-            let mut reader = &[12u8, 0u8, 0u8, 0u8, 0u8] as &[u8];
-            let expected = FieldType::Str(0);
-            match FieldType::read_from(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn enum_validate() -> Result<()> {
+            let names: Vec<String> = (0..300).map(|v| format!("v{}", v)).collect();
+            let refs: Vec<&str> = names.iter().map(|v| v.as_str()).collect();
+            enum_type(&refs).validate()?;
+            match enum_type(&["a", "b", "a"]).validate() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("duplicated enum variant \"a\"", e.to_string())
+            }
+            match enum_type(&[]).validate() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("enum field type requires at least one variant", e.to_string())
+            }
+            Ok(())
         }
 
         #[test]
-        fn bool_write_to() {
-            let field_type = FieldType::Bool;
-            let expected = [1u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn enum_write_read_header() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U32)?;
+            header.add("status", enum_type(&["active", "paused", "closed"]))?;
+
+            // test header bytes
+            let mut buf = Vec::new();
+            header.write_to(&mut buf)?;
+            assert_eq!(21u8, buf[4 + 59 * 2 - 5]);
+            assert_eq!(4 + 59 * 2 + (4 + 6) + (4 + 6) + (4 + 6), buf.len() as u64);
+            assert_eq!(buf.len() as u64, header.size_as_bytes());
+            assert_eq!(header, Header::read_from(&mut &buf[..])?);
+
+            // test descriptor
+            let descriptor = header.to_descriptor();
+            assert_eq!("enum", descriptor.fields[1].type_name);
+            assert_eq!(header, Header::from_descriptor(&descriptor)?);
+            assert!(FieldType::from_type_name("enum", None).is_err());
+
+            // test record
+            let mut record = header.new_record()?;
+            record.set("status", Value::Enum(2))?;
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+            assert_eq!(header.record_byte_size(), buf.len() as u64);
+            let read = header.read_record(&mut &buf[..])?;
+            assert_eq!(Some(&Value::Enum(2)), read.get("status"));
+            Ok(())
         }
 
         #[test]
-        fn i8_write_to() {
-            let field_type = FieldType::I8;
-            let expected = [2u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn uuid_read_write_value() -> Result<()> {
+            let field_type = FieldType::Uuid;
+            assert_eq!(16, field_type.value_byte_size());
+            let value = Value::parse_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
+            assert!(field_type.is_valid(&value));
+            assert!(!field_type.is_valid(&Value::Str("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())));
+
+            // test encode
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &value)?;
+            field_type.write_value(&mut buf, &Value::Default)?;
+            let expected = [
+                0x67u8, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f,
+                0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8
+            ];
+            assert_eq!(expected, buf[..16]);
+            assert_eq!([0u8; 16], buf[16..]);
+
+            // test decode
+            let mut reader = &buf[..];
+            assert_eq!(value, field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Uuid([0u8; 16]), field_type.read_value(&mut reader)?);
+            match field_type.write_value(&mut Vec::new(), &Value::Str("abc".to_string())) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Uuid", e.to_string())
+            }
+
+            // test parse
+            assert_eq!(value, field_type.parse_value("67E55044-10B1-426F-9247-BB680E5FE0C8")?);
+            Ok(())
         }
 
         #[test]
-        fn i16_write_to() {
-            let field_type = FieldType::I16;
-            let expected = [3u8, 0u8, 0u8, 0u8, 0u8];
+        fn uuid_read_write_to() -> Result<()> {
+            let field_type = FieldType::Uuid;
+            let expected = [22u8, 0u8, 0u8, 0u8, 0u8];
             let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+            field_type.write_to(&mut (&mut buf as &mut [u8]))?;
+            assert_eq!(expected, buf);
+            assert_eq!(field_type, FieldType::read_from(&mut &buf[..])?);
+
+            // test type name
+            assert_eq!("uuid", field_type.type_name());
+            assert_eq!(field_type, FieldType::from_type_name("uuid", None)?);
+            Ok(())
         }
 
         #[test]
-        fn i32_write_to() {
-            let field_type = FieldType::I32;
-            let expected = [4u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn json_read_write_value() -> Result<()> {
+            let field_type = FieldType::Json(30);
+            assert_eq!(34, field_type.value_byte_size());
+            let value = Value::Json(serde_json::json!({"a": 1, "b": [true, null]}));
+            assert!(field_type.is_valid(&value));
+            assert!(field_type.is_valid(&Value::Str("[1, 2]".to_string())));
+            assert!(!field_type.is_valid(&Value::Str("{\"a\":".to_string())));
+
+            // test encode
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &value)?;
+            field_type.write_value(&mut buf, &Value::Default)?;
+            let text = b"{\"a\":1,\"b\":[true,null]}";
+            assert_eq!([0u8, 0u8, 0u8, 23u8], buf[..4]);
+            assert_eq!(text, &buf[4..27]);
+            assert_eq!([0u8; 7], buf[27..34]);
+            assert_eq!([0u8; 34], buf[34..]);
+
+            // test decode
+            let mut reader = &buf[..];
+            assert_eq!(value, field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Json(JSValue::Null), field_type.read_value(&mut reader)?);
+
+            // test parse
+            assert_eq!(value, field_type.parse_value("{\"b\": [true, null], \"a\": 1}")?);
+            Ok(())
         }
 
         #[test]
-        fn i64_write_to() {
-            let field_type = FieldType::I64;
-            let expected = [5u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn json_write_value_with_invalid_json() {
+            let field_type = FieldType::Json(30);
+            match field_type.write_value(&mut Vec::new(), &Value::Str("{\"a\":".to_string())) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "invalid JSON value: EOF while parsing a value at line 1 column 5",
+                    e.to_string()
+                )
+            }
+            match field_type.write_value(&mut Vec::new(), &Value::I32(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Json", e.to_string())
+            }
+
+            // test read of malformed stored JSON
+            let mut buf = vec![0u8, 0u8, 0u8, 2u8, b'{', b'x'];
+            buf.extend_from_slice(&[0u8; 28]);
+            match field_type.read_value(&mut &buf[..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "invalid JSON value: key must be a string at line 1 column 2",
+                    e.to_string()
+                )
+            }
         }
 
         #[test]
-        fn u8_write_to() {
-            let field_type = FieldType::U8;
-            let expected = [6u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn json_write_value_with_oversized_value() {
+            let field_type = FieldType::Json(5);
+            let value = Value::Json(serde_json::json!({"abc": 1}));
+            assert!(!field_type.is_valid(&value));
+            match field_type.write_value(&mut Vec::new(), &value) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "JSON value size (9 bytes) is bigger than field size (5 bytes)",
+                    e.to_string()
+                )
+            }
+            match field_type.validate_value(&value) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "JSON value size (9 bytes) is bigger than field size (5 bytes)",
+                    e.to_string()
+                )
+            }
         }
 
         #[test]
-        fn u16_write_to() {
-            let field_type = FieldType::U16;
-            let expected = [7u8, 0u8, 0u8, 0u8, 0u8];
+        fn json_read_write_to() -> Result<()> {
+            let field_type = FieldType::Json(300);
+            let expected = [23u8, 0u8, 0u8, 1u8, 44u8];
             let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+            field_type.write_to(&mut (&mut buf as &mut [u8]))?;
+            assert_eq!(expected, buf);
+            assert_eq!(field_type, FieldType::read_from(&mut &buf[..])?);
+
+            // test type name
+            assert_eq!("json", field_type.type_name());
+            assert_eq!(field_type, FieldType::from_type_name("json", Some(300))?);
+            match FieldType::Json(0).validate_definition() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("json field capacity can't be zero", e.to_string())
+            }
+            Ok(())
         }
 
         #[test]
-        fn u32_write_to() {
-            let field_type = FieldType::U32;
-            let expected = [8u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn array_read_write_value() -> Result<()> {
+            let field_type = FieldType::Array{element: Box::new(FieldType::U8), len: 3};
+            assert_eq!(3, field_type.value_byte_size());
+            let value = Value::Array(vec![Value::U8(255), Value::U8(128), Value::U8(0)]);
+            assert!(field_type.is_valid(&value));
+
+            // test encode
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &value)?;
+            field_type.write_value(&mut buf, &Value::Default)?;
+            assert_eq!([255u8, 128u8, 0u8, 0u8, 0u8, 0u8], buf[..]);
+
+            // test decode
+            let mut reader = &buf[..];
+            assert_eq!(value, field_type.read_value(&mut reader)?);
+            assert_eq!(
+                Value::Array(vec![Value::U8(0), Value::U8(0), Value::U8(0)]),
+                field_type.read_value(&mut reader)?
+            );
+
+            // test parse
+            assert_eq!(value, field_type.parse_value("[255, 128, 0]")?);
+            Ok(())
         }
 
         #[test]
-        fn u64_write_to() {
-            let field_type = FieldType::U64;
-            let expected = [9u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn array_with_wrong_length() {
+            let field_type = FieldType::Array{element: Box::new(FieldType::U8), len: 3};
+            let short = Value::Array(vec![Value::U8(1), Value::U8(2)]);
+            let long = Value::Array(vec![Value::U8(1), Value::U8(2), Value::U8(3), Value::U8(4)]);
+            assert!(!field_type.is_valid(&short));
+            assert!(!field_type.is_valid(&long));
+            assert!(!field_type.is_valid(&Value::Array(vec![Value::U8(1), Value::U16(2), Value::U8(3)])));
+            match field_type.write_value(&mut Vec::new(), &short) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("array value has 2 elements but the field type requires 3", e.to_string())
+            }
+            match field_type.validate_value(&long) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("array value has 4 elements but the field type requires 3", e.to_string())
+            }
+            match field_type.validate_value(&Value::Array(vec![Value::U8(1), Value::U16(2), Value::U8(3)])) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("array element 1: value U16(2) doesn't match the field type U8", e.to_string())
+            }
+            match field_type.write_value(&mut Vec::new(), &Value::U8(1)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("value must be a Value::Array", e.to_string())
+            }
         }
 
         #[test]
-        fn f32_write_to() {
-            let field_type = FieldType::F32;
-            let expected = [10u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn array_read_write_to() -> Result<()> {
+            let field_type = FieldType::Array{element: Box::new(FieldType::U16), len: 4};
+            let expected = [24u8, 0u8, 0u8, 0u8, 4u8, 7u8, 0u8, 0u8, 0u8, 0u8];
+            let mut buf = Vec::new();
+            field_type.write_to(&mut buf)?;
+            assert_eq!(expected, buf[..]);
+            assert_eq!(5, field_type.variants_byte_size());
+            assert_eq!(field_type, FieldType::read_from(&mut &buf[..])?);
+
+            // test type name
+            assert_eq!("array", field_type.type_name());
+            match FieldType::from_type_name("array", None) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("array field type requires an element type and length", e.to_string())
+            }
+            Ok(())
         }
 
         #[test]
-        fn f64_write_to() {
-            let field_type = FieldType::F64;
-            let expected = [11u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn array_with_invalid_definition() {
+            let tests = [
+                (FieldType::Array{element: Box::new(FieldType::U8), len: 0}, "array field type requires at least one element"),
+                (FieldType::Array{element: Box::new(FieldType::Str(5)), len: 2}, "array element type can't be str"),
+                (FieldType::Array{element: Box::new(FieldType::Bytes(5)), len: 2}, "array element type can't be bytes"),
+                (FieldType::Array{element: Box::new(FieldType::Json(5)), len: 2}, "array element type can't be json"),
+                (FieldType::Array{element: Box::new(FieldType::StrVar), len: 2}, "array element type can't be strvar"),
+                (
+                    FieldType::Array{
+                        element: Box::new(FieldType::Array{element: Box::new(FieldType::U8), len: 2}),
+                        len: 2
+                    },
+                    "array element type can't be array"
+                )
+            ];
+            for (field_type, expected) in tests {
+                match field_type.validate() {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+
+            // nested arrays are rejected on read
+            let buf = [24u8, 0u8, 0u8, 0u8, 2u8, 24u8, 0u8, 0u8, 0u8, 2u8, 6u8, 0u8, 0u8, 0u8, 0u8];
+            match FieldType::read_from(&mut &buf[..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(ParseError::InvalidValue.to_string(), e.to_string())
+            }
         }
 
         #[test]
-        fn str_write_to_with_size() {
-            let field_type = FieldType::Str(234655354);
-            let expected = [12u8, 13u8, 252u8, 142u8, 122u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn str_var_read_write_value() -> Result<()> {
+            let field_type = FieldType::StrVar;
+            assert_eq!(4, field_type.value_byte_size());
+            assert!(!field_type.is_fixed_length());
+            assert!(field_type.is_valid(&Value::Str("hello world".to_string())));
+            assert!(!field_type.is_valid(&Value::I32(1)));
+
+            // test encode
+            let mut buf = Vec::new();
+            field_type.write_value(&mut buf, &Value::Str("abc".to_string()))?;
+            field_type.write_value(&mut buf, &Value::Default)?;
+            field_type.write_value(&mut buf, &Value::Str("hello world".to_string()))?;
+            let mut expected = vec![0u8, 0u8, 0u8, 3u8, 97u8, 98u8, 99u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 11u8];
+            expected.extend_from_slice(b"hello world");
+            assert_eq!(expected, buf);
+
+            // test decode
+            let mut reader = &buf[..];
+            assert_eq!(Value::Str("abc".to_string()), field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Str("".to_string()), field_type.read_value(&mut reader)?);
+            assert_eq!(Value::Str("hello world".to_string()), field_type.read_value(&mut reader)?);
+            assert!(reader.is_empty());
+
+            // test parse
+            assert_eq!(Value::Str("abc".to_string()), field_type.parse_value("abc")?);
+            Ok(())
         }
 
         #[test]
-        fn str_write_to_with_zero_size() {
-            let field_type = FieldType::Str(0);
-            let expected = [12u8, 0u8, 0u8, 0u8, 0u8];
-            let mut buf = [0u8; 5];
-            let mut writer = &mut buf as &mut [u8];
-            match field_type.write_to(&mut writer) {
-                Ok(()) => assert_eq!(expected, buf),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
-            };
+        fn str_var_read_write_to() -> Result<()> {
+            let mut buf = Vec::new();
+            FieldType::StrVar.write_to(&mut buf)?;
+            assert_eq!([25u8, 0u8, 0u8, 0u8, 0u8], buf[..]);
+            assert_eq!(FieldType::StrVar, FieldType::read_from(&mut &buf[..])?);
+            assert_eq!(FieldType::StrVar, FieldType::from_type_name("strvar", None)?);
+            Ok(())
         }
     }
 
@@ -2409,7 +5605,8 @@ mod tests {
         fn new_field() {
             let expected = Field{
                 _name: "foo".to_string(),
-                _value_type: FieldType::I16
+                _value_type: FieldType::I16,
+                _nullable: false
             };
             match Field::new("foo", FieldType::I16) {
                 Ok(v) => assert_eq!(expected, v),
@@ -2453,7 +5650,8 @@ mod tests {
         fn read_from() {
             let expected = Field{
                 _name: "abcde".to_string(),
-                _value_type: FieldType::I8
+                _value_type: FieldType::I8,
+                _nullable: false
             };
             let buf: [u8; Field::BYTES] = [
                 // name value size
@@ -2487,7 +5685,8 @@ mod tests {
             ];
             let field = Field{
                 _name: "bar foo".to_string(),
-                _value_type: FieldType::Str(23)
+                _value_type: FieldType::Str(23),
+                _nullable: false
             };
             let mut buf = [0u8; Field::BYTES];
             let mut writer = &mut buf as &mut [u8];
@@ -2502,7 +5701,8 @@ mod tests {
             let expected = "field name size must be <= 50 bytes length";
             let field = Field{
                 _name: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string(),
-                _value_type: FieldType::Str(23)
+                _value_type: FieldType::Str(23),
+                _nullable: false
             };
             let mut buf = [0u8; Field::BYTES];
             let mut writer = &mut buf as &mut [u8];
@@ -2511,17 +5711,98 @@ mod tests {
                 Err(e) => assert_eq!(expected, e.to_string())
             }
         }
+
+        #[test]
+        fn nullable_i32_round_trip() -> Result<()> {
+            let field = Field::new_nullable("foo", FieldType::I32)?;
+            assert_eq!(5, field.value_byte_size());
+
+            // test null value
+            let mut buf = Vec::new();
+            field.write_value_with(&mut buf, &Value::Null, TrimMode::None)?;
+            assert_eq!(vec![0u8, 0, 0, 0, 0], buf);
+            assert_eq!(Value::Null, field.read_value(&mut &buf[..])?);
+
+            // test zero value
+            let mut buf = Vec::new();
+            field.write_value_with(&mut buf, &Value::I32(0), TrimMode::None)?;
+            assert_eq!(vec![1u8, 0, 0, 0, 0], buf);
+            assert_eq!(Value::I32(0), field.read_value(&mut &buf[..])?);
+            Ok(())
+        }
+
+        #[test]
+        fn nullable_str_round_trip() -> Result<()> {
+            let field = Field::new_nullable("bar", FieldType::Str(3))?;
+            assert_eq!(8, field.value_byte_size());
+
+            // test null value
+            let mut buf = Vec::new();
+            field.write_value_with(&mut buf, &Value::Null, TrimMode::None)?;
+            assert_eq!(vec![0u8, 0, 0, 0, 0, 0, 0, 0], buf);
+            assert_eq!(Value::Null, field.read_value(&mut &buf[..])?);
+
+            // test empty string value
+            let mut buf = Vec::new();
+            field.write_value_with(&mut buf, &Value::Str("".to_string()), TrimMode::None)?;
+            assert_eq!(vec![1u8, 0, 0, 0, 0, 0, 0, 0], buf);
+            assert_eq!(Value::Str("".to_string()), field.read_value(&mut &buf[..])?);
+
+            // test string value
+            let mut buf = Vec::new();
+            field.write_value_with(&mut buf, &Value::Str("ab".to_string()), TrimMode::None)?;
+            assert_eq!(vec![1u8, 0, 0, 0, 2, 97, 98, 0], buf);
+            assert_eq!(Value::Str("ab".to_string()), field.read_value(&mut &buf[..])?);
+            Ok(())
+        }
+
+        #[test]
+        fn null_on_non_nullable() -> Result<()> {
+            let field = Field::new("foo", FieldType::I32)?;
+            assert!(!field.is_valid(&Value::Null));
+            assert!(!FieldType::I32.is_valid(&Value::Null));
+            assert!(Field::new_nullable("foo", FieldType::I32)?.is_valid(&Value::Null));
+            let expected = "null value on a non-nullable field";
+            match field.validate_value_with(&Value::Null, TrimMode::None) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            let mut buf = Vec::new();
+            match field.write_value_with(&mut buf, &Value::Null, TrimMode::None) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn nullable_write_to_and_read_from() -> Result<()> {
+            let field = Field::new_nullable("foo", FieldType::Str(23))?;
+            let mut buf = Vec::new();
+            field.write_to(&mut buf)?;
+            assert_eq!(Field::BYTES, buf.len());
+            assert_eq!([0x80 | 12u8, 0, 0, 0, 23u8], buf[54..]);
+
+            // test
+            let read = Field::read_from(&mut &buf[..])?;
+            assert!(read.is_nullable());
+            assert_eq!(field, read);
+            Ok(())
+        }
     }
 
     mod header {
         use super::*;
+        use crate::test_helper::{with_tmpdir, create_file_with_bytes};
 
         #[test]
         fn new_header() {
             let expected = Header{
                 _list: Vec::new(),
                 _map: HashMap::new(),
-                _record_byte_size: 0
+                _record_byte_size: 0,
+                _trim_mode: TrimMode::None,
+                _endianness: Endianness::Big
             };
             let header = Header::new();
             assert_eq!(expected, header);
@@ -2531,11 +5812,13 @@ mod tests {
         fn add_field() {
             let expected_0 = Field{
                 _name: "foo".to_string(),
-                _value_type: FieldType::F32
+                _value_type: FieldType::F32,
+                _nullable: false
             };
             let expected_1 = Field{
                 _name: "bar".to_string(),
-                _value_type: FieldType::I32
+                _value_type: FieldType::I32,
+                _nullable: false
             };
             let mut header = Header::new();
 
@@ -2586,15 +5869,19 @@ mod tests {
                 _list: vec!(
                     Field{
                         _name: "abc".to_string(),
-                        _value_type: FieldType::U32
+                        _value_type: FieldType::U32,
+                        _nullable: false
                     },
                     Field{
                         _name: "def".to_string(),
-                        _value_type: FieldType::Str(45)
+                        _value_type: FieldType::Str(45),
+                        _nullable: false
                     }
                 ),
                 _map: HashMap::new(),
-                _record_byte_size: 0
+                _record_byte_size: 0,
+                _trim_mode: TrimMode::None,
+                _endianness: Endianness::Big
             };
             header.rebuild_hashmap();
             assert_eq!(53u64, header._record_byte_size);
@@ -2612,7 +5899,8 @@ mod tests {
         fn remove_with_index() {
             let expected = Field{
                 _name: "abcde".to_string(),
-                _value_type: FieldType::I64
+                _value_type: FieldType::I64,
+                _nullable: false
             };
             let mut header = Header::new();
 
@@ -2655,7 +5943,8 @@ mod tests {
         fn remove_by_name() {
             let expected = Field{
                 _name: "abcde".to_string(),
-                _value_type: FieldType::I64
+                _value_type: FieldType::I64,
+                _nullable: false
             };
             let mut header = Header::new();
 
@@ -2761,7 +6050,8 @@ mod tests {
             // test search by index
             let expected = Field{
                 _name: "abcde".to_string(),
-                _value_type: FieldType::I64
+                _value_type: FieldType::I64,
+                _nullable: false
             };
             assert_eq!(expected, header._list[1]);
             match header.get_by_index(1) {
@@ -2772,7 +6062,8 @@ mod tests {
             // test search mutable by index
             let mut expected = Field{
                 _name: "foo".to_string(),
-                _value_type: FieldType::F32
+                _value_type: FieldType::F32,
+                _nullable: false
             };
             assert_eq!(expected, header._list[0]);
             match header.get_mut_by_index(0) {
@@ -2834,7 +6125,8 @@ mod tests {
             // test search by index
             let expected = Field{
                 _name: "abcde".to_string(),
-                _value_type: FieldType::I64
+                _value_type: FieldType::I64,
+                _nullable: false
             };
             assert_eq!(expected, header._list[1]);
             match header.get("abcde") {
@@ -2845,7 +6137,8 @@ mod tests {
             // test search mutable by index
             let mut expected = Field{
                 _name: "foo".to_string(),
-                _value_type: FieldType::F32
+                _value_type: FieldType::F32,
+                _nullable: false
             };
             assert_eq!(expected, header._list[0]);
             match header.get_mut("foo") {
@@ -2946,6 +6239,22 @@ mod tests {
             assert_eq!(181, header.size_as_bytes());
         }
 
+        #[test]
+        fn is_fixed_length() -> Result<()> {
+            let mut header = Header::new();
+            assert!(header.is_fixed_length());
+            header.add("id", FieldType::U64)?;
+            header.add("name", FieldType::Str(10))?;
+            header.add_nullable("status", FieldType::Enum8(vec!["new".to_string()]))?;
+            header.add("created", FieldType::Timestamp)?;
+            assert!(header.is_fixed_length());
+
+            // a single variable length field makes the whole record variable
+            header.add_nullable("notes", FieldType::StrVar)?;
+            assert!(!header.is_fixed_length());
+            Ok(())
+        }
+
         #[test]
         fn record_byte_size() {
             let mut header = Header::new();
@@ -3077,11 +6386,49 @@ mod tests {
                 return;
             }
 
-            // test
+            // test
+            match header.read_record(&mut reader) {
+                Ok(v) => assert_eq!(expected, v),
+                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+            }
+        }
+
+        #[test]
+        fn read_record_truncated() -> Result<()> {
+            let buf = [
+                // foo field
+                6u8, 74u8, 236u8, 75u8, 242u8, 24u8, 101u8, 197u8,
+                // bar field value size
+                0, 0, 0, 5u8,
+                // truncated bar field value
+                104u8, 101u8, 108u8
+            ];
+            let mut header = Header::new();
+            header.add("foo", FieldType::U64)?;
+            header.add("bar", FieldType::Str(10))?;
+            header.add("abc", FieldType::I16)?;
+
+            // truncated inside a field
+            let mut reader = &buf as &[u8];
+            match header.read_record(&mut reader) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "unexpected end of file while reading field \"bar\"",
+                    e.to_string()
+                )
+            }
+
+            // truncated at a field boundary
+            let mut reader = &buf[..8];
             match header.read_record(&mut reader) {
-                Ok(v) => assert_eq!(expected, v),
-                Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => match e.downcast::<ParseError>() {
+                    Ok(ParseError::UnexpectedEof(name)) => assert_eq!("bar", name),
+                    Ok(ex) => panic!("expected ParseError::UnexpectedEof but got {:?}", ex),
+                    Err(ex) => panic!("expected ParseError::UnexpectedEof but got {:?}", ex)
+                }
             }
+            Ok(())
         }
 
         #[test]
@@ -3136,6 +6483,100 @@ mod tests {
             }
         }
 
+        #[test]
+        fn write_record_with_field_count_mismatch() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(4))?;
+            header.add("abc", FieldType::U64)?;
+            let mut record = Record::new();
+            record.add("foo", Value::I32(1))?;
+            match header.write_record(&mut Vec::new(), &record) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => match e.downcast::<ParseError>() {
+                    Ok(ParseError::FieldCountMismatch{expected, got}) => {
+                        assert_eq!(3, expected);
+                        assert_eq!(1, got);
+                    },
+                    Ok(ex) => panic!("expected ParseError::FieldCountMismatch but got {:?}", ex),
+                    Err(ex) => panic!("expected ParseError::FieldCountMismatch but got {:?}", ex)
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn read_write_variable_length_record() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::U8)?;
+            header.add("bar", FieldType::StrVar)?;
+            let mut record = Record::new();
+            record.add("foo", Value::U8(7))?;
+            record.add("bar", Value::Str("abc".to_string()))?;
+
+            // the record is prefixed by its byte size
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+            let expected = [0u8, 0u8, 0u8, 8u8, 7u8, 0u8, 0u8, 0u8, 3u8, 97u8, 98u8, 99u8];
+            assert_eq!(expected, buf[..]);
+            assert_eq!(record, header.read_record(&mut &buf[..])?);
+
+            // the declared size must match the field values
+            buf[3] = 9;
+            buf.push(0u8);
+            match header.read_record(&mut &buf[..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("size mismatch, expected 8 bytes but found 9 bytes", e.to_string())
+            }
+
+            // a corrupted declared size fails on the short read
+            buf[..4].copy_from_slice(&u32::MAX.to_be_bytes());
+            match header.read_record(&mut &buf[..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    format!("size mismatch, expected {} bytes but found 9 bytes", u32::MAX),
+                    e.to_string()
+                )
+            }
+
+            // a corrupted value size fails on the short read too
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+            buf[5..9].copy_from_slice(&u32::MAX.to_be_bytes());
+            match FieldType::StrVar.read_value(&mut &buf[5..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("unexpected end of file", e.to_string())
+            }
+            match header.read_record(&mut &buf[..]) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("unexpected end of file while reading field \"bar\"", e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn write_default_records() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(4))?;
+            header.add("abc", FieldType::Bool)?;
+
+            let mut buf = Vec::new();
+            assert_eq!(65, header.write_default_records(&mut buf, 5)?);
+            assert_eq!(65, buf.len());
+
+            let mut expected = Record::new();
+            expected.add("foo", Value::I32(0))?;
+            expected.add("bar", Value::Str("".to_string()))?;
+            expected.add("abc", Value::Bool(false))?;
+            let mut reader = &buf as &[u8];
+            for _ in 0..5 {
+                assert_eq!(expected, header.read_record(&mut reader)?);
+            }
+            assert!(reader.is_empty());
+            Ok(())
+        }
+
         #[test]
         fn load_from_with_uniq_fields() {
             // expected header
@@ -3307,6 +6748,28 @@ mod tests {
             }
         }
 
+        #[test]
+        fn little_endian_flag() {
+            assert_eq!(0x8000_0000, Header::LITTLE_ENDIAN_FLAG);
+        }
+
+        #[test]
+        fn read_write_little_endian() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.set_endianness(Endianness::Little);
+            let mut buf = Vec::new();
+            header.write_to(&mut buf)?;
+            assert_eq!([128u8, 0u8, 0u8, 1u8], buf[..4]);
+
+            // the byte order is loaded along the fields
+            let loaded = Header::read_from(&mut &buf[..])?;
+            assert_eq!(1, loaded.len());
+            assert_eq!(Endianness::Little, loaded.endianness());
+            assert_eq!(header, loaded);
+            Ok(())
+        }
+
         #[test]
         fn write_to() {
             let expected = [
@@ -3390,5 +6853,791 @@ mod tests {
             }
             assert_eq!(expected, field_names);
         }
+
+        #[test]
+        fn eq_ignores_trim_mode() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Str(5))?;
+            header.set_trim_mode(TrimMode::Both);
+            let mut buf = Vec::new();
+            header.write_to(&mut buf)?;
+
+            // test
+            let loaded = Header::read_from(&mut &buf[..])?;
+            assert_eq!(TrimMode::None, loaded.trim_mode());
+            assert_eq!(header, loaded);
+            Ok(())
+        }
+
+        #[test]
+        fn structurally_eq() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("bar", FieldType::Str(37))?;
+
+            // drift the cached record size
+            let mut other = header.clone();
+            other._record_byte_size = 999;
+            assert_ne!(header, other);
+            assert!(header.structurally_eq(&other));
+            assert!(other.structurally_eq(&header));
+            Ok(())
+        }
+
+        #[test]
+        fn diff_identical() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("bar", FieldType::Str(37))?;
+            let other = header.clone();
+            let diff = header.diff(&other);
+            assert!(diff.is_empty());
+            assert_eq!(HeaderDiff::default(), diff);
+            assert!(header.is_read_compatible(&other));
+            Ok(())
+        }
+
+        #[test]
+        fn diff_added_and_removed() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("bar", FieldType::Str(37))?;
+            let mut other = Header::new();
+            other.add("foo", FieldType::Bool)?;
+            other.add("abc", FieldType::I32)?;
+            other.add("def", FieldType::U8)?;
+
+            let expected = HeaderDiff{
+                added: vec!["abc".to_string(), "def".to_string()],
+                removed: vec!["bar".to_string()],
+                type_changed: Vec::new()
+            };
+            assert_eq!(expected, header.diff(&other));
+            assert!(!header.is_read_compatible(&other));
+            Ok(())
+        }
+
+        #[test]
+        fn diff_type_changed() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("bar", FieldType::Str(37))?;
+            header.add("abc", FieldType::I32)?;
+            let mut other = Header::new();
+            other.add("foo", FieldType::Bool)?;
+            other.add("bar", FieldType::Str(40))?;
+            other.add_nullable("abc", FieldType::I32)?;
+
+            let expected = HeaderDiff{
+                added: Vec::new(),
+                removed: Vec::new(),
+                type_changed: vec!["bar".to_string(), "abc".to_string()]
+            };
+            assert_eq!(expected, header.diff(&other));
+            assert!(!header.is_read_compatible(&other));
+            Ok(())
+        }
+
+        #[test]
+        fn diff_reordered() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("bar", FieldType::Str(37))?;
+            let mut other = Header::new();
+            other.add("bar", FieldType::Str(37))?;
+            other.add("foo", FieldType::Bool)?;
+
+            // same fields but the record layout differs
+            assert!(header.diff(&other).is_empty());
+            assert!(!header.is_read_compatible(&other));
+            Ok(())
+        }
+
+        #[test]
+        fn structurally_eq_with_different_fields() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("bar", FieldType::Str(37))?;
+
+            // different field order
+            let mut other = Header::new();
+            other.add("bar", FieldType::Str(37))?;
+            other.add("foo", FieldType::Bool)?;
+            assert!(!header.structurally_eq(&other));
+
+            // different field type
+            let mut other = Header::new();
+            other.add("foo", FieldType::Bool)?;
+            other.add("bar", FieldType::Str(36))?;
+            assert!(!header.structurally_eq(&other));
+
+            // different byte order
+            let mut other = header.clone();
+            other.set_endianness(Endianness::Little);
+            assert!(!header.structurally_eq(&other));
+            assert!(!header.is_read_compatible(&other));
+            assert!(header.diff(&other).is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn field_offset() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("abcde", FieldType::Str(10))?;
+            header.add("bar", FieldType::I32)?;
+            header.add("ip", FieldType::IpAddr)?;
+            assert_eq!(0, header.field_offset("foo")?);
+            assert_eq!(1, header.field_offset("abcde")?);
+            assert_eq!(15, header.field_offset("bar")?);
+            assert_eq!(19, header.field_offset("ip")?);
+            Ok(())
+        }
+
+        #[test]
+        fn field_offset_not_found() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            match header.field_offset("bar") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"bar\" doesn't exists within the header", e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn field_offset_with_variable_length() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::Bool)?;
+            header.add("bar", FieldType::StrVar)?;
+            header.add("baz", FieldType::I32)?;
+            let expected = "random access isn't supported on tables with variable length fields";
+            for name in ["foo", "bar", "baz"] {
+                match header.field_offset(name) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+            match header.field_span("baz") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn field_span() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::U64)?;
+            header.add("bar", FieldType::Str(10))?;
+            header.add_nullable("abc", FieldType::I16)?;
+
+            // offsets sum the preceding value sizes: 8 + (4 + 10) + (1 + 2)
+            assert_eq!((0, 8), header.field_span("foo")?);
+            assert_eq!((8, 14), header.field_span("bar")?);
+            assert_eq!((22, 3), header.field_span("abc")?);
+            assert_eq!(header.record_byte_size(), 22 + 3);
+            match header.field_span("def") {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"def\" doesn't exists within the header", e.to_string())
+            }
+            Ok(())
+        }
+    
+        #[test]
+        fn schema_hash() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            let mut same = Header::new();
+            same.add("foo", FieldType::I32)?;
+            same.add("bar", FieldType::Str(5))?;
+            assert_eq!(header.schema_hash()?, same.schema_hash()?);
+
+            // reordered fields
+            let mut reordered = Header::new();
+            reordered.add("bar", FieldType::Str(5))?;
+            reordered.add("foo", FieldType::I32)?;
+            assert_ne!(header.schema_hash()?, reordered.schema_hash()?);
+
+            // renamed field
+            let mut renamed = Header::new();
+            renamed.add("foo", FieldType::I32)?;
+            renamed.add("baz", FieldType::Str(5))?;
+            assert_ne!(header.schema_hash()?, renamed.schema_hash()?);
+
+            // different type
+            let mut retyped = Header::new();
+            retyped.add("foo", FieldType::I32)?;
+            retyped.add("bar", FieldType::Str(6))?;
+            assert_ne!(header.schema_hash()?, retyped.schema_hash()?);
+            Ok(())
+        }
+
+        #[test]
+        fn hash_record() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            let mut record = header.new_record()?;
+            record.set("foo", Value::I32(10))?;
+            record.set("bar", Value::Str("abc".to_string()))?;
+            let mut same = header.new_record()?;
+            same.set("foo", Value::I32(10))?;
+            same.set("bar", Value::Str("abc".to_string()))?;
+            assert_eq!(header.hash_record(&record)?, header.hash_record(&same)?);
+
+            // different value
+            let mut other = header.new_record()?;
+            other.set("foo", Value::I32(10))?;
+            other.set("bar", Value::Str("abd".to_string()))?;
+            assert_ne!(header.hash_record(&record)?, header.hash_record(&other)?);
+
+            // invalid record
+            let mut invalid = Record::new();
+            invalid.add("foo", Value::I32(10))?;
+            match header.hash_record(&invalid) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "header field count mismatch the record value count, expected 2 values but got 1",
+                    e.to_string()
+                )
+            }
+            Ok(())
+        }
+    
+        #[test]
+        fn infer_from_csv() {
+            with_tmpdir(&|dir| -> Result<()> {
+                let path = dir.path().join("input.csv");
+                let buf = "\
+                    name,age,price,active,notes,empty\n\
+                    fork,12,1.5,true,,\n\
+                    keyboard,-3,20,false,a longer note,\n\
+                    mouse,,7.25,true,short,\n\
+                    pencil,abc,x,maybe,this row isn't sampled,\n\
+                ".as_bytes();
+                create_file_with_bytes(&path, buf)?;
+
+                // test
+                let header = Header::infer_from_csv(&path, 3)?;
+                let mut expected = Header::new();
+                expected.add("name", FieldType::Str(8))?;
+                expected.add("age", FieldType::I64)?;
+                expected.add("price", FieldType::F64)?;
+                expected.add("active", FieldType::Bool)?;
+                expected.add("notes", FieldType::Str(13))?;
+                expected.add("empty", FieldType::Str(1))?;
+                assert_eq!(expected, header);
+                for field in header.iter() {
+                    field.get_type().validate_definition()?;
+                }
+
+                // the last row turns every typed column into a string
+                let header = Header::infer_from_csv(&path, 10)?;
+                let mut expected = Header::new();
+                expected.add("name", FieldType::Str(8))?;
+                expected.add("age", FieldType::Str(3))?;
+                expected.add("price", FieldType::Str(4))?;
+                expected.add("active", FieldType::Str(5))?;
+                expected.add("notes", FieldType::Str(22))?;
+                expected.add("empty", FieldType::Str(1))?;
+                assert_eq!(expected, header);
+                for field in header.iter() {
+                    field.get_type().validate_definition()?;
+                }
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn nullable_record_round_trip() -> Result<()> {
+            let mut header = Header::new();
+            header.add_nullable("foo", FieldType::I32)?;
+            header.add_nullable("bar", FieldType::Str(5))?;
+            header.add("baz", FieldType::I32)?;
+            assert_eq!(19, header.record_byte_size());
+            assert_eq!(5, header.field_offset("bar")?);
+            assert_eq!(15, header.field_offset("baz")?);
+
+            // test
+            let mut record = header.new_record()?;
+            record.set("foo", Value::Null)?;
+            record.set("bar", Value::Null)?;
+            record.set("baz", Value::I32(0))?;
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+            assert_eq!(19, buf.len());
+            assert_eq!(record, header.read_record(&mut &buf[..])?);
+
+            // test zero values are not read as null
+            record.set("foo", Value::I32(0))?;
+            record.set("bar", Value::Str("".to_string()))?;
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+            assert_eq!(record, header.read_record(&mut &buf[..])?);
+
+            // test the nullable flag is kept by the header
+            let mut buf = Vec::new();
+            header.write_to(&mut buf)?;
+            let read = Header::read_from(&mut &buf[..])?;
+            assert_eq!(header, read);
+            Ok(())
+        }
+
+        #[test]
+        fn insert_at() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            let record_byte_size = header.record_byte_size();
+
+            // test insert in the middle and at the end
+            header.insert_at(1, "abc", FieldType::U64)?;
+            header.insert_at(3, "def", FieldType::Bool)?;
+            let names: Vec<&str> = header.iter().map(|v| v.get_name()).collect();
+            assert_eq!(vec!["foo", "abc", "bar", "def"], names);
+            assert_eq!(Some(&1), header._map.get("abc"));
+            assert_eq!(Some(&2), header._map.get("bar"));
+            assert_eq!(Some(&3), header._map.get("def"));
+            assert_eq!(record_byte_size + 9, header.record_byte_size());
+            assert_eq!(12, header.field_offset("bar")?);
+            Ok(())
+        }
+
+        #[test]
+        fn insert_at_with_errors() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            match header.insert_at(2, "bar", FieldType::I32) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field index 2 is out of range, the header has 1 fields", e.to_string())
+            }
+            match header.insert_at(0, "foo", FieldType::I32) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"foo\" already exists within the header", e.to_string())
+            }
+            assert_eq!(1, header.len());
+            Ok(())
+        }
+
+        #[test]
+        fn rename() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            header.add("abc", FieldType::U64)?;
+            let record_byte_size = header.record_byte_size();
+            let offset = header.field_offset("bar")?;
+
+            // test
+            header.rename("bar", "baz")?;
+            assert_eq!(None, header.get("bar"));
+            let field = header.get("baz").unwrap();
+            assert_eq!("baz", field.get_name());
+            assert_eq!(&FieldType::Str(5), field.get_type());
+            assert_eq!("baz", header.get_by_index(1).unwrap().get_name());
+            assert_eq!(offset, header.field_offset("baz")?);
+            assert_eq!(record_byte_size, header.record_byte_size());
+            Ok(())
+        }
+
+        #[test]
+        fn rename_with_errors() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            let long_name = "a".repeat(51);
+            let cases = [
+                ("abc", "def", "field \"abc\" doesn't exists within the header".to_string()),
+                ("foo", "bar", "field \"bar\" already exists within the header".to_string()),
+                ("foo", long_name.as_str(), "field name size must be <= 50 bytes length".to_string())
+            ];
+            for (old, new, expected) in cases {
+                match header.rename(old, new) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+            assert_eq!("foo", header.get_by_index(0).unwrap().get_name());
+            assert_eq!("bar", header.get_by_index(1).unwrap().get_name());
+            Ok(())
+        }
+
+        #[test]
+        fn reorder() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            header.add("abc", FieldType::U64)?;
+            let record_byte_size = header.record_byte_size();
+
+            // test
+            header.reorder(&[2, 1, 0])?;
+            let names: Vec<&str> = header.iter().map(|v| v.get_name()).collect();
+            assert_eq!(vec!["abc", "bar", "foo"], names);
+            assert_eq!(Some(&0), header._map.get("abc"));
+            assert_eq!(Some(&2), header._map.get("foo"));
+            assert_eq!(&FieldType::Str(5), header.get("bar").unwrap().get_type());
+            assert_eq!(record_byte_size, header.record_byte_size());
+            assert_eq!(0, header.field_offset("abc")?);
+            assert_eq!((u64::BYTES + u32::BYTES + 5) as u64, header.field_offset("foo")?);
+            Ok(())
+        }
+
+        #[test]
+        fn reorder_with_errors() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::I32)?;
+            header.add("bar", FieldType::Str(5))?;
+            header.add("abc", FieldType::U64)?;
+            let cases: [(&[usize], &str); 3] = [
+                (&[0, 1, 1], "field index 1 is repeated within the field order"),
+                (&[0, 1, 3], "field index 3 is out of range, the header has 3 fields"),
+                (&[1, 0], "field order has 2 indexes but the header has 3 fields")
+            ];
+            for (order, expected) in cases {
+                match header.reorder(order) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+            let names: Vec<&str> = header.iter().map(|v| v.get_name()).collect();
+            assert_eq!(vec!["foo", "bar", "abc"], names);
+            Ok(())
+        }
+
+        #[test]
+        fn record_from_json() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U32)?;
+            header.add("name", FieldType::Str(5))?;
+            header.add("active", FieldType::Bool)?;
+            header.add_nullable("price", FieldType::F64)?;
+
+            // test a mixed type record
+            let json = serde_json::json!({"id": 7, "name": "abc", "active": true, "price": 9.5});
+            let record = header.record_from_json(&json)?;
+            assert_eq!(Some(&Value::U32(7)), record.get("id"));
+            assert_eq!(Some(&Value::Str("abc".to_string())), record.get("name"));
+            assert_eq!(Some(&Value::Bool(true)), record.get("active"));
+            assert_eq!(Some(&Value::F64(9.5)), record.get("price"));
+            assert_eq!(json, record.to_json());
+
+            // test missing and null values
+            let record = header.record_from_json(&serde_json::json!({"id": null}))?;
+            assert_eq!(Some(&Value::Default), record.get("id"));
+            assert_eq!(Some(&Value::Default), record.get("name"));
+            assert_eq!(Some(&Value::Null), record.get("price"));
+            Ok(())
+        }
+
+        #[test]
+        fn record_from_json_with_invalid_values() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U32)?;
+            header.add("name", FieldType::Str(5))?;
+
+            let cases = [
+                (serde_json::json!({"id": "abc"}), "invalid value for field \"id\": can't convert JSON value \"abc\" into U32"),
+                (serde_json::json!({"id": -1}), "invalid value for field \"id\": can't convert JSON value -1 into U32"),
+                (serde_json::json!({"name": "too long"}), "invalid value for field \"name\": value Str(\"too long\") doesn't match the field type Str(5)"),
+                (serde_json::json!({"other": 1}), "field \"other\" doesn't exists within the header"),
+                (serde_json::json!([1, 2]), "can't build a record from a JSON value other than object")
+            ];
+            for (json, expected) in cases {
+                match header.record_from_json(&json) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn set_checked() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U32)?;
+            header.add_nullable("name", FieldType::Str(5))?;
+            let mut record = header.new_record()?;
+
+            // test valid values
+            header.set_checked(&mut record, "id", Value::U32(7))?;
+            header.set_checked(&mut record, "name", Value::Str("abc".to_string()))?;
+            assert_eq!(Some(&Value::U32(7)), record.get("id"));
+            assert_eq!(Some(&Value::Str("abc".to_string())), record.get("name"));
+            header.set_checked(&mut record, "name", Value::Null)?;
+            header.set_checked(&mut record, "id", Value::Default)?;
+            assert_eq!(Some(&Value::Null), record.get("name"));
+            assert_eq!(Some(&Value::Default), record.get("id"));
+            Ok(())
+        }
+
+        #[test]
+        fn set_checked_with_invalid_values() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U32)?;
+            header.add("name", FieldType::Str(5))?;
+            let mut record = header.new_record()?;
+            record.set("id", Value::U32(7))?;
+
+            let cases = [
+                ("id", Value::I32(7), "invalid value for field \"id\": value I32(7) doesn't match the field type U32"),
+                ("id", Value::Null, "invalid value for field \"id\": value Null doesn't match the field type U32"),
+                ("name", Value::Str("too long".to_string()), "invalid value for field \"name\": value Str(\"too long\") doesn't match the field type Str(5)"),
+                ("other", Value::U32(1), "field \"other\" doesn't exists within the header")
+            ];
+            for (name, value, expected) in cases {
+                match header.set_checked(&mut record, name, value) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+
+            // rejected values are not assigned
+            assert_eq!(Some(&Value::U32(7)), record.get("id"));
+            Ok(())
+        }
+    }
+
+    mod decode_partial {
+        use super::*;
+
+        #[test]
+        fn decode_fields_at_offsets() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::U64)?;
+            header.add("bar", FieldType::Str(10))?;
+            header.add("abc", FieldType::I16)?;
+            let mut record = header.new_record()?;
+            record.set("foo", Value::U64(453434523432543685u64))?;
+            record.set("bar", Value::Str("hello".to_string()))?;
+            record.set("abc", Value::I16(2345i16))?;
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+
+            // test
+            let types = [
+                (header.field_offset("abc")? as usize, FieldType::I16),
+                (header.field_offset("bar")? as usize, FieldType::Str(10))
+            ];
+            let expected = vec![
+                Value::I16(2345i16),
+                Value::Str("hello".to_string())
+            ];
+            assert_eq!(expected, decode_partial(&buf, &types, Endianness::Big)?);
+            assert_eq!(Vec::<Value>::new(), decode_partial(&buf, &[], Endianness::Big)?);
+            Ok(())
+        }
+
+        #[test]
+        fn decode_little_endian_fields() -> Result<()> {
+            let mut header = Header::new();
+            header.add("foo", FieldType::U64)?;
+            header.add("bar", FieldType::I16)?;
+            header.set_endianness(Endianness::Little);
+            let mut record = header.new_record()?;
+            record.set("foo", Value::U64(453434523432543685u64))?;
+            record.set("bar", Value::I16(2345i16))?;
+            let mut buf = Vec::new();
+            header.write_record(&mut buf, &record)?;
+
+            // test
+            let types = [
+                (header.field_offset("bar")? as usize, FieldType::I16),
+                (header.field_offset("foo")? as usize, FieldType::U64)
+            ];
+            let expected = vec![
+                Value::I16(2345i16),
+                Value::U64(453434523432543685u64)
+            ];
+            assert_eq!(expected, decode_partial(&buf, &types, header.endianness())?);
+            assert_ne!(expected, decode_partial(&buf, &types, Endianness::Big)?);
+            Ok(())
+        }
+
+        #[test]
+        fn out_of_bounds() {
+            let buf = [0u8, 0, 0, 0, 0, 0];
+            match decode_partial(&buf, &[(4, FieldType::I32)], Endianness::Big) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("size mismatch, expected 8 bytes but found 6 bytes", e.to_string())
+            }
+            match decode_partial(&buf, &[(usize::MAX, FieldType::I32)], Endianness::Big) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(format!("\"field offset {}\" is out of range", usize::MAX), e.to_string())
+            }
+        }
+    }
+
+    mod schema_descriptor {
+        use super::*;
+
+        #[test]
+        fn round_trip() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U64)?;
+            header.add("name", FieldType::Str(20))?;
+            header.add("ip", FieldType::IpAddr)?;
+            header.add("elapsed", FieldType::Duration)?;
+
+            // test
+            let descriptor = header.to_descriptor();
+            let expected = FieldDescriptor{
+                name: "name".to_string(),
+                type_name: "str".to_string(),
+                size: Some(20),
+                nullable: false,
+                variants: None,
+                scale: None,
+                element: None,
+                len: None,
+                byte_size: 24
+            };
+            assert_eq!(4, descriptor.fields.len());
+            assert_eq!(expected, descriptor.fields[1]);
+            assert_eq!(header, Header::from_descriptor(&descriptor)?);
+            Ok(())
+        }
+
+        #[test]
+        fn decimal_round_trip() -> Result<()> {
+            let mut header = Header::new();
+            header.add("price", FieldType::Decimal{scale: 2})?;
+            let descriptor = header.to_descriptor();
+            assert_eq!(Some(2), descriptor.fields[0].scale);
+            assert_eq!(header, Header::from_descriptor(&descriptor)?);
+            assert_eq!(header, Header::from_schema_json(&header.to_schema_json())?);
+            Ok(())
+        }
+
+        #[test]
+        fn array_round_trip() -> Result<()> {
+            let mut header = Header::new();
+            header.add("scores", FieldType::Array{element: Box::new(FieldType::Decimal{scale: 2}), len: 3})?;
+            let variants = vec!["a".to_string(), "b".to_string()];
+            header.add_nullable("tags", FieldType::Array{element: Box::new(FieldType::Enum{variants}), len: 2})?;
+
+            // test
+            let descriptor = header.to_descriptor();
+            let expected = ElementDescriptor{
+                type_name: "decimal".to_string(),
+                variants: None,
+                scale: Some(2)
+            };
+            assert_eq!(Some(expected), descriptor.fields[0].element);
+            assert_eq!(Some(3), descriptor.fields[0].len);
+            assert_eq!(header, Header::from_descriptor(&descriptor)?);
+
+            // schema JSON
+            let json = header.to_schema_json();
+            let expected = serde_json::json!([
+                {"name": "scores", "type": "array", "element": {"type": "decimal", "scale": 2}, "len": 3},
+                {"name": "tags", "type": "array", "nullable": true, "element": {"type": "enum", "variants": ["a", "b"]}, "len": 2}
+            ]);
+            assert_eq!(expected, json);
+            assert_eq!(header, Header::from_schema_json(&json)?);
+            Ok(())
+        }
+
+        #[test]
+        fn json() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::I32)?;
+            header.add("name", FieldType::Str(5))?;
+            let json = serde_json::to_string(&header.to_descriptor())?;
+            let expected = r#"{"fields":[{"name":"id","type":"i32","byte_size":4},{"name":"name","type":"str","size":5,"byte_size":9}]}"#;
+            assert_eq!(expected, json);
+
+            // rebuild from JSON
+            let descriptor: SchemaDescriptor = serde_json::from_str(&json)?;
+            assert_eq!(header, Header::from_descriptor(&descriptor)?);
+            Ok(())
+        }
+
+        #[test]
+        fn schema_json() -> Result<()> {
+            let mut header = Header::new();
+            header.add("id", FieldType::U32)?;
+            header.add_nullable("name", FieldType::Str(20))?;
+            header.add("thumb", FieldType::Bytes(64))?;
+
+            // test
+            let json = header.to_schema_json();
+            let expected = serde_json::json!([
+                {"name": "id", "type": "u32"},
+                {"name": "name", "type": "str", "size": 20, "nullable": true},
+                {"name": "thumb", "type": "bytes", "size": 64}
+            ]);
+            assert_eq!(expected, json);
+            assert_eq!(header, Header::from_schema_json(&json)?);
+            Ok(())
+        }
+
+        #[test]
+        fn from_schema_json_with_invalid_values() {
+            let cases = [
+                (serde_json::json!([{"name": "foo", "type": "i128"}]), "field \"foo\": unknown field type \"i128\""),
+                (serde_json::json!([{"name": "foo", "type": "bytes"}]), "field \"foo\": bytes field type requires a size"),
+                (
+                    serde_json::json!([{"name": "foo", "type": "array", "len": 2}]),
+                    "field \"foo\": array field type requires an element type and length"
+                ),
+                (
+                    serde_json::json!([{"name": "foo", "type": "array", "element": {"type": "str"}, "len": 2}]),
+                    "field \"foo\": str field type requires a size"
+                ),
+                (serde_json::json!([{"name": "foo"}]), "invalid schema JSON: missing field `type`"),
+                (serde_json::json!({"name": "foo", "type": "u8"}), "schema JSON must be an array of fields")
+            ];
+            for (json, expected) in cases {
+                match Header::from_schema_json(&json) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!(expected, e.to_string())
+                }
+            }
+        }
+
+        #[test]
+        fn from_descriptor_with_unknown_type() {
+            let descriptor = SchemaDescriptor{
+                fields: vec![FieldDescriptor{
+                    name: "foo".to_string(),
+                    type_name: "i128".to_string(),
+                    size: None,
+                    nullable: false,
+                    variants: None,
+                    scale: None,
+                    element: None,
+                    len: None,
+                    byte_size: 16
+                }]
+            };
+            match Header::from_descriptor(&descriptor) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"foo\": unknown field type \"i128\"", e.to_string())
+            }
+        }
+
+        #[test]
+        fn from_descriptor_str_without_size() {
+            let descriptor = SchemaDescriptor{
+                fields: vec![FieldDescriptor{
+                    name: "foo".to_string(),
+                    type_name: "str".to_string(),
+                    size: None,
+                    nullable: false,
+                    variants: None,
+                    scale: None,
+                    element: None,
+                    len: None,
+                    byte_size: 4
+                }]
+            };
+            match Header::from_descriptor(&descriptor) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("field \"foo\": str field type requires a size", e.to_string())
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,26 @@
+use chrono::{DateTime, Datelike, NaiveDate, SecondsFormat, Utc};
 use serde::ser::{Serialize, Serializer};
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+use std::time::Duration;
 use serde_json::{Value as JSValue, Number as JSNumber};
 use anyhow::{bail, Result};
+use super::header::FieldType;
 
 /// Represents a value.
-#[derive(Debug, PartialEq, Clone)]
+/// 
+/// Values can be hashed and compared for equality, [Self::Default] never
+/// equals a zero value of any type nor [Self::Null], and float variants are compared and
+/// hashed by their bit pattern so `NaN` equals itself while `0.0` and
+/// `-0.0` are different values. Only values of the same variant can be
+/// ordered, floats are ordered by their total order.
+#[derive(Debug, Clone)]
 pub enum Value {
     Default,
+
+    /// Represents an absent value, only nullable fields can store it.
+    Null,
+
     Bool(bool),
     I8(i8),
     I16(i16),
@@ -19,10 +34,243 @@ pub enum Value {
     F64(f64),
 
     /// Represents a string with a max size.
-    Str(String)
+    Str(String),
+
+    /// Represents an IPv4 or IPv6 address.
+    IpAddr(IpAddr),
+
+    /// Represents a single unicode character.
+    Char(char),
+
+    /// Represents a non negative elapsed time.
+    Duration(Duration),
+
+    /// Represents a date as days since the Unix epoch.
+    Date(i32),
+
+    /// Represents a timestamp as milliseconds since the Unix epoch.
+    Timestamp(i64),
+
+    /// Represents raw bytes.
+    Bytes(Vec<u8>),
+
+    /// Fixed-point decimal as the scaled integer and its scale.
+    Decimal(i64, u8),
+
+    /// Represents an enum variant by its discriminant, see [FieldType::enum_label].
+    Enum(u16),
+
+    /// Represents a UUID as its raw bytes.
+    Uuid([u8; 16]),
+
+    /// Represents a parsed JSON value.
+    Json(JSValue),
+
+    /// Represents a fixed amount of values of the same type.
+    Array(Vec<Value>)
+}
+
+/// Days from the first day of the common era to the Unix epoch.
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Converts days since the Unix epoch into a date.
+/// 
+/// # Arguments
+/// 
+/// * `days` - Days since the Unix epoch.
+pub(crate) fn date_from_days(days: i32) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(days.checked_add(UNIX_EPOCH_DAYS_FROM_CE)?)
+}
+
+/// Converts a date into days since the Unix epoch.
+/// 
+/// # Arguments
+/// 
+/// * `date` - Date to convert.
+pub(crate) fn days_from_date(date: &NaiveDate) -> i32 {
+    date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE
+}
+
+/// Formats days since the Unix epoch as `YYYY-MM-DD`, the day count is
+/// used as is when out of the supported date range.
+/// 
+/// # Arguments
+/// 
+/// * `days` - Days since the Unix epoch.
+fn format_date(days: i32) -> String {
+    match date_from_days(days) {
+        Some(v) => v.format("%Y-%m-%d").to_string(),
+        None => days.to_string()
+    }
+}
+
+/// Formats bytes as a lower case hexadecimal string.
+/// 
+/// # Arguments
+/// 
+/// * `buf` - Bytes to format.
+fn format_hex(buf: &[u8]) -> String {
+    buf.iter().map(|v| format!("{:02x}", v)).collect()
+}
+
+/// Formats UUID bytes as a lower case hyphenated string like
+/// `"67e55044-10b1-426f-9247-bb680e5fe0c8"`.
+/// 
+/// # Arguments
+/// 
+/// * `buf` - UUID bytes.
+fn format_uuid(buf: &[u8; 16]) -> String {
+    format!(
+        "{}-{}-{}-{}-{}",
+        format_hex(&buf[..4]),
+        format_hex(&buf[4..6]),
+        format_hex(&buf[6..8]),
+        format_hex(&buf[8..10]),
+        format_hex(&buf[10..])
+    )
+}
+
+/// Formats a decimal scaled integer inserting the decimal point based on
+/// its scale.
+/// 
+/// # Arguments
+/// 
+/// * `value` - Scaled integer.
+/// * `scale` - Decimal scale.
+fn format_decimal(value: i64, scale: u8) -> String {
+    let digits = (value as i128).abs().to_string();
+    let scale = scale as usize;
+    let sign = if value < 0 { "-" } else { "" };
+    if scale < 1 {
+        return format!("{}{}", sign, digits);
+    }
+    let digits = format!("{:0>width$}", digits, width = scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+    format!("{}{}.{}", sign, integer, fraction)
+}
+
+/// Formats milliseconds since the Unix epoch as an RFC 3339 UTC
+/// timestamp, the millisecond count is used as is when out of the
+/// supported date range.
+/// 
+/// # Arguments
+/// 
+/// * `millis` - Milliseconds since the Unix epoch.
+fn format_timestamp(millis: i64) -> String {
+    match DateTime::from_timestamp_millis(millis) {
+        Some(v) => v.to_rfc3339_opts(SecondsFormat::Millis, true),
+        None => millis.to_string()
+    }
 }
 
 impl Value {
+    /// Converts a [Self::Timestamp] into a UTC date time, `None` on any
+    /// other variant or when out of the supported date range.
+    pub fn as_datetime(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Timestamp(v) => DateTime::from_timestamp_millis(*v),
+            _ => None
+        }
+    }
+
+    /// Converts a [Self::Date] into a date, `None` on any other variant
+    /// or when out of the supported date range.
+    pub fn as_date(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Date(v) => date_from_days(*v),
+            _ => None
+        }
+    }
+
+    /// Returns the value as an i64 when it is a signed integer, `None` on
+    /// any other variant.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::I8(v) => Some(*v as i64),
+            Self::I16(v) => Some(*v as i64),
+            Self::I32(v) => Some(*v as i64),
+            Self::I64(v) => Some(*v),
+            _ => None
+        }
+    }
+
+    /// Returns the value as an u64 when it is an unsigned integer, `None`
+    /// on any other variant.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::U8(v) => Some(*v as u64),
+            Self::U16(v) => Some(*v as u64),
+            Self::U32(v) => Some(*v as u64),
+            Self::U64(v) => Some(*v),
+            _ => None
+        }
+    }
+
+    /// Returns the value as a f64 when it is a float, `None` on any other
+    /// variant.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::F32(v) => Some(*v as f64),
+            Self::F64(v) => Some(*v),
+            _ => None
+        }
+    }
+
+    /// Returns the string when [Self::Str], `None` on any other variant.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(v) => Some(v.as_str()),
+            _ => None
+        }
+    }
+
+    /// Returns the bool when [Self::Bool], `None` on any other variant.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(v) => Some(*v),
+            _ => None
+        }
+    }
+
+    /// Formats the value inserting the decimal point based on its scale
+    /// when [Self::Decimal], other values are formatted as displayed.
+    pub fn decimal_to_string(&self) -> String {
+        match self {
+            Self::Decimal(v, scale) => format_decimal(*v, *scale),
+            _ => self.to_string()
+        }
+    }
+
+    /// Formats the value as a hyphenated UUID string when [Self::Uuid],
+    /// other values are formatted as displayed.
+    pub fn uuid_to_hyphenated(&self) -> String {
+        match self {
+            Self::Uuid(v) => format_uuid(v),
+            _ => self.to_string()
+        }
+    }
+
+    /// Parses a hyphenated UUID string like
+    /// `"67e55044-10b1-426f-9247-bb680e5fe0c8"` into a [Self::Uuid], hex
+    /// digits are case insensitive.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `s` - String to parse.
+    pub fn parse_uuid(s: &str) -> Result<Self> {
+        let groups: Vec<&str> = s.split('-').collect();
+        let sizes: Vec<usize> = groups.iter().map(|v| v.len()).collect();
+        let digits = groups.concat();
+        if sizes != [8, 4, 4, 4, 12] || !digits.chars().all(|v| v.is_ascii_hexdigit()) {
+            bail!("invalid UUID \"{}\"", s);
+        }
+        let mut buf = [0u8; 16];
+        for (index, byte) in buf.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16)?;
+        }
+        Ok(Self::Uuid(buf))
+    }
+
     /// Try from a JS u64 value.
     /// 
     /// # Arguments
@@ -40,12 +288,271 @@ impl Value {
             _ => bail!("can't convert from a JS value other than number")
         }
     }
+
+    /// Converts the value into a string for text exports. Float values
+    /// are rounded when a precision is provided and [Self::Default] is
+    /// converted into an empty string.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `float_precision` - Decimal count for float values.
+    pub fn to_display_string(&self, float_precision: Option<usize>) -> String {
+        match (self, float_precision) {
+            (Self::F32(v), Some(precision)) => format!("{:.*}", precision, v),
+            (Self::F64(v), Some(precision)) => format!("{:.*}", precision, v),
+            _ => self.to_string()
+        }
+    }
+
+    /// Converts the value into a JSON value.
+    pub fn into_json(self) -> JSValue {
+        self.into()
+    }
+
+    /// Converts a JSON value into a value of a field type, JSON null is
+    /// converted into [Self::Default]. Numbers must fit the field type and
+    /// strings are parsed on IP address, char and duration field types.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - JSON value to convert from.
+    /// * `field_type` - Target field type.
+    pub fn from_json_typed(value: JSValue, field_type: &FieldType) -> Result<Self> {
+        let converted = match (field_type, &value) {
+            (_, JSValue::Null) => Some(Self::Default),
+            (FieldType::Bool, JSValue::Bool(v)) => Some(Self::Bool(*v)),
+            (FieldType::I8, JSValue::Number(n)) => n.as_i64().and_then(|v| i8::try_from(v).ok()).map(Self::I8),
+            (FieldType::I16, JSValue::Number(n)) => n.as_i64().and_then(|v| i16::try_from(v).ok()).map(Self::I16),
+            (FieldType::I32, JSValue::Number(n)) => n.as_i64().and_then(|v| i32::try_from(v).ok()).map(Self::I32),
+            (FieldType::I64, JSValue::Number(n)) => n.as_i64().map(Self::I64),
+            (FieldType::U8, JSValue::Number(n)) => n.as_u64().and_then(|v| u8::try_from(v).ok()).map(Self::U8),
+            (FieldType::U16, JSValue::Number(n)) => n.as_u64().and_then(|v| u16::try_from(v).ok()).map(Self::U16),
+            (FieldType::U32, JSValue::Number(n)) => n.as_u64().and_then(|v| u32::try_from(v).ok()).map(Self::U32),
+            (FieldType::U64, JSValue::Number(n)) => n.as_u64().map(Self::U64),
+            (FieldType::F32, JSValue::Number(n)) => n.as_f64().map(|v| Self::F32(v as f32)),
+            (FieldType::F64, JSValue::Number(n)) => n.as_f64().map(Self::F64),
+            (FieldType::Str(_), JSValue::String(s))
+            | (FieldType::StrVar, JSValue::String(s))
+            | (FieldType::Enum8(_), JSValue::String(s)) => Some(Self::Str(s.to_string())),
+            (FieldType::IpAddr, JSValue::String(s))
+            | (FieldType::Char, JSValue::String(s))
+            | (FieldType::Duration, JSValue::String(s))
+            | (FieldType::Date, JSValue::String(s))
+            | (FieldType::Timestamp, JSValue::String(s))
+            | (FieldType::Bytes(_), JSValue::String(s))
+            | (FieldType::Decimal{..}, JSValue::String(s)) => Some(field_type.parse_value(s)?),
+            (FieldType::Decimal{..}, JSValue::Number(n)) => Some(field_type.parse_value(&n.to_string())?),
+            (FieldType::Date, JSValue::Number(n)) => n.as_i64().and_then(|v| i32::try_from(v).ok()).map(Self::Date),
+            (FieldType::Enum{..}, JSValue::String(s))
+            | (FieldType::Uuid, JSValue::String(s)) => Some(field_type.parse_value(s)?),
+            (FieldType::Json(_), v) => Some(Self::Json(v.clone())),
+            (FieldType::Array{element, ..}, JSValue::Array(items)) => Some(Self::Array(
+                items.iter().map(|v| Self::from_json_typed(v.clone(), element)).collect::<Result<Vec<_>>>()?
+            )),
+            (FieldType::Enum{..}, JSValue::Number(n)) => n.as_u64().and_then(|v| u16::try_from(v).ok()).map(Self::Enum),
+            (FieldType::Timestamp, JSValue::Number(n)) => n.as_i64().map(Self::Timestamp),
+            _ => None
+        };
+        match converted {
+            Some(v) => Ok(v),
+            None => bail!("can't convert JSON value {} into {:?}", value, field_type)
+        }
+    }
+
+    /// Converts the value into a value of a field type by using checked
+    /// numeric conversions, so integers are converted while they fit the
+    /// target type and floats while no precision is lost. Numbers are
+    /// formatted on string field types when they fit the field size.
+    /// [Self::Default], [Self::Null] and values already valid for the field
+    /// type are returned as is.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `target` - Target field type.
+    pub fn coerce(self, target: &FieldType) -> Result<Self> {
+        if matches!(self, Self::Default | Self::Null) || target.is_valid(&self) {
+            return Ok(self);
+        }
+        let converted = match target {
+            FieldType::I8 => self.exact_integer().and_then(|v| i8::try_from(v).ok()).map(Self::I8),
+            FieldType::I16 => self.exact_integer().and_then(|v| i16::try_from(v).ok()).map(Self::I16),
+            FieldType::I32 => self.exact_integer().and_then(|v| i32::try_from(v).ok()).map(Self::I32),
+            FieldType::I64 => self.exact_integer().and_then(|v| i64::try_from(v).ok()).map(Self::I64),
+            FieldType::U8 => self.exact_integer().and_then(|v| u8::try_from(v).ok()).map(Self::U8),
+            FieldType::U16 => self.exact_integer().and_then(|v| u16::try_from(v).ok()).map(Self::U16),
+            FieldType::U32 => self.exact_integer().and_then(|v| u32::try_from(v).ok()).map(Self::U32),
+            FieldType::U64 => self.exact_integer().and_then(|v| u64::try_from(v).ok()).map(Self::U64),
+            FieldType::F32 => self.exact_float().and_then(|v| {
+                let converted = v as f32;
+                if v.is_nan() || converted as f64 == v {
+                    Some(Self::F32(converted))
+                } else {
+                    None
+                }
+            }),
+            FieldType::F64 => self.exact_float().map(Self::F64),
+            FieldType::Str(_) if self.exact_float().is_some() => Some(Self::Str(self.to_string())),
+            FieldType::Enum{..} => self.exact_integer().and_then(|v| u16::try_from(v).ok()).map(Self::Enum),
+            _ => None
+        };
+        match converted {
+            Some(v) if target.is_valid(&v) => Ok(v),
+            _ => bail!("can't coerce value {:?} into {:?}", self, target)
+        }
+    }
+
+    /// Returns the value as an integer when it is an integer or a float
+    /// without fractional part.
+    fn exact_integer(&self) -> Option<i128> {
+        let value = match self {
+            Self::I8(v) => *v as i128,
+            Self::I16(v) => *v as i128,
+            Self::I32(v) => *v as i128,
+            Self::I64(v) => *v as i128,
+            Self::U8(v) => *v as i128,
+            Self::U16(v) => *v as i128,
+            Self::U32(v) => *v as i128,
+            Self::U64(v) => *v as i128,
+            Self::F32(v) => return Self::F64(*v as f64).exact_integer(),
+            Self::F64(v) => {
+                if !v.is_finite() || v.fract() != 0.0 || v.abs() > i128::MAX as f64 {
+                    return None;
+                }
+                *v as i128
+            },
+            _ => return None
+        };
+        Some(value)
+    }
+
+    /// Returns the value as a float when it is a float or an integer that
+    /// can be represented without precision loss.
+    fn exact_float(&self) -> Option<f64> {
+        let value = match self {
+            Self::F32(v) => *v as f64,
+            Self::F64(v) => *v,
+            _ => {
+                let integer = self.exact_integer()?;
+                let value = integer as f64;
+                if value as i128 != integer {
+                    return None;
+                }
+                value
+            }
+        };
+        Some(value)
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Default, Self::Default) => true,
+            (Self::Null, Self::Null) => true,
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::I8(a), Self::I8(b)) => a == b,
+            (Self::I16(a), Self::I16(b)) => a == b,
+            (Self::I32(a), Self::I32(b)) => a == b,
+            (Self::I64(a), Self::I64(b)) => a == b,
+            (Self::U8(a), Self::U8(b)) => a == b,
+            (Self::U16(a), Self::U16(b)) => a == b,
+            (Self::U32(a), Self::U32(b)) => a == b,
+            (Self::U64(a), Self::U64(b)) => a == b,
+            (Self::F32(a), Self::F32(b)) => a.to_bits() == b.to_bits(),
+            (Self::F64(a), Self::F64(b)) => a.to_bits() == b.to_bits(),
+            (Self::Str(a), Self::Str(b)) => a == b,
+            (Self::IpAddr(a), Self::IpAddr(b)) => a == b,
+            (Self::Char(a), Self::Char(b)) => a == b,
+            (Self::Duration(a), Self::Duration(b)) => a == b,
+            (Self::Date(a), Self::Date(b)) => a == b,
+            (Self::Timestamp(a), Self::Timestamp(b)) => a == b,
+            (Self::Bytes(a), Self::Bytes(b)) => a == b,
+            (Self::Decimal(a, a_scale), Self::Decimal(b, b_scale)) => a == b && a_scale == b_scale,
+            (Self::Enum(a), Self::Enum(b)) => a == b,
+            (Self::Uuid(a), Self::Uuid(b)) => a == b,
+            (Self::Json(a), Self::Json(b)) => a == b,
+            (Self::Array(a), Self::Array(b)) => a == b,
+            _ => false
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Self::Default, Self::Default) => Some(std::cmp::Ordering::Equal),
+            (Self::Null, Self::Null) => Some(std::cmp::Ordering::Equal),
+            (Self::Bool(a), Self::Bool(b)) => a.partial_cmp(b),
+            (Self::I8(a), Self::I8(b)) => a.partial_cmp(b),
+            (Self::I16(a), Self::I16(b)) => a.partial_cmp(b),
+            (Self::I32(a), Self::I32(b)) => a.partial_cmp(b),
+            (Self::I64(a), Self::I64(b)) => a.partial_cmp(b),
+            (Self::U8(a), Self::U8(b)) => a.partial_cmp(b),
+            (Self::U16(a), Self::U16(b)) => a.partial_cmp(b),
+            (Self::U32(a), Self::U32(b)) => a.partial_cmp(b),
+            (Self::U64(a), Self::U64(b)) => a.partial_cmp(b),
+            (Self::F32(a), Self::F32(b)) => Some(a.total_cmp(b)),
+            (Self::F64(a), Self::F64(b)) => Some(a.total_cmp(b)),
+            (Self::Str(a), Self::Str(b)) => a.partial_cmp(b),
+            (Self::IpAddr(a), Self::IpAddr(b)) => a.partial_cmp(b),
+            (Self::Char(a), Self::Char(b)) => a.partial_cmp(b),
+            (Self::Duration(a), Self::Duration(b)) => a.partial_cmp(b),
+            (Self::Date(a), Self::Date(b)) => a.partial_cmp(b),
+            (Self::Timestamp(a), Self::Timestamp(b)) => a.partial_cmp(b),
+            (Self::Bytes(a), Self::Bytes(b)) => a.partial_cmp(b),
+            (Self::Decimal(a, a_scale), Self::Decimal(b, b_scale)) if a_scale == b_scale => a.partial_cmp(b),
+            (Self::Enum(a), Self::Enum(b)) => a.partial_cmp(b),
+            (Self::Uuid(a), Self::Uuid(b)) => a.partial_cmp(b),
+            (Self::Json(a), Self::Json(b)) if a == b => Some(std::cmp::Ordering::Equal),
+            (Self::Array(a), Self::Array(b)) => a.partial_cmp(b),
+            _ => None
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Default => {},
+            Self::Null => {},
+            Self::Bool(v) => v.hash(state),
+            Self::I8(v) => v.hash(state),
+            Self::I16(v) => v.hash(state),
+            Self::I32(v) => v.hash(state),
+            Self::I64(v) => v.hash(state),
+            Self::U8(v) => v.hash(state),
+            Self::U16(v) => v.hash(state),
+            Self::U32(v) => v.hash(state),
+            Self::U64(v) => v.hash(state),
+            Self::F32(v) => v.to_bits().hash(state),
+            Self::F64(v) => v.to_bits().hash(state),
+            Self::Str(v) => v.hash(state),
+            Self::IpAddr(v) => v.hash(state),
+            Self::Char(v) => v.hash(state),
+            Self::Duration(v) => v.hash(state),
+            Self::Date(v) => v.hash(state),
+            Self::Timestamp(v) => v.hash(state),
+            Self::Bytes(v) => v.hash(state),
+            Self::Decimal(v, scale) => {
+                v.hash(state);
+                scale.hash(state);
+            },
+            Self::Enum(v) => v.hash(state),
+            Self::Uuid(v) => v.hash(state),
+            Self::Json(v) => v.to_string().hash(state),
+            Self::Array(v) => v.hash(state)
+        }
+    }
 }
 
 impl std::fmt::Display for Value{
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { 
         write!(f, "{}", match self {
             Self::Default => "".to_string(),
+            Self::Null => "".to_string(),
             Self::Bool(v) => v.to_string(),
             Self::I8(v) => v.to_string(),
             Self::I16(v) => v.to_string(),
@@ -57,7 +564,18 @@ impl std::fmt::Display for Value{
             Self::U64(v) => v.to_string(),
             Self::F32(v) => v.to_string(),
             Self::F64(v) => v.to_string(),
-            Self::Str(v) => v.to_string()
+            Self::Str(v) => v.to_string(),
+            Self::IpAddr(v) => v.to_string(),
+            Self::Char(v) => v.to_string(),
+            Self::Duration(v) => format!("{:?}", v),
+            Self::Date(v) => format_date(*v),
+            Self::Timestamp(v) => format_timestamp(*v),
+            Self::Bytes(v) => format_hex(v),
+            Self::Decimal(v, scale) => format_decimal(*v, *scale),
+            Self::Enum(v) => v.to_string(),
+            Self::Uuid(v) => format_uuid(v),
+            Self::Json(v) => v.to_string(),
+            Self::Array(_) => JSValue::from(self).to_string()
         })
     }
 }
@@ -134,6 +652,30 @@ impl From<&str> for Value {
     }
 }
 
+impl From<char> for Value {
+    fn from(v: char) -> Self {
+        Value::Char(v)
+    }
+}
+
+impl From<IpAddr> for Value {
+    fn from(v: IpAddr) -> Self {
+        Value::IpAddr(v)
+    }
+}
+
+impl From<Duration> for Value {
+    fn from(v: Duration) -> Self {
+        Value::Duration(v)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(v: Vec<u8>) -> Self {
+        Value::Bytes(v)
+    }
+}
+
 impl TryFrom<JSValue> for Value {
     type Error = anyhow::Error;
 
@@ -165,6 +707,7 @@ impl From<Value> for JSValue {
         // convert to serde_json::Value
         match value {
             Value::Default => Self::Null,
+            Value::Null => Self::Null,
             Value::Bool(v) => Self::Bool(v),
             Value::I8(v) => Self::Number(JSNumber::from(v)),
             Value::I16(v) => Self::Number(JSNumber::from(v)),
@@ -182,7 +725,18 @@ impl From<Value> for JSValue {
                 Some(jv) => Self::Number(jv),
                 None => Self::Null
             },
-            Value::Str(v) => Self::String(v.to_string())
+            Value::Str(v) => Self::String(v.to_string()),
+            Value::IpAddr(v) => Self::String(v.to_string()),
+            Value::Char(v) => Self::String(v.to_string()),
+            Value::Duration(v) => Self::String(format!("{:?}", v)),
+            Value::Date(v) => Self::String(format_date(v)),
+            Value::Timestamp(v) => Self::String(format_timestamp(v)),
+            Value::Bytes(v) => Self::String(format_hex(&v)),
+            Value::Decimal(v, scale) => Self::String(format_decimal(v, scale)),
+            Value::Enum(v) => Self::Number(JSNumber::from(v)),
+            Value::Uuid(v) => Self::String(format_uuid(&v)),
+            Value::Json(v) => v,
+            Value::Array(v) => Self::Array(v.into_iter().map(Self::from).collect())
         }
     }
 }
@@ -192,6 +746,7 @@ impl From<&Value> for JSValue {
         // convert to serde_json::Value
         match value {
             Value::Default => Self::Null,
+            Value::Null => Self::Null,
             Value::Bool(v) => Self::Bool(*v),
             Value::I8(v) => Self::Number(JSNumber::from(*v)),
             Value::I16(v) => Self::Number(JSNumber::from(*v)),
@@ -209,7 +764,18 @@ impl From<&Value> for JSValue {
                 Some(jv) => Self::Number(jv),
                 None => Self::Null
             },
-            Value::Str(v) => Self::String(v.to_string())
+            Value::Str(v) => Self::String(v.to_string()),
+            Value::IpAddr(v) => Self::String(v.to_string()),
+            Value::Char(v) => Self::String(v.to_string()),
+            Value::Duration(v) => Self::String(format!("{:?}", v)),
+            Value::Date(v) => Self::String(format_date(*v)),
+            Value::Timestamp(v) => Self::String(format_timestamp(*v)),
+            Value::Bytes(v) => Self::String(format_hex(v)),
+            Value::Decimal(v, scale) => Self::String(format_decimal(*v, *scale)),
+            Value::Enum(v) => Self::Number(JSNumber::from(*v)),
+            Value::Uuid(v) => Self::String(format_uuid(v)),
+            Value::Json(v) => v.clone(),
+            Value::Array(v) => Self::Array(v.iter().map(Self::from).collect())
         }
     }
 }
@@ -221,6 +787,7 @@ impl Serialize for Value {
     {
         match self {
             Self::Default => serializer.serialize_none(),
+            Self::Null => serializer.serialize_none(),
             Self::Bool(v) => serializer.serialize_bool(*v),
             Self::I8(v) => serializer.serialize_i8(*v),
             Self::I16(v) => serializer.serialize_i16(*v),
@@ -232,14 +799,106 @@ impl Serialize for Value {
             Self::U64(v) => serializer.serialize_u64(*v),
             Self::F32(v) => serializer.serialize_f32(*v),
             Self::F64(v) => serializer.serialize_f64(*v),
-            Self::Str(v) => serializer.serialize_str(v)
+            Self::Str(v) => serializer.serialize_str(v),
+            Self::IpAddr(v) => serializer.collect_str(v),
+            Self::Char(v) => serializer.serialize_char(*v),
+            Self::Duration(v) => serializer.collect_str(&format_args!("{:?}", v)),
+            Self::Date(v) => serializer.serialize_str(&format_date(*v)),
+            Self::Timestamp(v) => serializer.serialize_str(&format_timestamp(*v)),
+            Self::Bytes(v) => serializer.serialize_str(&format_hex(v)),
+            Self::Decimal(v, scale) => serializer.serialize_str(&format_decimal(*v, *scale)),
+            Self::Enum(v) => serializer.serialize_u16(*v),
+            Self::Uuid(v) => serializer.serialize_str(&format_uuid(v)),
+            Self::Json(v) => v.serialize(serializer),
+            Self::Array(v) => serializer.collect_seq(v)
         }
     }
 }
 
+/// Implements `TryFrom<Value>` for a type by unwrapping a value variant.
+macro_rules! impl_try_from_value {
+    ($type:ty, $variant:ident) => {
+        impl TryFrom<Value> for $type {
+            type Error = anyhow::Error;
+
+            fn try_from(value: Value) -> Result<Self> {
+                match value {
+                    Value::$variant(v) => Ok(v),
+                    v => bail!("expected a {} value but got {:?}", stringify!($variant), v)
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_value!(bool, Bool);
+impl_try_from_value!(i8, I8);
+impl_try_from_value!(i16, I16);
+impl_try_from_value!(i32, I32);
+impl_try_from_value!(i64, I64);
+impl_try_from_value!(u8, U8);
+impl_try_from_value!(u16, U16);
+impl_try_from_value!(u32, U32);
+impl_try_from_value!(u64, U64);
+impl_try_from_value!(f32, F32);
+impl_try_from_value!(f64, F64);
+impl_try_from_value!(String, Str);
+impl_try_from_value!(IpAddr, IpAddr);
+impl_try_from_value!(char, Char);
+impl_try_from_value!(Duration, Duration);
+impl_try_from_value!(Vec<u8>, Bytes);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+    use std::collections::hash_map::DefaultHasher;
+
+    /// Hashes a value with the default hasher.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - Value to hash.
+    fn hash_of(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn hash_default_and_zero() {
+        assert_ne!(Value::Default, Value::U32(0));
+        assert_ne!(hash_of(&Value::Default), hash_of(&Value::U32(0)));
+        assert_ne!(hash_of(&Value::U32(0)), hash_of(&Value::U64(0)));
+
+        let mut histogram = HashMap::new();
+        for value in [Value::Default, Value::U32(0), Value::U32(0), Value::Default, Value::U32(0)] {
+            *histogram.entry(value).or_insert(0) += 1;
+        }
+        assert_eq!(2, histogram.len());
+        assert_eq!(Some(&2), histogram.get(&Value::Default));
+        assert_eq!(Some(&3), histogram.get(&Value::U32(0)));
+    }
+
+    #[test]
+    fn hash_float_bit_pattern() {
+        assert_eq!(Value::F64(f64::NAN), Value::F64(f64::NAN));
+        assert_eq!(hash_of(&Value::F64(f64::NAN)), hash_of(&Value::F64(f64::NAN)));
+        assert_eq!(hash_of(&Value::F32(f32::NAN)), hash_of(&Value::F32(f32::NAN)));
+        assert_ne!(Value::F64(0.0), Value::F64(-0.0));
+        assert_eq!(hash_of(&Value::F64(1.5)), hash_of(&Value::F64(1.5)));
+    }
+
+    #[test]
+    fn partial_cmp() {
+        assert!(Value::I32(-3) < Value::I32(2));
+        assert!(Value::U64(10) > Value::U64(9));
+        assert!(Value::F64(-0.0) < Value::F64(0.0));
+        assert!(Value::Str("abc".to_string()) < Value::Str("abd".to_string()));
+        assert_eq!(Some(std::cmp::Ordering::Equal), Value::Default.partial_cmp(&Value::Default));
+        assert_eq!(None, Value::I32(1).partial_cmp(&Value::I64(1)));
+        assert_eq!(None, Value::Default.partial_cmp(&Value::U32(0)));
+    }
     use serde_json::{Map as JSMap};
 
     #[test]
@@ -264,6 +923,33 @@ mod tests {
         assert_eq!("345.852", Value::F64(345.852).to_string());
         assert_eq!("-345.852", Value::F64(-345.852).to_string());
         assert_eq!("hello", Value::Str("hello".to_string()).to_string());
+        assert_eq!("127.0.0.1", Value::IpAddr("127.0.0.1".parse().unwrap()).to_string());
+        assert_eq!("::1", Value::IpAddr("::1".parse().unwrap()).to_string());
+        assert_eq!("\u{e9}", Value::Char('\u{e9}').to_string());
+        assert_eq!("1.5s", Value::Duration(Duration::from_millis(1500)).to_string());
+        assert_eq!("250ns", Value::Duration(Duration::from_nanos(250)).to_string());
+    }
+
+    #[test]
+    fn to_display_string_float_precision() {
+        assert_eq!("12.35", Value::F64(12.34567).to_display_string(Some(2)));
+        assert_eq!("2.000", Value::F32(2f32).to_display_string(Some(3)));
+        assert_eq!("-7", Value::F64(-6.5001).to_display_string(Some(0)));
+        assert_eq!("12.34567", Value::F64(12.34567).to_display_string(None));
+        assert_eq!("12", Value::I32(12).to_display_string(Some(2)));
+    }
+
+    #[test]
+    fn to_display_string_default() {
+        assert_eq!("", Value::Default.to_display_string(None));
+        assert_eq!("", Value::Default.to_display_string(Some(2)));
+        assert_eq!("true", Value::Bool(true).to_display_string(Some(2)));
+    }
+
+    #[test]
+    fn to_display_string_special_chars() {
+        let value = Value::Str("a \"quoted\", line\nbreak".to_string());
+        assert_eq!("a \"quoted\", line\nbreak", value.to_display_string(Some(2)));
     }
 
     #[test]
@@ -374,6 +1060,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn serialize_ip_addr() {
+        let expected = "\"10.1.2.3\"";
+        match serde_json::to_string(&Value::IpAddr("10.1.2.3".parse().unwrap())) {
+            Ok(v) => assert_eq!(expected, v),
+            Err(e) => panic!("expected {:?} but got error: {:?}", expected, e)
+        }
+    }
+
+    #[test]
+    fn serialize_char() {
+        let expected = "\"\u{1f980}\"";
+        match serde_json::to_string(&Value::Char('\u{1f980}')) {
+            Ok(v) => assert_eq!(expected, v),
+            Err(e) => panic!("expected {:?} but got error: {:?}", expected, e)
+        }
+    }
+
     #[test]
     fn try_from_js_u64_valid() {
         let expected = Value::U64(u64::MAX);
@@ -478,6 +1182,16 @@ mod tests {
         assert_eq!(Value::F64(-12245.321f64), Value::from(-12245.321f64));
     }
 
+    #[test]
+    fn from_char() {
+        assert_eq!(Value::Char('x'), 'x'.into());
+    }
+
+    #[test]
+    fn from_duration() {
+        assert_eq!(Value::Duration(Duration::from_secs(3)), Duration::from_secs(3).into());
+    }
+
     #[test]
     fn from_str() {
         assert_eq!(Value::Str("foo".to_string()), Value::from("foo"));
@@ -704,4 +1418,264 @@ mod tests {
     fn js_from_ref_str() {
         assert_eq!(JSValue::String("foo".to_string()), JSValue::from(&Value::Str("foo".to_string())));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn js_from_ref_ip_addr() {
+        let value = Value::IpAddr("2001:db8::1".parse().unwrap());
+        assert_eq!(JSValue::String("2001:db8::1".to_string()), JSValue::from(&value));
+    }
+
+    #[test]
+    fn try_from_value() -> Result<()> {
+        assert!(bool::try_from(Value::Bool(true))?);
+        assert_eq!(-3i16, i16::try_from(Value::I16(-3))?);
+        assert_eq!(42u32, u32::try_from(Value::U32(42))?);
+        assert_eq!(1.5f64, f64::try_from(Value::F64(1.5))?);
+        assert_eq!("abc", String::try_from(Value::Str("abc".to_string()))?);
+        assert_eq!(Duration::from_secs(2), Duration::try_from(Value::Duration(Duration::from_secs(2)))?);
+        match u32::try_from(Value::U64(42)) {
+            Ok(v) => panic!("expected error but got {:?}", v),
+            Err(e) => assert_eq!("expected a U32 value but got U64(42)", e.to_string())
+        }
+        match bool::try_from(Value::Default) {
+            Ok(v) => panic!("expected error but got {:?}", v),
+            Err(e) => assert_eq!("expected a Bool value but got Default", e.to_string())
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn json_round_trip() -> Result<()> {
+        let values = [
+            (Value::Default, FieldType::I32),
+            (Value::Bool(true), FieldType::Bool),
+            (Value::I8(-8), FieldType::I8),
+            (Value::I16(-1600), FieldType::I16),
+            (Value::I32(-320000), FieldType::I32),
+            (Value::I64(-6400000000), FieldType::I64),
+            (Value::U8(8), FieldType::U8),
+            (Value::U16(1600), FieldType::U16),
+            (Value::U32(320000), FieldType::U32),
+            (Value::U64(6400000000), FieldType::U64),
+            (Value::F32(1.5), FieldType::F32),
+            (Value::F64(-2.25), FieldType::F64),
+            (Value::Str("abc".to_string()), FieldType::Str(5)),
+            (Value::IpAddr("10.0.0.1".parse()?), FieldType::IpAddr),
+            (Value::Char('x'), FieldType::Char),
+            (Value::Duration(Duration::from_millis(1500)), FieldType::Duration),
+            (Value::Date(19782), FieldType::Date),
+            (Value::Date(-1), FieldType::Date),
+            (Value::Timestamp(1709214330250), FieldType::Timestamp),
+            (Value::Bytes(vec![0, 171, 255]), FieldType::Bytes(5))
+        ];
+        for (value, field_type) in values {
+            let json = value.clone().into_json();
+            assert_eq!(value, Value::from_json_typed(json, &field_type)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn date_timestamp_display() {
+        assert_eq!("2024-02-29", Value::Date(19782).to_string());
+        assert_eq!("1969-12-31", Value::Date(-1).to_string());
+        assert_eq!("2147483647", Value::Date(i32::MAX).to_string());
+        assert_eq!("2024-02-29T13:45:30.250Z", Value::Timestamp(1709214330250).to_string());
+        assert_eq!("-9223372036854775808", Value::Timestamp(i64::MIN).to_string());
+        assert_eq!(JSValue::from("2024-02-29"), JSValue::from(Value::Date(19782)));
+        assert_eq!(Value::Date(19782), Value::from_json_typed(JSValue::from(19782), &FieldType::Date).unwrap());
+        assert_eq!(Value::Timestamp(5), Value::from_json_typed(JSValue::from(5), &FieldType::Timestamp).unwrap());
+    }
+
+    #[test]
+    fn as_datetime() {
+        let expected = DateTime::parse_from_rfc3339("2024-02-29T13:45:30.250Z").unwrap().with_timezone(&Utc);
+        assert_eq!(Some(expected), Value::Timestamp(1709214330250).as_datetime());
+        assert_eq!(None, Value::Timestamp(i64::MAX).as_datetime());
+        assert_eq!(None, Value::I64(1709214330250).as_datetime());
+        assert_eq!(None, Value::Date(19782).as_datetime());
+    }
+
+    #[test]
+    fn as_date() {
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 2, 29), Value::Date(19782).as_date());
+        assert_eq!(NaiveDate::from_ymd_opt(1970, 1, 1), Value::Date(0).as_date());
+        assert_eq!(None, Value::Date(i32::MAX).as_date());
+        assert_eq!(None, Value::Timestamp(0).as_date());
+    }
+
+    #[test]
+    fn typed_getters() {
+        assert_eq!(Some(-8), Value::I8(-8).as_i64());
+        assert_eq!(Some(-1234), Value::I16(-1234).as_i64());
+        assert_eq!(Some(i32::MIN as i64), Value::I32(i32::MIN).as_i64());
+        assert_eq!(Some(i64::MAX), Value::I64(i64::MAX).as_i64());
+        assert_eq!(Some(8), Value::U8(8).as_u64());
+        assert_eq!(Some(1234), Value::U16(1234).as_u64());
+        assert_eq!(Some(u32::MAX as u64), Value::U32(u32::MAX).as_u64());
+        assert_eq!(Some(u64::MAX), Value::U64(u64::MAX).as_u64());
+        assert_eq!(Some(1.5), Value::F32(1.5).as_f64());
+        assert_eq!(Some(-2.25), Value::F64(-2.25).as_f64());
+        assert_eq!(Some("foo"), Value::Str("foo".to_string()).as_str());
+        assert_eq!(Some(true), Value::Bool(true).as_bool());
+    }
+
+    #[test]
+    fn typed_getters_with_mismatch() {
+        assert_eq!(None, Value::U8(8).as_i64());
+        assert_eq!(None, Value::F64(1.0).as_i64());
+        assert_eq!(None, Value::I8(8).as_u64());
+        assert_eq!(None, Value::I64(1).as_f64());
+        assert_eq!(None, Value::Char('a').as_str());
+        assert_eq!(None, Value::U8(1).as_bool());
+        for value in [Value::Default, Value::Null] {
+            assert_eq!(None, value.as_i64());
+            assert_eq!(None, value.as_u64());
+            assert_eq!(None, value.as_f64());
+            assert_eq!(None, value.as_str());
+            assert_eq!(None, value.as_bool());
+        }
+    }
+
+    #[test]
+    fn from_json_typed_with_invalid_values() {
+        let cases = [
+            (JSValue::from(300), FieldType::U8, "can't convert JSON value 300 into U8"),
+            (JSValue::from(-1), FieldType::U32, "can't convert JSON value -1 into U32"),
+            (JSValue::from(1.5), FieldType::I64, "can't convert JSON value 1.5 into I64"),
+            (JSValue::from("abc"), FieldType::Bool, "can't convert JSON value \"abc\" into Bool"),
+            (JSValue::from(true), FieldType::Str(5), "can't convert JSON value true into Str(5)")
+        ];
+        for (json, field_type, expected) in cases {
+            match Value::from_json_typed(json, &field_type) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn coerce() -> Result<()> {
+        assert_eq!(Value::U32(4_000_000_000), Value::I64(4_000_000_000).coerce(&FieldType::U32)?);
+        assert_eq!(Value::I8(-5), Value::F64(-5.0).coerce(&FieldType::I8)?);
+        assert_eq!(Value::F64(1.5), Value::F32(1.5).coerce(&FieldType::F64)?);
+        assert_eq!(Value::F32(0.25), Value::F64(0.25).coerce(&FieldType::F32)?);
+        assert_eq!(Value::F64(42.0), Value::U16(42).coerce(&FieldType::F64)?);
+        assert_eq!(Value::Str("1234".to_string()), Value::I32(1234).coerce(&FieldType::Str(4))?);
+        assert_eq!(Value::Str("2.5".to_string()), Value::F32(2.5).coerce(&FieldType::Str(10))?);
+
+        // matching, default and null values are kept as is
+        assert_eq!(Value::I32(7), Value::I32(7).coerce(&FieldType::I32)?);
+        assert_eq!(Value::Default, Value::Default.coerce(&FieldType::U8)?);
+        assert_eq!(Value::Null, Value::Null.coerce(&FieldType::U8)?);
+        Ok(())
+    }
+
+    #[test]
+    fn coerce_with_invalid_values() {
+        let cases = [
+            (Value::I64(-1), FieldType::U32, "can't coerce value I64(-1) into U32"),
+            (Value::I64(5_000_000_000), FieldType::U32, "can't coerce value I64(5000000000) into U32"),
+            (Value::F64(1.5), FieldType::I64, "can't coerce value F64(1.5) into I64"),
+            (Value::F64(0.1), FieldType::F32, "can't coerce value F64(0.1) into F32"),
+            (Value::I64(9_007_199_254_740_993), FieldType::F64, "can't coerce value I64(9007199254740993) into F64"),
+            (Value::I32(12345), FieldType::Str(4), "can't coerce value I32(12345) into Str(4)"),
+            (Value::Bool(true), FieldType::I8, "can't coerce value Bool(true) into I8"),
+            (Value::Str("12".to_string()), FieldType::I32, "can't coerce value Str(\"12\") into I32")
+        ];
+        for (value, field_type, expected) in cases {
+            match value.coerce(&field_type) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn decimal_to_string() {
+        assert_eq!("123.45", Value::Decimal(12345, 2).decimal_to_string());
+        assert_eq!("-0.05", Value::Decimal(-5, 2).decimal_to_string());
+        assert_eq!("0.000", Value::Decimal(0, 3).decimal_to_string());
+        assert_eq!("42", Value::Decimal(42, 0).decimal_to_string());
+        assert_eq!("-9223372036854775.808", Value::Decimal(i64::MIN, 3).decimal_to_string());
+        assert_eq!("12", Value::I32(12).decimal_to_string());
+        assert_eq!("123.45", Value::Decimal(12345, 2).to_string());
+        assert_eq!(JSValue::from("123.45"), JSValue::from(Value::Decimal(12345, 2)));
+    }
+
+    #[test]
+    fn decimal_eq_and_ord() {
+        assert_eq!(Value::Decimal(12345, 2), Value::Decimal(12345, 2));
+        assert_ne!(Value::Decimal(12345, 2), Value::Decimal(12345, 3));
+        assert!(Value::Decimal(100, 2) < Value::Decimal(101, 2));
+        assert_eq!(None, Value::Decimal(100, 2).partial_cmp(&Value::Decimal(100, 3)));
+    }
+
+    #[test]
+    fn decimal_from_json_typed() -> Result<()> {
+        let field_type = FieldType::Decimal{scale: 2};
+        assert_eq!(Value::Decimal(12345, 2), Value::from_json_typed(JSValue::from("123.45"), &field_type)?);
+        assert_eq!(Value::Decimal(1250, 2), Value::from_json_typed(JSValue::from(12.5), &field_type)?);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_uuid() -> Result<()> {
+        let value = Value::parse_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
+        let expected = [
+            0x67u8, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f,
+            0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8
+        ];
+        assert_eq!(Value::Uuid(expected), value);
+        assert_eq!("67e55044-10b1-426f-9247-bb680e5fe0c8", value.uuid_to_hyphenated());
+        assert_eq!("67e55044-10b1-426f-9247-bb680e5fe0c8", value.to_string());
+        assert_eq!(JSValue::from("67e55044-10b1-426f-9247-bb680e5fe0c8"), JSValue::from(&value));
+        assert_eq!(value, Value::parse_uuid("67E55044-10B1-426F-9247-BB680E5FE0C8")?);
+        for s in [
+            "",
+            "67e5504410b1426f9247bb680e5fe0c8",
+            "67e55044-10b1-426f-9247-bb680e5fe0c",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8a",
+            "67e5504-410b1-426f-9247-bb680e5fe0c8",
+            "67e55044-10b1-426f-9247-bb680e5fe0cg",
+            "+7e55044-10b1-426f-9247-bb680e5fe0c8",
+            "67e55044-10b1-426f-9247-bb680e5fe0é"
+        ] {
+            match Value::parse_uuid(s) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(format!("invalid UUID \"{}\"", s), e.to_string())
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn enum_json_and_coerce() -> Result<()> {
+        let field_type = FieldType::Enum{variants: vec!["active".to_string(), "paused".to_string()]};
+        assert_eq!(Value::Enum(1), Value::from_json_typed(JSValue::from("paused"), &field_type)?);
+        assert_eq!(Value::Enum(1), Value::from_json_typed(JSValue::from(1), &field_type)?);
+        assert_eq!(JSValue::from(1), JSValue::from(Value::Enum(1)));
+        assert_eq!("1", Value::Enum(1).to_string());
+        assert_eq!(Value::Enum(1), Value::U8(1).coerce(&field_type)?);
+        match Value::U8(2).coerce(&field_type) {
+            Ok(v) => panic!("expected error but got {:?}", v),
+            Err(e) => assert_eq!("can't coerce value U8(2) into Enum { variants: [\"active\", \"paused\"] }", e.to_string())
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn array_json_and_display() -> Result<()> {
+        let field_type = FieldType::Array{element: Box::new(FieldType::U8), len: 3};
+        let value = Value::Array(vec![Value::U8(10), Value::U8(20), Value::U8(30)]);
+        assert_eq!(value, Value::from_json_typed(serde_json::json!([10, 20, 30]), &field_type)?);
+        assert_eq!(serde_json::json!([10, 20, 30]), JSValue::from(&value));
+        assert_eq!("[10,20,30]", serde_json::to_string(&value)?);
+        assert_eq!("[10,20,30]", value.to_string());
+        match Value::from_json_typed(serde_json::json!([10, 256]), &field_type) {
+            Ok(v) => panic!("expected error but got {:?}", v),
+            Err(e) => assert_eq!("can't convert JSON value 256 into U8", e.to_string())
+        }
+        Ok(())
+    }
+}
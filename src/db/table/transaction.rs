@@ -0,0 +1,204 @@
+use anyhow::{bail, Result};
+use std::fs::OpenOptions;
+use std::io::{Seek, SeekFrom, Write};
+use crate::error::TableError;
+use super::Table;
+use super::record::Record;
+
+/// Buffers record appends and writes them into a table on commit, either
+/// every buffered record lands on the table or none does. Dropping the
+/// transaction without committing discards the buffered records.
+pub struct Transaction<'a> {
+    /// Table to append records into.
+    table: &'a mut Table,
+
+    /// Encoded buffered records.
+    buf: Vec<u8>,
+
+    /// Buffered record count.
+    count: u64
+}
+
+impl<'a> Transaction<'a> {
+    /// Creates a new transaction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `table` - Table to append records into.
+    pub fn new(table: &'a mut Table) -> Result<Self> {
+        if table.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if table.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !table.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+        Ok(Self{
+            table,
+            buf: Vec::new(),
+            count: 0
+        })
+    }
+
+    /// Buffers a record to be appended on commit. The record is encoded
+    /// right away so invalid records are reported before committing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `record` - Record to append.
+    pub fn append(&mut self, record: &Record) -> Result<()> {
+        let mut buf = Vec::with_capacity(self.table.record_size() as usize);
+        if self.table.header.user_flags {
+            buf.push(0u8);
+        }
+        self.table.record_header.write_record(&mut buf, record)?;
+        self.buf.extend_from_slice(&buf);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Returns the buffered record count.
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns `true` when there are no buffered records.
+    pub fn is_empty(&self) -> bool {
+        self.count < 1
+    }
+
+    /// Writes the buffered records into the table and returns the
+    /// committed record count. The records are written first and the
+    /// record count is updated last, so the records only become visible
+    /// once every one of them was written. The table file is truncated
+    /// back to its previous size on error.
+    pub fn commit(mut self) -> Result<u64> {
+        if self.count < 1 {
+            return Ok(0);
+        }
+        let old_count = self.table.header.record_count;
+        let old_checksum = self.table.header.checksum;
+        let old_size = self.table.calc_record_pos(old_count);
+        if let Err(e) = self.write_records(old_size) {
+            // restore the table as it was before the commit
+            self.table.header.record_count = old_count;
+            self.table.header.checksum = old_checksum;
+            let file = OpenOptions::new().write(true).open(&self.table.path)?;
+            file.set_len(old_size)?;
+            self.table.save_headers()?;
+            return Err(e);
+        }
+        Ok(self.count)
+    }
+
+    /// Discards the buffered records leaving the table unchanged.
+    pub fn rollback(self) {}
+
+    /// Writes the buffered records at a position and then saves the
+    /// table headers with the new record count.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pos` - Table file position to write the records at.
+    fn write_records(&mut self, pos: u64) -> Result<()> {
+        let mut writer = self.table.new_writer(false)?;
+        writer.seek(SeekFrom::Start(pos))?;
+        writer.write_all(&self.buf)?;
+        writer.flush()?;
+        self.table.header.record_count += self.count;
+        self.table.header.checksum = None;
+        self.table.save_headers_into(&mut writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::test_helper::*;
+
+    #[test]
+    fn commit() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut record = table.record_header.new_record()?;
+            record.set("foo", 11i32.into())?;
+            record.set("bar", "abc".into())?;
+            {
+                let mut transaction = Transaction::new(table)?;
+                transaction.append(&record)?;
+                transaction.append(&records[0])?;
+                assert_eq!(2, transaction.len());
+
+                // buffered records aren't visible before commit
+                let other = Table::from_file(transaction.table.path.clone())?;
+                assert_eq!(4, other.header.record_count);
+                assert_eq!(2, transaction.commit()?);
+            }
+
+            // test
+            assert_eq!(6, table.header.record_count);
+            let other = Table::from_file(table.path.clone())?;
+            assert_eq!(6, other.header.record_count);
+            assert_eq!(Some(&records[3]), other.record(3)?.as_ref());
+            assert_eq!(Some(&record), other.record(4)?.as_ref());
+            assert_eq!(Some(&records[0]), other.record(5)?.as_ref());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rollback() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let expected = std::fs::read(&table.path)?;
+            let mut record = table.record_header.new_record()?;
+            record.set("bar", "abc".into())?;
+
+            // test rollback
+            let mut transaction = Transaction::new(table)?;
+            transaction.append(&record)?;
+            transaction.rollback();
+            assert_eq!(4, table.header.record_count);
+            assert_eq!(expected, std::fs::read(&table.path)?);
+
+            // test drop without commit
+            {
+                let mut transaction = Transaction::new(table)?;
+                transaction.append(&record)?;
+                transaction.append(&record)?;
+            }
+            assert_eq!(4, table.header.record_count);
+            assert_eq!(expected, std::fs::read(&table.path)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn append_invalid_record() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut record = table.record_header.new_record()?;
+            record.set("bar", "too long value".into())?;
+
+            // test
+            let mut transaction = Transaction::new(table)?;
+            match transaction.append(&record) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(
+                    "error saving field \"bar\": string value size (14 bytes) is bigger than field size (5 bytes)",
+                    e.to_string()
+                )
+            }
+            assert!(transaction.is_empty());
+            assert_eq!(0, transaction.commit()?);
+            assert_eq!(4, table.header.record_count);
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,263 @@
+use anyhow::{bail, Result};
+use sha3::{Digest, Sha3_256};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write, BufWriter};
+use crate::error::TableError;
+use crate::db::indexer::header::HASH_SIZE;
+use super::Table;
+use super::header::LEGACY_VERSION;
+use super::record::Record;
+
+/// Appends records into a table flushing the pending records and the
+/// table headers every `flush_every` appends and on [Self::finish]. Pending
+/// records are also flushed on drop but any error is ignored, so use
+/// [Self::finish] to handle it. It also
+/// keeps a running hash of the record data region so the table checksum
+/// can be stored without a separate full scan.
+pub struct TableWriter<'a> {
+    /// Table to append records into.
+    table: &'a mut Table,
+
+    /// Table file writer.
+    writer: BufWriter<File>,
+
+    /// Append count between flushes, `0` means only flush on finish or drop.
+    pub flush_every: usize,
+
+    /// Appended records since the last flush.
+    pending: usize,
+
+    /// Running hash over the record data region.
+    hasher: Sha3_256
+}
+
+impl<'a> TableWriter<'a> {
+    /// Creates a new table writer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `table` - Table to append records into.
+    /// * `flush_every` - Append count between flushes, `0` means only flush on finish or drop.
+    pub fn new(table: &'a mut Table, flush_every: usize) -> Result<Self> {
+        if table.record_header.len() < 1 {
+            bail!(TableError::NoFields)
+        }
+        if table.header.compressed {
+            bail!(TableError::Compressed)
+        }
+        if !table.record_header.is_fixed_length() {
+            bail!(TableError::VariableLength)
+        }
+
+        // seed the running hash with the existing records
+        let mut hasher = Sha3_256::new();
+        if table.header.record_count > 0 {
+            let mut reader = table.new_reader()?;
+            reader.seek(SeekFrom::Start(table.calc_record_pos(0)))?;
+            let size = table.calc_record_pos(table.header.record_count) - table.calc_record_pos(0);
+            io::copy(&mut reader.take(size), &mut hasher)?;
+        }
+
+        // move to the append position once, header saves jump back to it
+        let mut writer = table.new_writer(false)?;
+        writer.seek(SeekFrom::Start(table.calc_record_pos(table.header.record_count)))?;
+        Ok(Self{
+            table,
+            writer,
+            flush_every,
+            pending: 0,
+            hasher
+        })
+    }
+
+    /// Appends a record into the table and returns the record index.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `record` - Record to append.
+    pub fn append(&mut self, record: &Record) -> Result<u64> {
+        let mut buf = Vec::with_capacity(self.table.record_size() as usize);
+        if self.table.header.user_flags {
+            buf.push(0u8);
+        }
+        self.table.record_header.write_record(&mut buf, record)?;
+
+        // write the record bytes and invalidate the stored checksum, the
+        // writer is always at the append position so it's not seeked to
+        // keep the writes buffered
+        let index = self.table.header.record_count;
+        self.writer.write_all(&buf)?;
+        self.hasher.update(&buf);
+        self.table.header.record_count += 1;
+        self.table.header.checksum = None;
+        self.pending += 1;
+        if self.flush_every > 0 && self.pending >= self.flush_every {
+            self.flush()?;
+        }
+        Ok(index)
+    }
+
+    /// Flushes the pending records and saves the table headers.
+    pub fn flush(&mut self) -> Result<()> {
+        self.table.save_headers_into(&mut self.writer)?;
+        self.pending = 0;
+        Ok(())
+    }
+
+    /// Flushes the pending records and closes the writer.
+    pub fn finish(mut self) -> Result<()> {
+        self.flush()
+    }
+
+    /// Stores the record data region checksum into the table header,
+    /// flushes and closes the writer, then returns the checksum. Version 1
+    /// table headers can't store a checksum so these fail instead.
+    pub fn finalize_checksum(mut self) -> Result<[u8; HASH_SIZE]> {
+        if self.table.header.get_version() == LEGACY_VERSION {
+            bail!("checksum can't be stored on version {} tables", LEGACY_VERSION);
+        }
+        let checksum: [u8; HASH_SIZE] = self.hasher.clone().finalize().into();
+        self.table.header.checksum = Some(checksum);
+        self.flush()?;
+        Ok(checksum)
+    }
+}
+
+impl Drop for TableWriter<'_> {
+    fn drop(&mut self) {
+        // errors can't be returned on drop, finish should be used instead
+        if self.pending > 0 {
+            let _ = self.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::table::test_helper::*;
+    use crate::db::table::header::Header as TableHeader;
+    use crate::test_helper::create_file_with_bytes;
+    use crate::traits::ByteSized;
+
+    #[test]
+    fn append_with_flush_every() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            add_fields(&mut table.record_header)?;
+            table.load_or_create(false, true)?;
+            let mut record = table.record_header.new_record()?;
+            record.set("bar", "abc".into())?;
+            {
+                let mut writer = TableWriter::new(table, 10)?;
+                for i in 0..15 {
+                    record.set("foo", (i as i32).into())?;
+                    assert_eq!(i, writer.append(&record)?);
+                }
+
+                // flushed records are visible from a separate handle
+                let mut other = Table::new(writer.table.path.clone(), "")?;
+                let mut reader = other.new_reader()?;
+                other.load_headers_from(&mut reader)?;
+                assert_eq!(10, other.header.record_count);
+                record.set("foo", 9i32.into())?;
+                assert_eq!(Some(&record), other.record(9)?.as_ref());
+                assert!(other.record(10)?.is_none());
+            }
+
+            // the tail is flushed on drop
+            let mut other = Table::new(table.path.clone(), "")?;
+            let mut reader = other.new_reader()?;
+            other.load_headers_from(&mut reader)?;
+            assert_eq!(15, other.header.record_count);
+            record.set("foo", 14i32.into())?;
+            assert_eq!(Some(&record), other.record(14)?.as_ref());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn finish() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            add_fields(&mut table.record_header)?;
+            table.load_or_create(false, true)?;
+            let records = fake_records()?;
+            let mut writer = TableWriter::new(table, 0)?;
+            for record in records.iter() {
+                writer.append(record)?;
+            }
+
+            // test
+            writer.finish()?;
+            let other = Table::from_file(table.path.clone())?;
+            assert_eq!(records.len() as u64, other.header.record_count);
+            for (index, record) in records.iter().enumerate() {
+                assert_eq!(Some(record), other.record(index as u64)?.as_ref());
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn finalize_checksum() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            add_fields(&mut table.record_header)?;
+            table.load_or_create(false, true)?;
+            let records = fake_records()?;
+            let mut writer = TableWriter::new(table, 2)?;
+            for record in records.iter() {
+                writer.append(record)?;
+            }
+
+            // test
+            let checksum = writer.finalize_checksum()?;
+            let other = Table::from_file(table.path.clone())?;
+            assert_eq!(Some(checksum), other.header.checksum);
+            assert_eq!(checksum, other.data_checksum()?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn finalize_checksum_on_legacy_version() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            let records = create_fake_table(&table.path, false)?;
+            let buf = std::fs::read(&table.path)?;
+
+            // rebuild the table file using a version 1 header
+            let mut legacy = buf[..TableHeader::LEGACY_BYTES].to_vec();
+            legacy[14] = 1;
+            legacy.extend_from_slice(&buf[TableHeader::BYTES..]);
+            create_file_with_bytes(&table.path, &legacy)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut writer = TableWriter::new(table, 0)?;
+            writer.append(&records[0])?;
+
+            // test
+            match writer.finalize_checksum() {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!("checksum can't be stored on version 1 tables", e.to_string())
+            }
+            let other = Table::from_file(table.path.clone())?;
+            assert_eq!(LEGACY_VERSION, other.header.get_version());
+            assert_eq!(5, other.header.record_count);
+            assert_eq!(Some(&records[0]), other.record(4)?.as_ref());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn finalize_checksum_on_existing_records() {
+        with_tmpdir_and_table(&|_, table| -> Result<()> {
+            create_fake_table(&table.path, false)?;
+            table.load_headers_from(&mut table.new_reader()?)?;
+            let mut writer = TableWriter::new(table, 0)?;
+            writer.append(&fake_records()?[0])?;
+
+            // test
+            let checksum = writer.finalize_checksum()?;
+            assert_eq!(5, table.header.record_count);
+            assert_eq!(checksum, table.data_checksum()?);
+            Ok(())
+        });
+    }
+}
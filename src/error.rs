@@ -13,6 +13,41 @@ pub enum ParseError {
     InvalidValue,
     #[error("retry limit reached")]
     RetryLimit,
+    #[error("\"{}\" is not a valid number", .0)]
+    NotNumeric(String),
+    #[error("\"{}\" is out of range", .0)]
+    OutOfRange(String),
+    #[error("size mismatch, expected {} bytes but found {} bytes", .expected, .found)]
+    SizeMismatch{
+        expected: usize,
+        found: usize
+    },
+    #[error(
+        "the index has {} records up to byte {} but the input file is {} bytes long, the input file must be reindexed",
+        .indexed_count, .indexed_size, .input_size
+    )]
+    InputLengthMismatch{
+        indexed_count: u64,
+        indexed_size: u64,
+        input_size: u64
+    },
+    #[error("record size {} bytes exceeds the max record size {} bytes", .size, .max)]
+    RecordTooBig{
+        size: u64,
+        max: u64
+    },
+    #[error(
+        "header field count mismatch the record value count, expected {} values but got {}",
+        .expected, .got
+    )]
+    FieldCountMismatch{
+        expected: usize,
+        got: usize
+    },
+    #[error("unexpected end of file while reading field \"{}\"", .0)]
+    UnexpectedEof(String),
+    #[error("invalid UTF-8 string value: {}", .0)]
+    InvalidUtf8(#[source] std::string::FromUtf8Error),
     #[error("{}", .0)]
     Other(String)
 }
@@ -43,6 +78,10 @@ pub enum IndexError {
 pub enum TableError {
     #[error("the table doesn't have any fields")]
     NoFields,
+    #[error("random access isn't supported on compressed tables")]
+    Compressed,
+    #[error("random access isn't supported on tables with variable length fields")]
+    VariableLength,
     #[error("unavailable due status \"{}\"", .0)]
     Unavailable(TableStatus)
 }
\ No newline at end of file
@@ -6,13 +6,25 @@ use path_absolutize::Absolutize;
 use regex::Regex;
 use std::fs::{File, OpenOptions};
 use std::io::{Seek, SeekFrom, Read, Write, BufReader, BufWriter};
-use std::path::PathBuf;
+use std::num::IntErrorKind;
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use sha2::Sha256;
 use sha3::{Digest, Sha3_256};
 use db::indexer::header::HASH_SIZE;
 use anyhow::{bail, Result};
+use error::ParseError;
 
 const BUF_SIZE: u64 = 4096;
 
+/// Chunk size hashed by each task on [generate_hash_parallel].
+pub const PARALLEL_HASH_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Min file size hashed in parallel by [generate_hash_parallel], smaller
+/// files are hashed by [generate_hash].
+pub const PARALLEL_HASH_THRESHOLD: u64 = 4 * PARALLEL_HASH_CHUNK_SIZE;
+
 /// Fill function action.
 #[derive(Debug, PartialEq)]
 pub enum FillAction {
@@ -40,6 +52,39 @@ pub fn file_size(path: &PathBuf) -> Result<u64> {
     Ok(reader.stream_position()?)
 }
 
+/// Returns true when both paths resolve to the same file, so aliases like
+/// `dir/../dir/file` or symlinks are detected. A path that doesn't exist
+/// yet is resolved by using its parent directory.
+/// 
+/// # Arguments
+/// 
+/// * `a` - File path.
+/// * `b` - File path to compare with.
+pub fn is_same_file(a: &PathBuf, b: &PathBuf) -> bool {
+    match (resolve_path(a), resolve_path(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b
+    }
+}
+
+/// Resolves a file path into its canonical form, the parent directory is
+/// resolved instead when the file doesn't exist.
+/// 
+/// # Arguments
+/// 
+/// * `path` - File path.
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+    if let Ok(v) = path.canonicalize() {
+        return Some(v);
+    }
+    let name = path.file_name()?;
+    let parent = match path.parent() {
+        Some(v) if !v.as_os_str().is_empty() => v,
+        _ => Path::new(".")
+    };
+    parent.canonicalize().ok().map(|v| v.join(name))
+}
+
 /// Fill a file with zero byte until the target size or ignore if
 /// bigger. Return true if file is bigger.
 /// 
@@ -49,6 +94,19 @@ pub fn file_size(path: &PathBuf) -> Result<u64> {
 /// * `target_size` - Target file size in bytes.
 /// * `truncate` - If `true` then it truncates de file and fill it.
 pub fn fill_file(path: &PathBuf, target_size: u64, truncate: bool) -> std::io::Result<FillAction> {
+    fill_file_with(path, target_size, truncate, 0u8)
+}
+
+/// Fill a file with a byte until the target size or ignore if
+/// bigger. Return true if file is bigger.
+/// 
+/// # Arguments
+/// 
+/// * `path` - File path to fill.
+/// * `target_size` - Target file size in bytes.
+/// * `truncate` - If `true` then it truncates de file and fill it.
+/// * `fill_byte` - Byte value used to fill the file.
+pub fn fill_file_with(path: &PathBuf, target_size: u64, truncate: bool, fill_byte: u8) -> std::io::Result<FillAction> {
     let mut action = FillAction::Fill;
     let file = if truncate {
         OpenOptions::new()
@@ -86,9 +144,9 @@ pub fn fill_file(path: &PathBuf, target_size: u64, truncate: bool) -> std::io::R
         }
     }
 
-    // fill file with zeros until target size is match
+    // fill file with the fill byte until target size is match
     let buf_size = 4096u64;
-    let buf = [0u8; 4096];
+    let buf = [fill_byte; 4096];
     let mut wrt = BufWriter::new(file);
     while size + buf_size < target_size {
         wrt.write_all(&buf)?;
@@ -104,12 +162,207 @@ pub fn fill_file(path: &PathBuf, target_size: u64, truncate: bool) -> std::io::R
     Ok(action)
 }
 
-/// Generates a hash value from a file contents.
+/// Hash algorithms supported by [generate_hash_with].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum HashAlgorithm {
+    Sha3_256,
+    Sha256,
+    Blake3
+}
+
+/// Generates a hash value from a reader contents using a hash algorithm.
 /// 
 /// # Arguments
 /// 
-/// * `path` - File path.
+/// * `reader` - Byte reader.
+/// * `algorithm` - Hash algorithm.
+pub fn generate_hash_with(reader: &mut impl Read, algorithm: HashAlgorithm) -> std::io::Result<Vec<u8>> {
+    let hash = match algorithm {
+        HashAlgorithm::Sha3_256 => {
+            let mut hasher = Sha3_256::new();
+            std::io::copy(reader, &mut hasher)?;
+            hasher.finalize().to_vec()
+        },
+        HashAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            std::io::copy(reader, &mut hasher)?;
+            hasher.finalize().to_vec()
+        },
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            std::io::copy(reader, &mut hasher)?;
+            hasher.finalize().as_bytes().to_vec()
+        }
+    };
+    Ok(hash)
+}
+
+/// Generates a SHA3-256 hash value from a reader contents.
+/// 
+/// # Arguments
+/// 
+/// * `reader` - Byte reader.
 pub fn generate_hash(reader: &mut impl Read) -> std::io::Result<[u8; HASH_SIZE]> {
+    generate_hash_with_progress(reader, &mut |_| {})
+}
+
+/// Generates a SHA3-256 hash value from a reader contents while reporting
+/// the cumulative byte count read after every chunk of up to `BUF_SIZE`
+/// bytes. The digest is the same as [generate_hash].
+/// 
+/// # Arguments
+/// 
+/// * `reader` - Byte reader.
+/// * `progress` - Function called with the cumulative byte count read.
+pub fn generate_hash_with_progress(reader: &mut impl Read, progress: &mut impl FnMut(u64)) -> std::io::Result<[u8; HASH_SIZE]> {
+    generate_hash_chunked(reader, BUF_SIZE as usize, progress)
+}
+
+/// Same as [generate_hash] but it reads the contents in chunks of
+/// `buf_size` bytes, the digest doesn't depend on the chunk size.
+/// 
+/// # Arguments
+/// 
+/// * `reader` - Byte reader.
+/// * `buf_size` - Chunk byte size, must be bigger than zero.
+pub fn generate_hash_buffered(reader: &mut impl Read, buf_size: usize) -> std::io::Result<[u8; HASH_SIZE]> {
+    generate_hash_chunked(reader, buf_size, &mut |_| {})
+}
+
+/// Generates a SHA3-256 hash value from a reader contents by reading
+/// chunks of `buf_size` bytes into a single buffer and reporting the
+/// cumulative byte count read after every chunk.
+/// 
+/// # Arguments
+/// 
+/// * `reader` - Byte reader.
+/// * `buf_size` - Chunk byte size.
+/// * `progress` - Function called with the cumulative byte count read.
+fn generate_hash_chunked(reader: &mut impl Read, buf_size: usize, progress: &mut impl FnMut(u64)) -> std::io::Result<[u8; HASH_SIZE]> {
+    if buf_size < 1 {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "hash buffer size can't be zero"));
+    }
+    let mut hasher = Sha3_256::new();
+    let mut chunk = vec![0u8; buf_size];
+    let mut total = 0u64;
+
+    loop {
+        // fill the chunk unless the contents end
+        let mut bytes_count = 0;
+        while bytes_count < buf_size {
+            match reader.read(&mut chunk[bytes_count..]) {
+                Ok(0) => break,
+                Ok(n) => bytes_count += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            }
+        }
+        if bytes_count == 0 {
+            break;
+        }
+        hasher.update(&chunk[..bytes_count]);
+        total += bytes_count as u64;
+        progress(total);
+        if bytes_count < buf_size {
+            break;
+        }
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Generates a SHA3-256 tree hash value from a file contents by hashing
+/// chunks of [PARALLEL_HASH_CHUNK_SIZE] bytes in parallel. The digest is
+/// the SHA3-256 hash of `<file_size:8><chunk_hash_0:32>..<chunk_hash_N:32>`,
+/// so it doesn't depend on the thread count but it's distinct from the
+/// [generate_hash] digest. Files smaller than [PARALLEL_HASH_THRESHOLD]
+/// are hashed by [generate_hash] instead.
+/// 
+/// # Arguments
+/// 
+/// * `path` - File path.
+/// * `threads` - Thread count, 0 to use as many threads as CPUs.
+pub fn generate_hash_parallel(path: &PathBuf, threads: usize) -> Result<[u8; HASH_SIZE]> {
+    generate_hash_parallel_with(path, threads, PARALLEL_HASH_CHUNK_SIZE, PARALLEL_HASH_THRESHOLD)
+}
+
+/// Generates a SHA3-256 tree hash value from a file contents, see
+/// [generate_hash_parallel].
+/// 
+/// # Arguments
+/// 
+/// * `path` - File path.
+/// * `threads` - Thread count, 0 to use as many threads as CPUs.
+/// * `chunk_size` - Chunk size hashed by each task.
+/// * `threshold` - Min file size hashed in parallel.
+fn generate_hash_parallel_with(path: &PathBuf, threads: usize, chunk_size: u64, threshold: u64) -> Result<[u8; HASH_SIZE]> {
+    let size = file_size(path)?;
+    if size < threshold {
+        let mut reader = BufReader::new(File::open(path)?);
+        return Ok(generate_hash(&mut reader)?);
+    }
+
+    // hash every chunk
+    let chunk_count = size.div_ceil(chunk_size);
+    let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+    let chunk_hashes = pool.install(|| {
+        (0..chunk_count).into_par_iter().map(|index| -> Result<[u8; HASH_SIZE]> {
+            let mut reader = BufReader::new(File::open(path)?);
+            reader.seek(SeekFrom::Start(index * chunk_size))?;
+            Ok(generate_hash(&mut reader.take(chunk_size))?)
+        }).collect::<Result<Vec<_>>>()
+    })?;
+
+    // combine the chunk hashes
+    let mut hasher = Sha3_256::new();
+    hasher.update(size.to_be_bytes());
+    for chunk_hash in chunk_hashes {
+        hasher.update(chunk_hash);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Same as [file_size] but it runs on a blocking thread so it doesn't
+/// stall the async executor.
+/// 
+/// # Arguments
+/// 
+/// * `path` - File path.
+#[cfg(feature = "tokio")]
+pub async fn file_size_async(path: PathBuf) -> Result<u64> {
+    match tokio::task::spawn_blocking(move || file_size(&path)).await {
+        Ok(v) => v,
+        Err(e) => bail!("file size task failed: {}", e)
+    }
+}
+
+/// Generates a SHA3-256 hash value from a file contents on a blocking
+/// thread so it doesn't stall the async executor. The digest is the same
+/// as [generate_hash].
+/// 
+/// # Arguments
+/// 
+/// * `path` - File path.
+#[cfg(feature = "tokio")]
+pub async fn generate_hash_async(path: PathBuf) -> Result<[u8; HASH_SIZE]> {
+    let task = tokio::task::spawn_blocking(move || -> Result<[u8; HASH_SIZE]> {
+        let mut reader = BufReader::new(File::open(&path)?);
+        Ok(generate_hash(&mut reader)?)
+    });
+    match task.await {
+        Ok(v) => v,
+        Err(e) => bail!("hash task failed: {}", e)
+    }
+}
+
+/// Generates the hash value stored by index files created before the
+/// hash algorithm was recorded. It hashes zero filled chunks as long as
+/// the reader contents, so it only changes with the contents size. It's
+/// kept to validate those index files.
+/// 
+/// # Arguments
+/// 
+/// * `reader` - Byte reader.
+pub fn generate_legacy_hash(reader: &mut impl Read) -> std::io::Result<[u8; HASH_SIZE]> {
     let mut hasher = Sha3_256::new();
 
     loop {
@@ -127,6 +380,52 @@ pub fn generate_hash(reader: &mut impl Read) -> std::io::Result<[u8; HASH_SIZE]>
     Ok(hash)
 }
 
+/// Writer adapter that hashes every byte written through it, so data
+/// can be hashed while it is written without reading it back.
+pub struct HashingWriter<W: Write> {
+    /// Wrapped writer.
+    inner: W,
+
+    /// Running hash over the written bytes.
+    hasher: Sha3_256
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Creates a new hashing writer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inner` - Writer to wrap.
+    pub fn new(inner: W) -> Self {
+        Self{
+            inner,
+            hasher: Sha3_256::new()
+        }
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the hash of every byte written so far.
+    pub fn finalize(self) -> [u8; HASH_SIZE] {
+        self.hasher.finalize().into()
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let count = self.inner.write(buf)?;
+        self.hasher.update(&buf[..count]);
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Validate a file path extension.
 /// 
 /// # Arguments
@@ -203,6 +502,70 @@ pub fn scan_path(source_path: &PathBuf, path_list: &mut Vec<PathBuf>, raw_exclud
     Ok(())
 }
 
+/// Parses a raw record index, usually coming from an user provided
+/// path parameter.
+/// 
+/// # Arguments
+/// 
+/// * `raw_index` - Raw index string.
+pub fn parse_index(raw_index: &str) -> std::result::Result<u64, ParseError> {
+    let raw = raw_index.trim();
+    match raw.parse::<u64>() {
+        Ok(v) => Ok(v),
+        Err(e) => match e.kind() {
+            IntErrorKind::PosOverflow => Err(ParseError::OutOfRange(raw.to_string())),
+            _ => {
+                // negative numbers are valid numbers but not valid indexes
+                let digits = raw.strip_prefix('-').unwrap_or("");
+                if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(ParseError::OutOfRange(raw.to_string()));
+                }
+                Err(ParseError::NotNumeric(raw.to_string()))
+            }
+        }
+    }
+}
+
+/// Copies the CSV header and only the rows matching a predicate from an
+/// input CSV file into an output CSV file, so the output can be indexed
+/// instead of the input. Returns the kept row count.
+/// 
+/// # Arguments
+/// 
+/// * `input` - Input CSV file path.
+/// * `output` - Output CSV file path.
+/// * `predicate` - Returns `true` for the rows to keep.
+pub fn prefilter_csv(input: &PathBuf, output: &PathBuf, predicate: impl Fn(&csv::StringRecord) -> bool) -> Result<u64> {
+    if is_same_file(input, output) {
+        bail!("input and output files must be different");
+    }
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_path(input)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(output)?;
+    let mut csv_writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .from_writer(BufWriter::new(file));
+
+    // copy the headers and the matching rows
+    csv_writer.write_record(csv_reader.headers()?)?;
+    let mut count = 0;
+    for item in csv_reader.records() {
+        let item = item?;
+        if predicate(&item) {
+            csv_writer.write_record(&item)?;
+            count += 1;
+        }
+    }
+    csv_writer.flush()?;
+    Ok(count)
+}
+
 #[cfg(test)]
 pub mod test_helper;
 
@@ -244,6 +607,67 @@ mod tests {
         });
     }
 
+    #[test]
+    fn is_same_file_with_aliases() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let sub = dir.path().join("sub");
+            std::fs::create_dir(&sub)?;
+            let path = sub.join("a.csv");
+            create_file_with_bytes(&path, b"id\n")?;
+
+            // test existing file aliases
+            assert!(is_same_file(&path, &path));
+            assert!(is_same_file(&path, &sub.join("..").join("sub").join("a.csv")));
+            assert!(is_same_file(&path, &sub.join(".").join("a.csv")));
+            #[cfg(unix)]
+            {
+                let link = dir.path().join("link.csv");
+                std::os::unix::fs::symlink(&path, &link)?;
+                assert!(is_same_file(&path, &link));
+            }
+
+            // test different and missing files
+            assert!(!is_same_file(&path, &sub.join("b.csv")));
+            assert!(is_same_file(&sub.join("b.csv"), &sub.join("..").join("sub").join("b.csv")));
+            Ok(())
+        });
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn file_size_async() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+            let path = dir.path().join("my_file");
+            create_file_with_bytes(&path, &[0u8; 34])?;
+            assert_eq!(file_size(&path)?, runtime.block_on(super::file_size_async(path.clone()))?);
+
+            // test without file
+            let path = dir.path().join("my_file_non_exists");
+            let expected = format!("\"{}\" is not a file", path.to_string_lossy());
+            match runtime.block_on(super::file_size_async(path)) {
+                Ok(v) => panic!("expected error but got {:?}", v),
+                Err(e) => assert_eq!(expected, e.to_string())
+            }
+            Ok(())
+        });
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn generate_hash_async() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+            let path = dir.path().join("my_file");
+            let data: Vec<u8> = (0..10000u32).map(|v| (v % 251) as u8).collect();
+            create_file_with_bytes(&path, &data)?;
+            let expected = generate_hash(&mut &data[..])?;
+            assert_eq!(expected, runtime.block_on(super::generate_hash_async(path.clone()))?);
+            assert!(runtime.block_on(super::generate_hash_async(dir.path().join("my_file_non_exists"))).is_err());
+            Ok(())
+        });
+    }
+
     #[test]
     fn fill_file_non_exists() {
         with_tmpdir(&|dir| -> Result<()> {
@@ -391,6 +815,42 @@ mod tests {
         });
     }
 
+    #[test]
+    fn fill_file_with_smaller() {
+        with_tmpdir(&|dir| -> Result<()> {
+            // create test file
+            let path = dir.path().join("my_file");
+            let buf: [u8; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            create_file_with_bytes(&path, &buf)?;
+
+            // fill file
+            assert_eq!(FillAction::Fill, fill_file_with(&path, 15, false, 0xAB)?);
+
+            // compare
+            let expected = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0xAB, 0xAB, 0xAB, 0xAB, 0xAB].to_vec();
+            assert_eq!(expected, std::fs::read(&path)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn fill_file_with_truncate() {
+        with_tmpdir(&|dir| -> Result<()> {
+            // create test file
+            let path = dir.path().join("my_file");
+            let buf: [u8; 15] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+            create_file_with_bytes(&path, &buf)?;
+
+            // fill file
+            assert_eq!(FillAction::Truncated, fill_file_with(&path, 5000, true, 7)?);
+
+            // compare
+            let expected = [7u8; 5000].to_vec();
+            assert_eq!(expected, std::fs::read(&path)?);
+            Ok(())
+        });
+    }
+
     #[test]
     fn gen_hash() {
         with_tmpdir(&|dir| -> Result<()> {
@@ -398,9 +858,9 @@ mod tests {
             let buf: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
             create_file_with_bytes(&path, buf)?;
             
-            let expected: &[u8] = &[12, 213, 40, 91, 168, 82, 79, 228, 42, 200,
-              240, 7, 109, 233, 19, 93, 5, 97, 50, 169, 153, 98, 19, 174, 28,
-              15, 20, 32, 201, 8, 65, 139];
+            let expected: &[u8] = &[50, 188, 209, 172, 34, 68, 138, 81, 200, 216,
+              146, 196, 224, 166, 47, 223, 67, 157, 37, 174, 114, 238, 126, 236,
+              30, 59, 36, 241, 141, 110, 80, 223];
             let file = File::open(&path)?;
             let mut reader = BufReader::new(file);
             let value = generate_hash(&mut reader)?;
@@ -411,5 +871,236 @@ mod tests {
         });
     }
 
+    #[test]
+    fn gen_hash_with_progress() -> Result<()> {
+        let buf: Vec<u8> = (0..10000u32).map(|v| (v % 251) as u8).collect();
+        let mut calls = Vec::new();
+        let value = generate_hash_with_progress(&mut &buf[..], &mut |v| calls.push(v))?;
+        assert_eq!(vec![4096, 8192, 10000], calls);
+        assert_eq!(Some(&10000), calls.last());
+        assert_eq!(generate_hash(&mut &buf[..])?, value);
+        assert_eq!(&generate_hash_with(&mut &buf[..], HashAlgorithm::Sha3_256)?[..], &value[..]);
+
+        // empty readers don't report progress
+        let mut calls = Vec::new();
+        generate_hash_with_progress(&mut &[][..], &mut |v| calls.push(v))?;
+        assert!(calls.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn gen_hash_buffered() -> Result<()> {
+        let buf: Vec<u8> = (0..10000u32).map(|v| (v % 251) as u8).collect();
+        let expected = generate_hash(&mut &buf[..])?;
+        for buf_size in [1, 7, 1000, 4096, 10000, 20000] {
+            assert_eq!(expected, generate_hash_buffered(&mut &buf[..], buf_size)?);
+        }
+        assert_eq!(generate_hash(&mut &[][..])?, generate_hash_buffered(&mut &[][..], 7)?);
+        match generate_hash_buffered(&mut &buf[..], 0) {
+            Ok(v) => panic!("expected error but got {:?}", v),
+            Err(e) => assert_eq!("hash buffer size can't be zero", e.to_string())
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn gen_hash_with() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let path = dir.path().join("my_file");
+            let buf: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            create_file_with_bytes(&path, buf)?;
+
+            let cases: [(HashAlgorithm, [u8; HASH_SIZE]); 3] = [
+                (HashAlgorithm::Sha3_256, [50, 188, 209, 172, 34, 68, 138, 81,
+                  200, 216, 146, 196, 224, 166, 47, 223, 67, 157, 37, 174, 114,
+                  238, 126, 236, 30, 59, 36, 241, 141, 110, 80, 223]),
+                (HashAlgorithm::Sha256, [200, 72, 225, 1, 63, 159, 4, 169, 214,
+                  63, 164, 60, 231, 253, 74, 240, 53, 21, 44, 124, 102, 154, 74,
+                  64, 75, 103, 16, 124, 238, 95, 46, 78]),
+                (HashAlgorithm::Blake3, [165, 131, 141, 69, 2, 69, 39, 236, 196,
+                  244, 180, 213, 147, 124, 222, 39, 68, 223, 54, 176, 242, 97, 200,
+                  101, 204, 79, 21, 233, 56, 51, 1, 199])
+            ];
+            for (algorithm, expected) in cases {
+                let mut reader = BufReader::new(File::open(&path)?);
+                let value = generate_hash_with(&mut reader, algorithm)?;
+                assert_eq!(&expected[..], &value[..], "{:?}", algorithm);
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn gen_hash_parallel() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let path = dir.path().join("my_file");
+            let buf: Vec<u8> = (0..10000u32).map(|v| (v % 251) as u8).collect();
+            create_file_with_bytes(&path, &buf)?;
+
+            // the digest is stable across thread counts
+            let expected = generate_hash_parallel_with(&path, 1, 1024, 4096)?;
+            for threads in [0, 2, 3, 8] {
+                assert_eq!(expected, generate_hash_parallel_with(&path, threads, 1024, 4096)?, "{} threads", threads);
+            }
+
+            // the digest is the tree hash of the chunk hashes
+            let mut hasher = Sha3_256::new();
+            hasher.update(10000u64.to_be_bytes());
+            for chunk in buf.chunks(1024) {
+                hasher.update(generate_hash(&mut &chunk[..])?);
+            }
+            let tree_hash: [u8; HASH_SIZE] = hasher.finalize().into();
+            assert_eq!(tree_hash, expected);
+            assert_ne!(generate_hash(&mut &buf[..])?, expected);
+
+            // the digest depends on the chunk size
+            assert_ne!(expected, generate_hash_parallel_with(&path, 2, 2048, 4096)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn gen_hash_parallel_below_threshold() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let path = dir.path().join("my_file");
+            let buf: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            create_file_with_bytes(&path, buf)?;
+
+            // small files are hashed serially
+            let expected = generate_hash(&mut &buf[..])?;
+            assert_eq!(expected, generate_hash_parallel(&path, 4)?);
+            assert_eq!(expected, generate_hash_parallel_with(&path, 4, 4, 11)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn gen_legacy_hash() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let path = dir.path().join("my_file");
+            let buf: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            create_file_with_bytes(&path, buf)?;
+
+            let expected: &[u8] = &[12, 213, 40, 91, 168, 82, 79, 228, 42, 200,
+              240, 7, 109, 233, 19, 93, 5, 97, 50, 169, 153, 98, 19, 174, 28,
+              15, 20, 32, 201, 8, 65, 139];
+            let mut reader = BufReader::new(File::open(&path)?);
+            let value = generate_legacy_hash(&mut reader)?;
+            assert_eq!(expected, value);
+
+            Ok(())
+        });
+    }
+
+
+    #[test]
+    fn hashing_writer() -> Result<()> {
+        let buf: Vec<u8> = (0..10000u32).map(|v| (v % 251) as u8).collect();
+        let mut writer = HashingWriter::new(Vec::new());
+        for chunk in buf.chunks(777) {
+            writer.write_all(chunk)?;
+        }
+        writer.flush()?;
+        assert_eq!(&buf, writer.get_ref());
 
+        // test
+        let expected: [u8; HASH_SIZE] = Sha3_256::digest(&buf).into();
+        assert_eq!(expected, writer.finalize());
+        Ok(())
+    }
+
+    #[test]
+    fn hashing_writer_empty() {
+        let expected: [u8; HASH_SIZE] = Sha3_256::digest([]).into();
+        assert_eq!(expected, HashingWriter::new(Vec::new()).finalize());
+    }
+
+    #[test]
+    fn prefilter_csv_drops_empty_keys() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let input = dir.path().join("input.csv");
+            let output = dir.path().join("output.csv");
+            create_file_with_bytes(&input, b"\
+                id,name,price\n\
+                1,fork,12.34\n\
+                ,keyboard,23.45\n\
+                3,\"mouse, wireless\",98.76\n\
+                \"  \",book,1\n\
+            ")?;
+
+            // test
+            let count = prefilter_csv(&input, &output, |row| {
+                !row.get(0).unwrap_or("").trim().is_empty()
+            })?;
+            assert_eq!(2, count);
+            let expected = "\
+                id,name,price\n\
+                1,fork,12.34\n\
+                3,\"mouse, wireless\",98.76\n\
+            ";
+            assert_eq!(expected, std::fs::read_to_string(&output)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn prefilter_csv_same_file() {
+        with_tmpdir(&|dir| -> Result<()> {
+            let input = dir.path().join("input.csv");
+            create_file_with_bytes(&input, b"id\n1\n")?;
+            let alias = dir.path().join(".").join("input.csv");
+            for output in [&input, &alias] {
+                match prefilter_csv(&input, output, |_| true) {
+                    Ok(v) => panic!("expected error but got {:?}", v),
+                    Err(e) => assert_eq!("input and output files must be different", e.to_string())
+                }
+            }
+            assert_eq!(b"id\n1\n".to_vec(), std::fs::read(&input)?);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn parse_index_valid() {
+        assert_eq!(0, parse_index("0").unwrap());
+        assert_eq!(123, parse_index("123").unwrap());
+        assert_eq!(u64::MAX, parse_index(" 18446744073709551615 ").unwrap());
+    }
+
+    #[test]
+    fn parse_index_non_numeric() {
+        match parse_index("abc") {
+            Err(ParseError::NotNumeric(v)) => assert_eq!("abc", v),
+            v => panic!("expected ParseError::NotNumeric but got {:?}", v)
+        }
+        match parse_index("12a") {
+            Err(ParseError::NotNumeric(v)) => assert_eq!("12a", v),
+            v => panic!("expected ParseError::NotNumeric but got {:?}", v)
+        }
+    }
+
+    #[test]
+    fn parse_index_empty() {
+        match parse_index("") {
+            Err(ParseError::NotNumeric(v)) => assert_eq!("", v),
+            v => panic!("expected ParseError::NotNumeric but got {:?}", v)
+        }
+        match parse_index("-") {
+            Err(ParseError::NotNumeric(v)) => assert_eq!("-", v),
+            v => panic!("expected ParseError::NotNumeric but got {:?}", v)
+        }
+    }
+
+    #[test]
+    fn parse_index_out_of_range() {
+        match parse_index("18446744073709551616") {
+            Err(ParseError::OutOfRange(v)) => assert_eq!("18446744073709551616", v),
+            v => panic!("expected ParseError::OutOfRange but got {:?}", v)
+        }
+        match parse_index("-5") {
+            Err(ParseError::OutOfRange(v)) => assert_eq!("-5", v),
+            v => panic!("expected ParseError::OutOfRange but got {:?}", v)
+        }
+    }
 }
@@ -27,6 +27,7 @@ impl_byte_sized!(i16, i16::BITS);
 impl_byte_sized!(i8, i8::BITS);
 impl_byte_sized!(f64, 64);
 impl_byte_sized!(f32, 32);
+impl_byte_sized!(char, 32);
 
 pub trait FromByteSlice: ByteSized {
     /// Creates a value from its representation as bytes from a byte buffer.
@@ -74,6 +75,30 @@ impl ReadFrom for bool {
     }
 }
 
+impl FromByteSlice for char {
+    fn from_byte_slice(buf: &[u8]) -> Result<Self> {
+        // validate value size
+        if buf.len() != Self::BYTES {
+            bail!(ParseError::InvalidSize);
+        }
+
+        // the value must be a valid unicode scalar value
+        match char::from_u32(u32::from_byte_slice(buf)?) {
+            Some(v) => Ok(v),
+            None => bail!(ParseError::InvalidValue)
+        }
+    }
+}
+
+impl ReadFrom for char {
+    fn read_from(reader: &mut impl Read) -> Result<Self> {
+        // read and convert bytes into the type value
+        let mut buf = [0u8; Self::BYTES];
+        reader.read_exact(&mut buf)?;
+        Self::from_byte_slice(&buf)
+    }
+}
+
 macro_rules! impl_from_byte_reader {
     ($type:ty, $fn:ident) => {
         impl FromByteSlice for $type {
@@ -152,6 +177,18 @@ impl WriteTo for bool {
     }
 }
 
+impl WriteAsBytes for char {
+    fn write_as_bytes(&self, buf: &mut [u8]) -> Result<()> {
+        (*self as u32).write_as_bytes(buf)
+    }
+}
+
+impl WriteTo for char {
+    fn write_to(&self, writer: &mut impl Write) -> Result<()> {
+        (*self as u32).write_to(writer)
+    }
+}
+
 macro_rules! impl_write_as_bytes {
     ($t:ty, $fn:ident) => {
         impl WriteAsBytes for $t {
@@ -189,6 +226,105 @@ impl_write_as_bytes!(i8, to_be_bytes);
 impl_write_as_bytes!(f64, to_be_bytes);
 impl_write_as_bytes!(f32, to_be_bytes);
 
+/// Byte order used to encode numeric values.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum Endianness {
+    /// Most significant byte first, the default encoding.
+    #[default]
+    Big,
+
+    /// Least significant byte first.
+    Little
+}
+
+pub trait ReadFromEndian: Sized {
+    /// Create an instance from a reader contents using the provided
+    /// byte order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reader` - Byte reader.
+    /// * `endianness` - Byte order.
+    fn read_from_endian(reader: &mut impl Read, endianness: Endianness) -> Result<Self>;
+}
+
+pub trait WriteToEndian {
+    /// Write instance value as bytes into a writer using the provided
+    /// byte order.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `writer` - Byte writer.
+    /// * `endianness` - Byte order.
+    fn write_to_endian(&self, writer: &mut impl Write, endianness: Endianness) -> Result<()>;
+}
+
+impl ReadFromEndian for bool {
+    fn read_from_endian(reader: &mut impl Read, _endianness: Endianness) -> Result<Self> {
+        Self::read_from(reader)
+    }
+}
+
+impl WriteToEndian for bool {
+    fn write_to_endian(&self, writer: &mut impl Write, _endianness: Endianness) -> Result<()> {
+        self.write_to(writer)
+    }
+}
+
+impl ReadFromEndian for char {
+    fn read_from_endian(reader: &mut impl Read, endianness: Endianness) -> Result<Self> {
+        // the value must be a valid unicode scalar value
+        match char::from_u32(u32::read_from_endian(reader, endianness)?) {
+            Some(v) => Ok(v),
+            None => bail!(ParseError::InvalidValue)
+        }
+    }
+}
+
+impl WriteToEndian for char {
+    fn write_to_endian(&self, writer: &mut impl Write, endianness: Endianness) -> Result<()> {
+        (*self as u32).write_to_endian(writer, endianness)
+    }
+}
+
+macro_rules! impl_endian {
+    ($type:ty) => {
+        impl ReadFromEndian for $type {
+            fn read_from_endian(reader: &mut impl Read, endianness: Endianness) -> Result<Self> {
+                // read and convert bytes into the type value
+                let mut buf = [0u8; Self::BYTES];
+                reader.read_exact(&mut buf)?;
+                Ok(match endianness {
+                    Endianness::Big => <$type>::from_be_bytes(buf),
+                    Endianness::Little => <$type>::from_le_bytes(buf)
+                })
+            }
+        }
+
+        impl WriteToEndian for $type {
+            fn write_to_endian(&self, writer: &mut impl Write, endianness: Endianness) -> Result<()> {
+                match endianness {
+                    Endianness::Big => writer.write_all(&self.to_be_bytes())?,
+                    Endianness::Little => writer.write_all(&self.to_le_bytes())?
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+// implement endian aware read and write functions on numeric types
+impl_endian!(u64);
+impl_endian!(u32);
+impl_endian!(u16);
+impl_endian!(u8);
+impl_endian!(i64);
+impl_endian!(i32);
+impl_endian!(i16);
+impl_endian!(i8);
+impl_endian!(f64);
+impl_endian!(f32);
+
 pub trait LoadFrom {
     /// Loads data into the instance from a reader.
     /// 
@@ -247,6 +383,11 @@ mod tests {
         assert_eq!(1, i8::BYTES);
     }
 
+    #[test]
+    fn char_byte_size() {
+        assert_eq!(4, char::BYTES);
+    }
+
     #[test]
     fn bool_from_byte_slice() {
         let expected = false;
@@ -1160,4 +1301,87 @@ mod tests {
             Err(e) => assert!(false, "expected {:?} but got error: {:?}", expected, e)
         };
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn char_from_byte_slice() -> Result<()> {
+        assert_eq!('a', char::from_byte_slice(&[0u8, 0u8, 0u8, 97u8])?);
+        assert_eq!('\u{e9}', char::from_byte_slice(&[0u8, 0u8, 0u8, 233u8])?);
+        assert_eq!('\u{1f980}', char::from_byte_slice(&[0u8, 1u8, 249u8, 128u8])?);
+        match char::from_byte_slice(&[0u8, 0u8, 216u8, 0u8]) {
+            Ok(v) => panic!("expected ParseError::InvalidValue but got {:?}", v),
+            Err(e) => match e.downcast() {
+                Ok(ParseError::InvalidValue) => {},
+                Ok(ex) => panic!("expected ParseError::InvalidValue but got error: {:?}", ex),
+                Err(ex) => panic!("expected ParseError::InvalidValue but got error: {:?}", ex)
+            }
+        };
+        match char::from_byte_slice(&[0u8, 97u8]) {
+            Ok(v) => panic!("expected ParseError::InvalidSize but got {:?}", v),
+            Err(e) => match e.downcast() {
+                Ok(ParseError::InvalidSize) => {},
+                Ok(ex) => panic!("expected ParseError::InvalidSize but got error: {:?}", ex),
+                Err(ex) => panic!("expected ParseError::InvalidSize but got error: {:?}", ex)
+            }
+        };
+        Ok(())
+    }
+
+    #[test]
+    fn char_read_from() -> Result<()> {
+        let mut reader = &[0u8, 1u8, 249u8, 128u8, 5u8] as &[u8];
+        assert_eq!('\u{1f980}', char::read_from(&mut reader)?);
+        assert_eq!([5u8], reader);
+        Ok(())
+    }
+
+    #[test]
+    fn char_write_as_bytes() -> Result<()> {
+        let mut buf = [0u8; 4];
+        '\u{e9}'.write_as_bytes(&mut buf)?;
+        assert_eq!([0u8, 0u8, 0u8, 233u8], buf);
+        assert!('a'.write_as_bytes(&mut [0u8; 2]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn char_on_write_to() -> Result<()> {
+        let mut buf = [0u8; 4];
+        '\u{1f980}'.write_to(&mut (&mut buf as &mut [u8]))?;
+        assert_eq!([0u8, 1u8, 249u8, 128u8], buf);
+        Ok(())
+    }
+
+    #[test]
+    fn i32_write_to_endian() -> Result<()> {
+        let mut buf = Vec::new();
+        2064390957i32.write_to_endian(&mut buf, Endianness::Big)?;
+        assert_eq!(vec![123u8, 12u8, 27u8, 45u8], buf);
+        let mut buf = Vec::new();
+        2064390957i32.write_to_endian(&mut buf, Endianness::Little)?;
+        assert_eq!(vec![45u8, 27u8, 12u8, 123u8], buf);
+        Ok(())
+    }
+
+    #[test]
+    fn i32_read_from_endian() -> Result<()> {
+        let mut reader = &[123u8, 12u8, 27u8, 45u8] as &[u8];
+        assert_eq!(2064390957i32, i32::read_from_endian(&mut reader, Endianness::Big)?);
+        let mut reader = &[45u8, 27u8, 12u8, 123u8] as &[u8];
+        assert_eq!(2064390957i32, i32::read_from_endian(&mut reader, Endianness::Little)?);
+        Ok(())
+    }
+
+    #[test]
+    fn char_endian_round_trip() -> Result<()> {
+        let mut buf = Vec::new();
+        '\u{1f980}'.write_to_endian(&mut buf, Endianness::Little)?;
+        assert_eq!(vec![128u8, 249u8, 1u8, 0u8], buf);
+        assert_eq!('\u{1f980}', char::read_from_endian(&mut (&buf as &[u8]), Endianness::Little)?);
+        Ok(())
+    }
+
+    #[test]
+    fn endianness_default() {
+        assert_eq!(Endianness::Big, Endianness::default());
+    }
+}